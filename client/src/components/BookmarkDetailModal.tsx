@@ -689,7 +689,7 @@ function FetcherRow({
                 <div key={k} className="flex gap-1.5">
                   <dt className="shrink-0 font-medium">{k}:</dt>
                   <dd className="break-all">
-                    {String(v)}
+                    {typeof v === 'object' && !Array.isArray(v) ? JSON.stringify(v) : String(v)}
                   </dd>
                 </div>
               ))}
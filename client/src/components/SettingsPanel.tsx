@@ -739,7 +739,7 @@ function RulesManager() {
 
   function ruleLabel(rule: Rule, index: number): string {
     const label = rule.comment || rule.url || rule.title || rule.query || 'Empty rule'
-    return `${index + 1}. ${label}`
+    return `${index + 1}. ${label}${rule.enabled === false ? ' (disabled)' : ''}`
   }
 
   if (loading) {
@@ -853,8 +853,12 @@ function RuleEditor({
   const [description, setDescription] = useState(rule.description ?? '')
   const [query, setQuery] = useState(rule.query ?? '')
   const [comment, setComment] = useState(rule.comment ?? '')
-  const [actionTitle, setActionTitle] = useState(rule.action.UpdateBookmark.title ?? '')
-  const [actionDesc, setActionDesc] = useState(rule.action.UpdateBookmark.description ?? '')
+  // Only UpdateBookmark is editable here; other actions are shown as-is
+  const update = typeof rule.action === 'object' && 'UpdateBookmark' in rule.action
+    ? rule.action.UpdateBookmark
+    : null
+  const [actionTitle, setActionTitle] = useState(update?.title ?? '')
+  const [actionDesc, setActionDesc] = useState(update?.description ?? '')
 
 
   function saveField(patch: Partial<Rule>) {
@@ -865,7 +869,7 @@ function RuleEditor({
     onUpdate({
       action: {
         UpdateBookmark: {
-          ...rule.action.UpdateBookmark,
+          ...update,
           [field]: value || undefined,
         },
       },
@@ -873,7 +877,7 @@ function RuleEditor({
   }
 
   const conditionTags = useMemo(() => rule.tags ?? [], [rule.tags])
-  const actionTags = useMemo(() => rule.action.UpdateBookmark.tags ?? [], [rule.action.UpdateBookmark.tags])
+  const actionTags = useMemo(() => update?.tags ?? [], [update?.tags])
 
   // Visible tags for autocomplete (exclude hidden + already-used)
   const hiddenSet = useMemo(() => new Set(hiddenTags), [hiddenTags])
@@ -962,6 +966,12 @@ function RuleEditor({
         <div className="mb-2.5">
           <span className="rounded bg-surface-active px-1.5 py-0.5 text-[10px] font-bold uppercase tracking-widest text-text-muted">then</span>
         </div>
+        {update === null ? (
+          <div className="flex flex-col gap-1">
+            <code className="whitespace-pre-wrap break-all text-xs text-text-muted">{JSON.stringify(rule.action)}</code>
+            <span className="text-[11px] text-text-dim">Edit this action in rules.yaml</span>
+          </div>
+        ) : (
         <div className="flex flex-col gap-2">
           <div className="flex items-start gap-2">
             <span className={labelClass}>Set title</span>
@@ -991,7 +1001,7 @@ function RuleEditor({
                 onChange={(tags) => onUpdate({
                   action: {
                     UpdateBookmark: {
-                      ...rule.action.UpdateBookmark,
+                      ...update,
                       tags: tags.length > 0 ? tags : undefined,
                     },
                   },
@@ -1002,6 +1012,7 @@ function RuleEditor({
             </div>
           </div>
         </div>
+        )}
       </div>
     </div>
   )
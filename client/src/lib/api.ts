@@ -87,15 +87,31 @@ export interface Config {
 }
 
 export interface Rule {
+  id?: number
+  enabled?: boolean
   url?: string
   title?: string
   description?: string
   tags?: string[]
   query?: string
   comment?: string
-  action: { UpdateBookmark: { title?: string; description?: string; tags?: string[] } }
+  when?: RuleTrigger | RuleTrigger[]
+  stop?: boolean
+  priority?: number
+  action: RuleAction
 }
 
+export type RuleTrigger = 'on_create' | 'on_update' | 'after_metadata' | 'manual'
+
+export type RuleAction =
+  | { UpdateBookmark: { title?: string; description?: string; tags?: string[] } }
+  | { RemoveTags: { tags: string[] } }
+  | { RewriteUrl: { pattern: string; replacement: string } }
+  | { ReplaceDescription: { pattern?: string; replacement: string } }
+  | 'Delete'
+  | 'SkipMetadata'
+  | 'ForceHeadless'
+
 export interface TaskDump {
   id: string
   task: {
@@ -108,9 +124,17 @@ export interface TaskDump {
   status: 'Interrupted' | 'Pending' | 'InProgress' | 'Done' | { Error: string }
 }
 
+export interface HostStatus {
+  host: string
+  active: number
+  waiting: number
+  wait_ms: number
+}
+
 export interface TaskQueue {
   queue: TaskDump[]
   now: number
+  hosts: HostStatus[]
 }
 
 export interface SemanticStatus {
@@ -403,6 +427,8 @@ export interface FetcherFields {
   icon_url: string | null
   has_image_bytes: boolean
   has_icon_bytes: boolean
+  fields?: Record<string, string>
+  tags?: string[]
 }
 
 export interface FieldDecision {
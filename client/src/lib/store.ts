@@ -276,7 +276,7 @@ export const useStore = create<AppState>()((set, get) => ({
   setWorkspacesAvailable: (workspacesAvailable) => set({ workspacesAvailable }),
 
   // Task queue
-  taskQueue: { queue: [], now: 0 },
+  taskQueue: { queue: [], now: 0, hosts: [] },
   setTaskQueue: (taskQueue) => set({ taskQueue }),
 
   // Semantic
@@ -1,7 +1,7 @@
 use serde::{Deserialize, Serialize};
 use std::sync::{Arc, RwLock};
 
-use crate::{bookmarks, config::{Config, RulesConfig}, metadata::{MetaOptions, MetadataReport}};
+use crate::{bookmarks, config::{Config, RulesConfig}, metadata::{reports::StoredReport, MetaOptions, MetadataReport}, saved_searches::{SavedSearch, SavedSearchStore}, search_query};
 
 use super::errors::AppError;
 
@@ -33,10 +33,107 @@ pub trait AppBackend: Send + Sync {
         query: bookmarks::SearchQuery,
     ) -> anyhow::Result<Vec<bookmarks::Bookmark>, AppError>;
 
+    /// Sorted, paged search with the total match count.
+    fn search_page(
+        &self,
+        query: bookmarks::SearchQuery,
+    ) -> anyhow::Result<bookmarks::SearchPage, AppError> {
+        let matches = self.search(query.without_paging())?;
+        Ok(bookmarks::paginate(matches, &query))
+    }
+
+    /// Explain how `query` parses and, given `id`, how it matches that bookmark.
+    fn explain_query(
+        &self,
+        query: &str,
+        id: Option<u64>,
+    ) -> anyhow::Result<search_query::QueryExplanation, AppError> {
+        let bookmark = match id {
+            Some(id) => {
+                let found = self.search(bookmarks::SearchQuery {
+                    id: Some(id),
+                    ..Default::default()
+                })?;
+                let bookmark = found
+                    .into_iter()
+                    .next()
+                    .ok_or_else(|| anyhow::anyhow!("bookmark {} not found", id))?;
+                Some(bookmark)
+            }
+            None => None,
+        };
+
+        let saved = self
+            .saved_searches()
+            .map(|store| store.read().unwrap().map())
+            .unwrap_or_default();
+        Ok(search_query::explain(query, &saved, &self.metadata_sources(), bookmark.as_ref())?)
+    }
+
+    /// The saved searches `@name` references resolve against. `None` when
+    /// another process resolves them, like the daemon for a remote backend.
+    fn saved_searches(&self) -> Option<Arc<RwLock<SavedSearchStore>>> {
+        None
+    }
+
+    /// Which fetcher supplied each bookmark field, for `source:` terms. Empty
+    /// when another process keeps the metadata reports.
+    fn metadata_sources(&self) -> Arc<search_query::MetadataSources> {
+        Default::default()
+    }
+
     fn config(&self) -> anyhow::Result<Arc<RwLock<Config>>, AppError>;
     #[allow(dead_code)]
     fn update_config(&self, config: Config) -> anyhow::Result<(), AppError>;
     fn rules(&self) -> anyhow::Result<Arc<RwLock<RulesConfig>>, AppError>;
+
+    /// Run the rules against existing bookmarks. With `dry_run` only the
+    /// changes are reported.
+    fn apply_rules_to_bookmarks(&self, _opts: RuleApplyOpts) -> anyhow::Result<RuleApplyReport, AppError> {
+        Err(AppError::Other(anyhow::anyhow!("applying rules is not supported by this backend")))
+    }
+
+    /// Revert the last `apply_rules_to_bookmarks`, returning how many
+    /// bookmarks were restored.
+    fn undo_rules_apply(&self) -> anyhow::Result<usize, AppError> {
+        Err(AppError::Other(anyhow::anyhow!("undoing rules is not supported by this backend")))
+    }
+
+    /// The stored metadata reports of a bookmark, newest first.
+    fn metadata_reports(&self, _id: u64) -> anyhow::Result<Vec<StoredReport>, AppError> {
+        Err(AppError::Other(anyhow::anyhow!("metadata reports are not supported by this backend")))
+    }
+
+    fn list_saved_searches(&self) -> anyhow::Result<Vec<SavedSearch>, AppError> {
+        Err(AppError::Other(anyhow::anyhow!("saved searches are not supported by this backend")))
+    }
+
+    fn create_saved_search(
+        &self,
+        _name: String,
+        _query: String,
+        _description: Option<String>,
+    ) -> anyhow::Result<SavedSearch, AppError> {
+        Err(AppError::Other(anyhow::anyhow!("saved searches are not supported by this backend")))
+    }
+
+    /// Rename a saved search or change its query or description. Fails when
+    /// a rule or workspace query would stop resolving.
+    fn update_saved_search(
+        &self,
+        _name: &str,
+        _new_name: Option<String>,
+        _query: Option<String>,
+        _description: Option<String>,
+    ) -> anyhow::Result<SavedSearch, AppError> {
+        Err(AppError::Other(anyhow::anyhow!("saved searches are not supported by this backend")))
+    }
+
+    /// Fails while a rule, workspace or other saved search references it.
+    fn delete_saved_search(&self, _name: &str) -> anyhow::Result<(), AppError> {
+        Err(AppError::Other(anyhow::anyhow!("saved searches are not supported by this backend")))
+    }
+
     fn bookmark_version(&self) -> u64;
 }
 
@@ -62,3 +159,32 @@ pub struct FetchMetadataOpts {
     #[serde(default)]
     pub force_overwrite: bool,
 }
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RuleApplyOpts {
+    /// Id of the one rule to run; all rules when unset.
+    #[serde(default)]
+    pub rule: Option<u64>,
+    /// Only bookmarks matching this query.
+    #[serde(default)]
+    pub query: Option<String>,
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+/// A bookmark the rules changed, or would change.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RuleChange {
+    pub before: bookmarks::Bookmark,
+    /// `None` when a rule deletes the bookmark.
+    pub after: Option<bookmarks::Bookmark>,
+    /// Ids of the rules that matched.
+    pub rules: Vec<u64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RuleApplyReport {
+    pub changes: Vec<RuleChange>,
+    /// False for dry runs.
+    pub applied: bool,
+}
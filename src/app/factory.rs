@@ -1,6 +1,7 @@
 use crate::{
     app::{backend::AppBackend, local::AppLocal, remote::AppRemote, service::AppService},
     config::{Config, RulesConfig},
+    saved_searches::SavedSearchStore,
     semantic::SemanticSearchService,
     storage,
 };
@@ -47,9 +48,11 @@ impl AppFactory {
         rules_config: Arc<RwLock<RulesConfig>>,
     ) -> Result<Box<dyn AppBackend>> {
         let storage_mgr = storage::BackendLocal::new(&paths.uploads_path)?;
+        let saved_searches = SavedSearchStore::load(&paths.base_path)?;
         Ok(Box::new(AppLocal::new(
             config,
             rules_config,
+            saved_searches,
             &paths.bookmarks_path,
             storage_mgr,
         )))
@@ -59,9 +62,10 @@ impl AppFactory {
     pub fn create_local_app(paths: &AppPaths) -> Result<AppLocal> {
         let config = Arc::new(RwLock::new(Config::load_with(&paths.base_path)?));
         let rules_config = Arc::new(RwLock::new(RulesConfig::load_with(&paths.base_path)?));
+        let saved_searches = SavedSearchStore::load(&paths.base_path)?;
         let storage = storage::BackendLocal::new(&paths.uploads_path)?;
 
-        Ok(AppLocal::new(config, rules_config, &paths.bookmarks_path, storage))
+        Ok(AppLocal::new(config, rules_config, saved_searches, &paths.bookmarks_path, storage))
     }
 
     /// Get application paths with validation
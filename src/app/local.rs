@@ -3,15 +3,17 @@ use crate::{
     config::{Config, ImageConfig, RulesConfig},
     eid::Eid,
     images,
-    metadata::{fetch_meta, Metadata, MetadataReport},
-    rules::{self, Rule},
-    storage::{self, BackendLocal},
+    metadata::{fetch_meta, reports::{ReportStore, StoredReport}, MetaOptions, Metadata, MetadataReport},
+    rules::{self, Action, FetchDirectives, Rule, Trigger},
+    saved_searches::{self, SavedSearch, SavedSearchStore},
+    search_query::{MetadataSources, SavedSearches},
+    storage::{self, BackendLocal, StorageManager},
 };
 
 use super::task_runner::{self, Status, Task};
-use anyhow::anyhow;
+use anyhow::{anyhow, Context};
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     sync::{mpsc, Arc, RwLock},
 };
 
@@ -27,6 +29,48 @@ pub struct AppLocal {
 
     config: Arc<RwLock<Config>>,
     rules_config: Arc<RwLock<RulesConfig>>,
+    reports: Arc<ReportStore>,
+    saved_searches: Arc<RwLock<SavedSearchStore>>,
+}
+
+/// What the matching rules do to a bookmark.
+#[derive(Debug, Clone)]
+pub struct RulesOutcome {
+    /// `None` when a `Delete` rule matched.
+    pub update: Option<bookmarks::BookmarkUpdate>,
+    /// Indices of the matching rules, in the order they ran.
+    pub matched: Vec<usize>,
+}
+
+/// The changes of the last `bb rule apply`, kept next to `config.yaml` until
+/// undone.
+const RULES_UNDO_FILE: &str = "rules-undo.json";
+
+impl RulesOutcome {
+    /// `bmark` as it looks once the outcome is written, `None` if deleted.
+    pub fn preview(&self, bmark: &bookmarks::Bookmark) -> Option<bookmarks::Bookmark> {
+        let update = self.update.as_ref()?;
+        let mut after = bmark.clone();
+        if let Some(title) = &update.title {
+            after.title = title.clone();
+        }
+        if let Some(description) = &update.description {
+            after.description = description.clone();
+        }
+        if let Some(tags) = &update.tags {
+            let mut seen = std::collections::HashSet::new();
+            after.tags = tags.iter().filter(|tag| seen.insert(*tag)).cloned().collect();
+        }
+        if let Some(url) = &update.url {
+            after.url = url.clone();
+        }
+        Some(after)
+    }
+}
+
+// Bookmark's PartialEq only compares ids
+fn same_fields(a: &bookmarks::Bookmark, b: &bookmarks::Bookmark) -> bool {
+    a.title == b.title && a.description == b.description && a.tags == b.tags && a.url == b.url
 }
 
 impl AppLocal {
@@ -38,6 +82,8 @@ impl AppLocal {
             let storage_mgr = self.storage_mgr.clone();
             let config = self.config.clone();
             let rules_config = self.rules_config.clone();
+            let reports = self.reports.clone();
+            let saved_searches = self.saved_searches.clone();
 
             let mut queue_dump = task_runner::read_queue_dump();
             let task_list = queue_dump.queue.clone();
@@ -63,7 +109,7 @@ impl AppLocal {
             });
 
             move || {
-                task_runner::start_queue(task_rx, bmark_mgr, storage_mgr, config, rules_config);
+                task_runner::start_queue(task_rx, bmark_mgr, storage_mgr, config, rules_config, reports, saved_searches);
             }
         });
 
@@ -71,13 +117,15 @@ impl AppLocal {
         self.task_tx = Some(Arc::new(task_tx));
     }
 
-    pub fn new(config: Arc<RwLock<Config>>, rules_config: Arc<RwLock<RulesConfig>>, path: &str, storage_mgr: BackendLocal) -> Self {
+    pub fn new(config: Arc<RwLock<Config>>, rules_config: Arc<RwLock<RulesConfig>>, saved_searches: SavedSearchStore, path: &str, storage_mgr: BackendLocal) -> Self {
         let bmark_mgr = Arc::new(bookmarks::BackendCsv::load(path).unwrap());
         let storage_mgr = Arc::new(storage_mgr);
 
         if let Err(e) = bmark_mgr.save() {
             log::warn!("Failed to re-save bookmarks on load: {e}");
         }
+        let reports = Arc::new(ReportStore::load(config.read().unwrap().base_path()));
+        let saved_searches = Arc::new(RwLock::new(saved_searches));
 
         Self {
             bmark_mgr,
@@ -87,12 +135,19 @@ impl AppLocal {
             task_queue_handle: None,
             config,
             rules_config,
+            reports,
+            saved_searches,
         }
     }
 }
 
 impl AppBackend for AppLocal {
-    fn update_config(&self, config: Config) -> anyhow::Result<(), AppError> {
+    fn update_config(&self, mut config: Config) -> anyhow::Result<(), AppError> {
+        // base_path and the paths derived from it aren't part of the payload
+        let base_path = self.config.read().unwrap().base_path().to_string();
+        config.set_base_path(&base_path);
+        // plugins run local commands, so only config.yaml can change them
+        config.scrape.plugins = self.config.read().unwrap().scrape.plugins.clone();
         *self.config.write().unwrap() = config;
         self.config().write().unwrap().save()
             .map_err(|e| AppError::Other(e))?;
@@ -115,12 +170,21 @@ impl AppBackend for AppLocal {
 
         let bmark = bmarks.first().ok_or(anyhow!("not found"))?;
 
-        let report = if opts.async_meta {
+        let mut meta_opts = opts.meta_opts.clone();
+        let fetch = {
+            let rules_guard = self.rules_config.read().unwrap();
+            Self::apply_fetch_rules(bmark, rules_guard.rules(), &self.saved_map(), &mut meta_opts)
+        };
+
+        let report = if !fetch {
+            log::info!("metadata fetch for bookmark {id} skipped by rule");
+            None
+        } else if opts.async_meta {
             self.schedule_fetch_and_update_metadata(
                 bmark,
                 FetchMetadataOpts {
                     no_https_upgrade: true,
-                    meta_opts: opts.meta_opts.clone(),
+                    meta_opts,
                     force_overwrite: true,
                 },
             );
@@ -131,10 +195,11 @@ impl AppBackend for AppLocal {
                 &bmark.url,
                 FetchMetadataOpts {
                     no_https_upgrade: true,
-                    meta_opts: opts.meta_opts.clone(),
-                    ..Default::default()
+                    meta_opts,
+                    force_overwrite: true,
                 },
             )?;
+            Self::record_report(&self.reports, &self.config, bmark.id, &bmark.url, &report);
 
             let img_config = &self.config.read().unwrap().images;
             Self::merge_metadata(
@@ -148,7 +213,7 @@ impl AppBackend for AppLocal {
 
             // apply rules
             let rules_guard = self.rules_config.read().unwrap();
-            Self::apply_rules(bmark.id, self.bmark_mgr.clone(), rules_guard.rules())?;
+            Self::apply_rules(bmark.id, self.bmark_mgr.clone(), rules_guard.rules(), &self.saved_map(), &[Trigger::AfterMetadata])?;
 
             Some(report)
         };
@@ -184,9 +249,40 @@ impl AppBackend for AppLocal {
         // create empty bookmark
         let bmark = self.bmark_mgr.create(bmark_create)?;
 
+        // rules may skip the fetch or force headless for it
+        let meta_opts = opts.meta_opts.and_then(|mut meta_opts| {
+            if opts.skip_rules {
+                return Some(meta_opts);
+            }
+            let rules_guard = self.rules_config.read().unwrap();
+            let fetch = Self::apply_fetch_rules(&bmark, rules_guard.rules(), &self.saved_map(), &mut meta_opts);
+            if !fetch {
+                log::info!("metadata fetch for bookmark {} skipped by rule", bmark.id);
+            }
+            fetch.then_some(meta_opts)
+        });
+
         // add metadata
-        if let Some(meta_opts) = opts.meta_opts {
+        if let Some(meta_opts) = meta_opts {
             if opts.async_meta {
+                // Rules that also run after_metadata wait for the fetch, so
+                // each rule runs once per add. Applied before scheduling so
+                // they can't race the fetch.
+                let mut bmark = bmark;
+                if !opts.skip_rules {
+                    let rules_guard = self.rules_config.read().unwrap();
+                    let on_create: Vec<Rule> = rules_guard
+                        .rules()
+                        .iter()
+                        .filter(|rule| !rule.runs_on(Trigger::AfterMetadata))
+                        .cloned()
+                        .collect();
+                    if !on_create.is_empty() {
+                        bmark = Self::apply_rules(bmark.id, self.bmark_mgr.clone(), &on_create, &self.saved_map(), &[Trigger::OnCreate])?
+                            .ok_or_else(|| anyhow!("bookmark for '{}' was deleted by a rule", url))?;
+                    }
+                }
+
                 self.schedule_fetch_and_update_metadata(
                     &bmark,
                     FetchMetadataOpts {
@@ -195,6 +291,8 @@ impl AppBackend for AppLocal {
                         ..Default::default()
                     },
                 );
+                Self::schedule_tags_cache_reval(self.bmark_mgr.clone(), self.tags_cache.clone());
+                return Ok((bmark, None));
             } else {
                 // attempt to fetch and merge metadata
                 let (meta, report) = Self::fetch_metadata(
@@ -205,6 +303,7 @@ impl AppBackend for AppLocal {
                         ..Default::default()
                     },
                 )?;
+                Self::record_report(&self.reports, &self.config, bmark.id, &url, &report);
 
                 let img_config = &self.config.read().unwrap().images;
                 let with_meta = Self::merge_metadata(
@@ -219,7 +318,9 @@ impl AppBackend for AppLocal {
                 // apply rules
                 if !opts.skip_rules {
                     let rules_guard = self.rules_config.read().unwrap();
-                    let with_rules = Self::apply_rules(bmark.id, self.bmark_mgr.clone(), rules_guard.rules())?;
+                    let triggers = [Trigger::OnCreate, Trigger::AfterMetadata];
+                    let with_rules = Self::apply_rules(bmark.id, self.bmark_mgr.clone(), rules_guard.rules(), &self.saved_map(), &triggers)?
+                        .ok_or_else(|| anyhow!("bookmark for '{}' was deleted by a rule", url))?;
                     return Ok((with_meta.map(|_| with_rules)?, Some(report)));
                 }
 
@@ -228,7 +329,9 @@ impl AppBackend for AppLocal {
         } else if !opts.skip_rules {
             // if no metadata apply Rules.
             let rules_guard = self.rules_config.read().unwrap();
-            return Ok((Self::apply_rules(bmark.id, self.bmark_mgr.clone(), rules_guard.rules())?, None));
+            let with_rules = Self::apply_rules(bmark.id, self.bmark_mgr.clone(), rules_guard.rules(), &self.saved_map(), &[Trigger::OnCreate])?
+                .ok_or_else(|| anyhow!("bookmark for '{}' was deleted by a rule", url))?;
+            return Ok((with_rules, None));
         }
 
         Self::schedule_tags_cache_reval(self.bmark_mgr.clone(), self.tags_cache.clone());
@@ -264,6 +367,14 @@ impl AppBackend for AppLocal {
 
         let bmark = self.bmark_mgr.update(id, bmark_update)?;
 
+        let rules_guard = self.rules_config.read().unwrap();
+        let bmark = if rules_guard.rules().iter().any(|rule| rule.runs_on(Trigger::OnUpdate)) {
+            Self::apply_rules(id, self.bmark_mgr.clone(), rules_guard.rules(), &self.saved_map(), &[Trigger::OnUpdate])?
+                .ok_or_else(|| anyhow!("bookmark {id} was deleted by a rule"))?
+        } else {
+            bmark
+        };
+
         Self::schedule_tags_cache_reval(self.bmark_mgr.clone(), self.tags_cache.clone());
 
         Ok(bmark)
@@ -271,6 +382,7 @@ impl AppBackend for AppLocal {
 
     fn delete(&self, id: u64) -> anyhow::Result<(), AppError> {
         self.bmark_mgr.delete(id)?;
+        self.forget_reports(&[id]);
 
         Self::schedule_tags_cache_reval(self.bmark_mgr.clone(), self.tags_cache.clone());
 
@@ -278,7 +390,10 @@ impl AppBackend for AppLocal {
     }
 
     fn search_delete(&self, query: bookmarks::SearchQuery) -> anyhow::Result<usize, AppError> {
+        let query = self.resolving(query);
+        let ids: Vec<u64> = self.bmark_mgr.search(query.clone())?.iter().map(|b| b.id).collect();
         let search_delete = self.bmark_mgr.search_delete(query)?;
+        self.forget_reports(&ids);
         Self::schedule_tags_cache_reval(self.bmark_mgr.clone(), self.tags_cache.clone());
         Ok(search_delete)
     }
@@ -288,8 +403,23 @@ impl AppBackend for AppLocal {
         query: bookmarks::SearchQuery,
         bmark_update: bookmarks::BookmarkUpdate,
     ) -> anyhow::Result<usize, AppError> {
+        let query = self.resolving(query);
+        let rules_guard = self.rules_config.read().unwrap();
+        let on_update = rules_guard.rules().iter().any(|rule| rule.runs_on(Trigger::OnUpdate));
+
+        // the query may stop matching once updated, so find the ids first
+        let ids: Vec<u64> = if on_update {
+            self.bmark_mgr.search(query.clone())?.iter().map(|b| b.id).collect()
+        } else {
+            vec![]
+        };
+
         let search_update = self.bmark_mgr.search_update(query, bmark_update)?;
 
+        for id in ids {
+            Self::apply_rules(id, self.bmark_mgr.clone(), rules_guard.rules(), &self.saved_map(), &[Trigger::OnUpdate])?;
+        }
+
         Self::schedule_tags_cache_reval(self.bmark_mgr.clone(), self.tags_cache.clone());
 
         Ok(search_update)
@@ -304,7 +434,7 @@ impl AppBackend for AppLocal {
         &self,
         query: bookmarks::SearchQuery,
     ) -> anyhow::Result<Vec<bookmarks::Bookmark>, AppError> {
-        let mut query = query;
+        let mut query = self.resolving(query);
 
         // TODO: do we prevent queries against empty strings?
         log::info!("{:?}", query);
@@ -326,6 +456,203 @@ impl AppBackend for AppLocal {
         Ok(self.bmark_mgr.search(query)?)
     }
 
+    fn search_page(
+        &self,
+        query: bookmarks::SearchQuery,
+    ) -> anyhow::Result<bookmarks::SearchPage, AppError> {
+        // relevance sorting parses the query again
+        let query = self.resolving(query);
+        let matches = self.search(query.without_paging())?;
+        Ok(bookmarks::paginate(matches, &query))
+    }
+
+    fn saved_searches(&self) -> Option<Arc<RwLock<SavedSearchStore>>> {
+        Some(self.saved_searches.clone())
+    }
+
+    fn metadata_sources(&self) -> Arc<MetadataSources> {
+        self.reports.sources()
+    }
+
+    fn apply_rules_to_bookmarks(&self, opts: RuleApplyOpts) -> anyhow::Result<RuleApplyReport, AppError> {
+        let rules = {
+            let rules_guard = self.rules_config.read().unwrap();
+            match opts.rule {
+                Some(id) => vec![rules_guard
+                    .position(id)
+                    .map(|idx| rules_guard.rules()[idx].clone())
+                    .ok_or_else(|| anyhow!("rule {id} not found"))?],
+                None => rules_guard.rules().to_vec(),
+            }
+        };
+
+        let bmarks = self.search(bookmarks::SearchQuery {
+            query: opts.query.clone(),
+            ..Default::default()
+        })?;
+
+        // rewritten URLs must stay unique, among the changes too
+        let mut url_owners: HashMap<String, u64> = self
+            .bmark_mgr
+            .search(bookmarks::SearchQuery::default())?
+            .into_iter()
+            .map(|b| (b.url, b.id))
+            .collect();
+
+        let saved = self.saved_map();
+        let mut changes = Vec::new();
+        for bmark in bmarks {
+            let outcome = Self::evaluate_rules(&bmark, &rules, &saved, &[Trigger::Manual])?;
+            if outcome.matched.is_empty() {
+                continue;
+            }
+
+            let mut after = outcome.preview(&bmark);
+            match after.as_mut() {
+                Some(after) if after.url != bmark.url => match url_owners.get(&after.url) {
+                    Some(owner) if *owner != bmark.id => {
+                        log::warn!(
+                            "not rewriting the URL of bookmark {} to '{}': bookmark {owner} already has it",
+                            bmark.id,
+                            after.url
+                        );
+                        after.url = bmark.url.clone();
+                    }
+                    _ => {
+                        url_owners.remove(&bmark.url);
+                        url_owners.insert(after.url.clone(), bmark.id);
+                    }
+                },
+                Some(_) => {}
+                None => {
+                    url_owners.remove(&bmark.url);
+                }
+            }
+            if after.as_ref().is_some_and(|after| same_fields(&bmark, after)) {
+                continue;
+            }
+
+            changes.push(RuleChange {
+                before: bmark,
+                after,
+                rules: outcome.matched.iter().map(|idx| rules[*idx].id).collect(),
+            });
+        }
+
+        if opts.dry_run || changes.is_empty() {
+            return Ok(RuleApplyReport {
+                changes,
+                applied: false,
+            });
+        }
+
+        let undo_str = serde_json::to_string_pretty(&changes).context("failed to serialize undo log")?;
+        self.undo_store()?
+            .write(RULES_UNDO_FILE, undo_str.as_bytes())
+            .context("failed to write rules undo log")?;
+
+        let updated: Vec<bookmarks::Bookmark> = changes.iter().filter_map(|c| c.after.clone()).collect();
+        let deleted: Vec<u64> = changes.iter().filter(|c| c.after.is_none()).map(|c| c.before.id).collect();
+        self.bmark_mgr.write_batch(updated, &deleted)?;
+
+        Self::schedule_tags_cache_reval(self.bmark_mgr.clone(), self.tags_cache.clone());
+
+        Ok(RuleApplyReport {
+            changes,
+            applied: true,
+        })
+    }
+
+    fn undo_rules_apply(&self) -> anyhow::Result<usize, AppError> {
+        let store = self.undo_store()?;
+        if !store.exists(RULES_UNDO_FILE) {
+            return Err(AppError::Other(anyhow!("no rules apply to undo")));
+        }
+
+        let undo_log: Vec<RuleChange> =
+            serde_json::from_slice(&store.read(RULES_UNDO_FILE).context("failed to read rules undo log")?)
+                .context("rules undo log is malformed")?;
+
+        let existing: HashSet<u64> = self
+            .bmark_mgr
+            .search(bookmarks::SearchQuery::default())?
+            .iter()
+            .map(|b| b.id)
+            .collect();
+
+        // deleted bookmarks come back under their own id, unless a bookmark
+        // added since took it
+        let (restore, reused): (Vec<&RuleChange>, Vec<&RuleChange>) = undo_log
+            .iter()
+            .partition(|change| change.after.is_some() || !existing.contains(&change.before.id));
+        self.bmark_mgr
+            .write_batch(restore.into_iter().map(|change| change.before.clone()).collect(), &[])?;
+
+        for change in reused {
+            let before = &change.before;
+            let bmark = self.bmark_mgr.create(bookmarks::BookmarkCreate {
+                title: Some(before.title.clone()),
+                description: Some(before.description.clone()),
+                tags: Some(before.tags.clone()),
+                url: before.url.clone(),
+                image_id: before.image_id.clone(),
+                icon_id: before.icon_id.clone(),
+            })?;
+            log::warn!("bookmark {} was restored as {}, its id is taken", before.id, bmark.id);
+        }
+
+        store.delete(RULES_UNDO_FILE).context("failed to remove rules undo log")?;
+        Self::schedule_tags_cache_reval(self.bmark_mgr.clone(), self.tags_cache.clone());
+
+        Ok(undo_log.len())
+    }
+
+    fn metadata_reports(&self, id: u64) -> anyhow::Result<Vec<StoredReport>, AppError> {
+        let found = self.bmark_mgr.search(bookmarks::SearchQuery {
+            id: Some(id),
+            ..Default::default()
+        })?;
+        if found.is_empty() {
+            return Err(AppError::Other(anyhow!("bookmark {id} not found")));
+        }
+
+        Ok(self.reports.get(id))
+    }
+
+    fn list_saved_searches(&self) -> anyhow::Result<Vec<SavedSearch>, AppError> {
+        Ok(self.saved_searches.read().unwrap().list().to_vec())
+    }
+
+    fn create_saved_search(
+        &self,
+        name: String,
+        query: String,
+        description: Option<String>,
+    ) -> anyhow::Result<SavedSearch, AppError> {
+        let mut store = self.saved_searches.write().unwrap();
+        Ok(store.create(name, query, description).map_err(anyhow::Error::from)?)
+    }
+
+    fn update_saved_search(
+        &self,
+        name: &str,
+        new_name: Option<String>,
+        query: Option<String>,
+        description: Option<String>,
+    ) -> anyhow::Result<SavedSearch, AppError> {
+        let uses = self.saved_search_uses()?;
+        let mut store = self.saved_searches.write().unwrap();
+        Ok(store
+            .update(name, new_name, query, description, &uses)
+            .map_err(anyhow::Error::from)?)
+    }
+
+    fn delete_saved_search(&self, name: &str) -> anyhow::Result<(), AppError> {
+        let uses = self.saved_search_uses()?;
+        let mut store = self.saved_searches.write().unwrap();
+        Ok(store.delete(name, &uses).map_err(anyhow::Error::from)?)
+    }
+
     fn bookmark_version(&self) -> u64 {
         self.bmark_mgr.version()
     }
@@ -375,7 +702,9 @@ impl AppLocal {
         Ok(())
     }
 
-    pub fn fetch_metadata(url: &str, opts: FetchMetadataOpts) -> anyhow::Result<(Metadata, MetadataReport)> {
+    pub fn fetch_metadata(url: &str, mut opts: FetchMetadataOpts) -> anyhow::Result<(Metadata, MetadataReport)> {
+        // a forced refresh wants what the page says now, not what it said
+        opts.meta_opts.no_cache |= opts.force_overwrite;
         let mut url_parsed = reqwest::Url::parse(url)
             .map_err(|e| anyhow!("invalid URL '{}': {}", url, e))?;
         let mut tried_https = false;
@@ -419,6 +748,11 @@ impl AppLocal {
             bmark_update.description = meta.description;
         }
 
+        // Site extractor tags, present only with `scrape.site_tags`
+        if !meta.tags.is_empty() {
+            bmark_update.append_tags = Some(meta.tags);
+        }
+
         // Compress preview image to WebP
         if let Some(ref image) = meta.image {
             match images::compress_image(image, img_config.max_size, img_config.quality) {
@@ -457,11 +791,17 @@ impl AppLocal {
         bmark_mgr.update(bookmark.id, bmark_update)
     }
 
+    /// Run the actions of the rules for `triggers` that match bookmark `id`,
+    /// by priority, until a `stop` rule matches. Returns `None` when a
+    /// `Delete` rule removed it. `SkipMetadata` and `ForceHeadless` act
+    /// before the fetch and are skipped here.
     pub fn apply_rules(
         id: u64,
         bmark_mgr: Arc<dyn bookmarks::BookmarkManager>,
         rules: &[Rule],
-    ) -> anyhow::Result<bookmarks::Bookmark> {
+        saved: &SavedSearches,
+        triggers: &[Trigger],
+    ) -> anyhow::Result<Option<bookmarks::Bookmark>> {
         let query = bookmarks::SearchQuery {
             id: Some(id),
             ..Default::default()
@@ -472,6 +812,49 @@ impl AppLocal {
             .map(|b| b.first().cloned())?
             .ok_or_else(|| anyhow!("bookmark not found"))?;
 
+        match Self::evaluate_rules(&bmark, rules, saved, triggers)?.update {
+            Some(mut bmark_update) => {
+                if let Some(url) = bmark_update.url.as_ref().filter(|url| **url != bmark.url) {
+                    if let Some(owner) = Self::url_owner(bmark_mgr.as_ref(), url, bmark.id)? {
+                        log::warn!(
+                            "not rewriting the URL of bookmark {} to '{url}': bookmark {owner} already has it",
+                            bmark.id
+                        );
+                        bmark_update.url = None;
+                    }
+                }
+                bmark_mgr.update(bmark.id, bmark_update).map(Some)
+            }
+            None => {
+                log::info!("deleting bookmark {} by rule", bmark.id);
+                bmark_mgr.delete(bmark.id)?;
+                Ok(None)
+            }
+        }
+    }
+
+    /// Id of a bookmark other than `id` saved under `url`.
+    fn url_owner(
+        bmark_mgr: &dyn bookmarks::BookmarkManager,
+        url: &str,
+        id: u64,
+    ) -> anyhow::Result<Option<u64>> {
+        let found = bmark_mgr.search(bookmarks::SearchQuery {
+            url: Some(url.to_string()),
+            exact: true,
+            ..Default::default()
+        })?;
+        Ok(found.iter().map(|b| b.id).find(|owner| *owner != id))
+    }
+
+    /// Work out what the rules for `triggers` would do to `bmark` without
+    /// writing anything.
+    pub fn evaluate_rules(
+        bmark: &bookmarks::Bookmark,
+        rules: &[Rule],
+        saved: &SavedSearches,
+        triggers: &[Trigger],
+    ) -> anyhow::Result<RulesOutcome> {
         let mut bmark_update = bookmarks::BookmarkUpdate {
             title: if bmark.title.is_empty() {
                 None
@@ -491,8 +874,9 @@ impl AppLocal {
             },
             ..Default::default()
         };
+        let mut matched = Vec::new();
 
-        for rule in rules.iter() {
+        for (idx, rule) in rules::in_order(rules, triggers) {
             // recreating query because it could've been changed by previous rule
             let record = rules::Record {
                 url: bmark_update.url.clone().unwrap_or(bmark.url.clone()),
@@ -501,32 +885,111 @@ impl AppLocal {
                 tags: bmark_update.tags.clone(),
             };
 
-            if !rule.is_match(&record) {
+            if !rule.is_match(&record, saved) {
                 continue;
             }
+            matched.push(idx);
 
             match &rule.action {
-                crate::rules::Action::UpdateBookmark {
+                Action::UpdateBookmark {
                     title,
                     description,
                     tags,
                 } => {
-                    if title.is_some() {
-                        bmark_update.title = title.clone();
+                    let ctx = rule.template_context(&record);
+                    if let Some(title) = title {
+                        bmark_update.title = Some(ctx.render(title));
                     }
-                    if description.is_some() {
-                        bmark_update.description = description.clone();
+                    if let Some(description) = description {
+                        bmark_update.description = Some(ctx.render(description));
                     }
                     if let Some(tags) = tags {
                         let mut curr_tags = bmark_update.tags.take().unwrap_or_default();
-                        curr_tags.append(&mut tags.clone());
+                        curr_tags.extend(tags.iter().map(|tag| ctx.render(tag)).filter(|tag| !tag.is_empty()));
                         bmark_update.tags = Some(curr_tags);
                     }
                 }
+                Action::RemoveTags { tags } => {
+                    if let Some(curr_tags) = bmark_update.tags.as_mut() {
+                        curr_tags.retain(|t| !tags.iter().any(|r| r.eq_ignore_ascii_case(t)));
+                    }
+                }
+                Action::RewriteUrl { replacement, .. } => {
+                    if let Some(regex) = rule.action_regex().map_err(|e| anyhow!("RewriteUrl: {e}"))? {
+                        let url = bmark_update.url.clone().unwrap_or(bmark.url.clone());
+                        bmark_update.url = Some(regex.replace_all(&url, replacement.as_str()).into_owned());
+                    }
+                }
+                Action::ReplaceDescription { replacement, .. } => {
+                    let description = match rule.action_regex().map_err(|e| anyhow!("ReplaceDescription: {e}"))? {
+                        Some(regex) => {
+                            let curr = bmark_update.description.clone().unwrap_or_default();
+                            regex.replace_all(&curr, replacement.as_str()).into_owned()
+                        }
+                        None => replacement.clone(),
+                    };
+                    bmark_update.description = Some(description);
+                }
+                Action::Delete => {
+                    return Ok(RulesOutcome {
+                        update: None,
+                        matched,
+                    });
+                }
+                Action::SkipMetadata | Action::ForceHeadless => {}
+            }
+
+            if rule.stop {
+                break;
             }
         }
 
-        bmark_mgr.update(bmark.id, bmark_update)
+        Ok(RulesOutcome {
+            update: Some(bmark_update),
+            matched,
+        })
+    }
+
+    /// Keep `report` among the last `scrape.report_history` of bookmark `id`.
+    pub fn record_report(
+        reports: &ReportStore,
+        config: &RwLock<Config>,
+        id: u64,
+        url: &str,
+        report: &MetadataReport,
+    ) {
+        let keep = config.read().unwrap().scrape.report_history;
+        if let Err(err) = reports.record(id, url, report, keep) {
+            log::warn!("failed to store metadata report for bookmark {id}: {err:#}");
+        }
+    }
+
+    fn forget_reports(&self, ids: &[u64]) {
+        if let Err(err) = self.reports.remove(ids) {
+            log::warn!("failed to drop metadata reports of deleted bookmarks: {err:#}");
+        }
+    }
+
+    fn undo_store(&self) -> anyhow::Result<BackendLocal> {
+        let base_path = self.config.read().unwrap().base_path().to_string();
+        BackendLocal::new(&base_path).context("failed to initialize rules undo storage")
+    }
+
+    /// Adjust a metadata fetch for the `SkipMetadata`/`ForceHeadless` rules
+    /// matching `bmark`. Returns `false` when the fetch should be skipped.
+    /// An explicit `no_headless` still wins over `ForceHeadless`.
+    pub fn apply_fetch_rules(
+        bmark: &bookmarks::Bookmark,
+        rules: &[Rule],
+        saved: &SavedSearches,
+        meta_opts: &mut MetaOptions,
+    ) -> bool {
+        let directives = FetchDirectives::from_rules(rules, &rules::Record::from_bookmark(bmark), saved);
+        if directives.force_headless {
+            let scrape_config = meta_opts.scrape_config.get_or_insert_with(Default::default);
+            scrape_config.force_headless();
+        }
+        !directives.skip_metadata
     }
 
     pub fn schedule_fetch_and_update_metadata(
@@ -552,6 +1015,8 @@ impl AppLocal {
         task_queue_handle: Option<std::thread::JoinHandle<()>>,
         config: Arc<RwLock<Config>>,
     ) -> Self {
+        let reports = Arc::new(ReportStore::load(config.read().unwrap().base_path()));
+        let saved_searches = SavedSearchStore::load(config.read().unwrap().base_path()).unwrap();
         Self {
             bmark_mgr,
             storage_mgr,
@@ -560,6 +1025,32 @@ impl AppLocal {
             task_queue_handle,
             config,
             rules_config: Arc::new(RwLock::new(RulesConfig::default())),
+            reports,
+            saved_searches: Arc::new(RwLock::new(saved_searches)),
+        }
+    }
+
+    /// What `@name` references expand to right now.
+    fn saved_map(&self) -> Arc<SavedSearches> {
+        self.saved_searches.read().unwrap().map()
+    }
+
+    /// The rule and workspace queries a saved search change mustn't break.
+    /// Taken before the saved searches lock, like the daemon does.
+    fn saved_search_uses(&self) -> anyhow::Result<Vec<saved_searches::QueryUse>> {
+        let base_path = self.config.read().unwrap().base_path().to_string();
+        let workspaces = crate::workspaces::WorkspaceStore::load(&base_path)?;
+        let rules_config = self.rules_config.read().unwrap();
+        Ok(saved_searches::query_uses(&rules_config, &workspaces))
+    }
+
+    /// `query` with `@name` references resolving against the saved searches
+    /// and `source:` terms against the stored metadata reports.
+    fn resolving(&self, query: bookmarks::SearchQuery) -> bookmarks::SearchQuery {
+        bookmarks::SearchQuery {
+            saved_searches: self.saved_map(),
+            metadata_sources: self.reports.sources(),
+            ..query
         }
     }
 
@@ -571,4 +1062,9 @@ impl AppLocal {
     pub fn rules_config(&self) -> Arc<RwLock<RulesConfig>> {
         self.rules_config.clone()
     }
+
+    #[cfg(test)]
+    pub fn reports(&self) -> Arc<ReportStore> {
+        self.reports.clone()
+    }
 }
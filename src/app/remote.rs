@@ -5,7 +5,7 @@ use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use serde_json::json;
 use std::sync::Arc;
 
-use crate::{bookmarks, config::{Config, RulesConfig}, rules::Rule, web::TotalResponse};
+use crate::{bookmarks, config::{Config, RulesConfig}, metadata::reports::StoredReport, rules::Rule, saved_searches::SavedSearch, search_query::{Facets, QueryExplanation}, web::{TotalResponse, TOTAL_COUNT_HEADER}};
 
 use super::{backend::*, errors::AppError};
 
@@ -52,6 +52,22 @@ impl AppRemote {
         self.attach_auth(request)
     }
 
+    fn put(&self, url: &str) -> reqwest::blocking::RequestBuilder {
+        log::info!("{}{}", self.remote_addr, url);
+        let url = format!("{}{}", self.remote_addr, url);
+        let request = self.client.put(&url);
+
+        self.attach_auth(request)
+    }
+
+    fn delete_request(&self, url: &str) -> reqwest::blocking::RequestBuilder {
+        log::info!("{}{}", self.remote_addr, url);
+        let url = format!("{}{}", self.remote_addr, url);
+        let request = self.client.delete(&url);
+
+        self.attach_auth(request)
+    }
+
     fn attach_auth(&self, request: reqwest::blocking::RequestBuilder) -> reqwest::blocking::RequestBuilder {
         // Bearer token takes precedence over basic auth
         if let Some(ref token) = self.bearer_token {
@@ -84,6 +100,13 @@ pub enum WebResponse<T> {
     Data(T),
 }
 
+/// Search response shape when facets were requested.
+#[derive(Deserialize, Debug, Clone)]
+struct FacetedResponse {
+    bookmarks: Vec<bookmarks::Bookmark>,
+    facets: Facets,
+}
+
 fn handle_response<T>(response: reqwest::blocking::Response) -> anyhow::Result<T>
 where
     T: DeserializeOwned + Clone,
@@ -103,6 +126,16 @@ where
     }
 }
 
+/// For endpoints that answer `204 No Content` on success.
+fn handle_empty_response(response: reqwest::blocking::Response) -> anyhow::Result<()> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(());
+    }
+    handle_response::<serde_json::Value>(response)?;
+    bail!("request failed with {status}")
+}
+
 impl AppBackend for AppRemote {
     fn config(&self) -> anyhow::Result<Arc<RwLock<Config>>, AppError> {
         let resp = self.get("/api/config").send()?;
@@ -236,6 +269,13 @@ impl AppBackend for AppRemote {
         &self,
         query: bookmarks::SearchQuery,
     ) -> anyhow::Result<Vec<bookmarks::Bookmark>, AppError> {
+        Ok(self.search_page(query)?.bookmarks)
+    }
+
+    fn search_page(
+        &self,
+        query: bookmarks::SearchQuery,
+    ) -> anyhow::Result<bookmarks::SearchPage, AppError> {
         log::debug!("search: {query:?}");
         let resp = self
             .post("/api/bookmarks/search")
@@ -249,13 +289,113 @@ impl AppBackend for AppRemote {
                 "semantic": query.semantic,
                 "threshold": query.threshold,
                 "exact": query.exact,
-                "limit": query.limit
+                "limit": query.limit,
+                "fuzzy_fallback": query.fuzzy_fallback,
+                "sort": query.sort,
+                "direction": query.direction,
+                "offset": query.offset,
+                "facets": query.facets
+            }))
+            .send()?;
+
+        let total = resp
+            .headers()
+            .get(TOTAL_COUNT_HEADER)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.parse::<usize>().ok());
+        let (bookmarks, facets) = if query.facets {
+            let faceted: FacetedResponse = handle_response(resp)?;
+            (faceted.bookmarks, Some(faceted.facets))
+        } else {
+            (handle_response::<Vec<bookmarks::Bookmark>>(resp)?, None)
+        };
+        let total = total.unwrap_or(bookmarks.len());
+
+        Ok(bookmarks::SearchPage {
+            bookmarks,
+            total,
+            facets,
+        })
+    }
+
+    fn explain_query(&self, query: &str, id: Option<u64>) -> anyhow::Result<QueryExplanation, AppError> {
+        let resp = self
+            .post("/api/query/explain")
+            .json(&json!({ "query": query, "id": id }))
+            .send()?;
+
+        Ok(handle_response(resp)?)
+    }
+
+    fn apply_rules_to_bookmarks(&self, opts: RuleApplyOpts) -> anyhow::Result<RuleApplyReport, AppError> {
+        let resp = self.post("/api/rules/apply").json(&opts).send()?;
+
+        Ok(handle_response(resp)?)
+    }
+
+    fn undo_rules_apply(&self) -> anyhow::Result<usize, AppError> {
+        let resp = self.post("/api/rules/undo").send()?;
+
+        Ok(handle_response(resp)?)
+    }
+
+    fn metadata_reports(&self, id: u64) -> anyhow::Result<Vec<StoredReport>, AppError> {
+        let resp = self.get(&format!("/api/bookmarks/{id}/metadata_report")).send()?;
+
+        Ok(handle_response(resp)?)
+    }
+
+    fn list_saved_searches(&self) -> anyhow::Result<Vec<SavedSearch>, AppError> {
+        let resp = self.get("/api/searches").send()?;
+
+        Ok(handle_response(resp)?)
+    }
+
+    fn create_saved_search(
+        &self,
+        name: String,
+        query: String,
+        description: Option<String>,
+    ) -> anyhow::Result<SavedSearch, AppError> {
+        let resp = self
+            .post("/api/searches")
+            .json(&json!({
+                "name": name,
+                "query": query,
+                "description": description,
             }))
             .send()?;
 
         Ok(handle_response(resp)?)
     }
 
+    fn update_saved_search(
+        &self,
+        name: &str,
+        new_name: Option<String>,
+        query: Option<String>,
+        description: Option<String>,
+    ) -> anyhow::Result<SavedSearch, AppError> {
+        let resp = self
+            .put(&format!("/api/searches/{}", name.trim_start_matches('@')))
+            .json(&json!({
+                "name": new_name,
+                "query": query,
+                "description": description,
+            }))
+            .send()?;
+
+        Ok(handle_response(resp)?)
+    }
+
+    fn delete_saved_search(&self, name: &str) -> anyhow::Result<(), AppError> {
+        let resp = self
+            .delete_request(&format!("/api/searches/{}", name.trim_start_matches('@')))
+            .send()?;
+
+        Ok(handle_empty_response(resp)?)
+    }
+
     fn bookmark_version(&self) -> u64 {
         0
     }
@@ -1,7 +1,9 @@
 use crate::{
-    app::backend::{AddOpts, AppBackend, RefreshMetadataOpts},
-    bookmarks::{Bookmark, BookmarkCreate, BookmarkUpdate, SearchQuery},
+    app::backend::{AddOpts, AppBackend, RefreshMetadataOpts, RuleApplyOpts, RuleApplyReport},
+    bookmarks::{self, Bookmark, BookmarkCreate, BookmarkUpdate, SearchPage, SearchQuery},
     config::{Config, RulesConfig},
+    saved_searches::{SavedSearch, SavedSearchStore},
+    search_query::{QueryExplanation, SavedSearches},
     semantic::{content_hash, preprocess_content, SemanticSearchService},
 };
 use anyhow::{Context, Result};
@@ -100,6 +102,30 @@ impl AppService {
         Ok(bookmarks)
     }
 
+    /// Search with `sort`/`offset`/`limit` applied and the total match count.
+    ///
+    /// Semantic ranking needs the whole match set, so in that case the page is
+    /// cut here; otherwise paging is left to the backend (the daemon, for a
+    /// remote backend).
+    pub fn search_page(&self, query: SearchQuery) -> Result<SearchPage> {
+        if query.semantic.is_some() && self.semantic_service.is_some() {
+            let ranked = self.search_bookmarks(query.without_paging(), false)?;
+            return Ok(bookmarks::paginate(ranked, &query));
+        }
+
+        self.backend
+            .search_page(query)
+            .context("Failed to search bookmarks")
+    }
+
+    /// Parse `query` for inspection and, given a bookmark id, report which
+    /// terms match it and where.
+    pub fn explain_query(&self, query: &str, id: Option<u64>) -> Result<QueryExplanation> {
+        self.backend
+            .explain_query(query, id)
+            .context("Failed to explain query")
+    }
+
     /// Apply hybrid ranking (semantic + lexical with RRF fusion) to filtered bookmarks.
     ///
     /// Hybrid search combines:
@@ -549,6 +575,54 @@ impl AppService {
         Ok(config)
     }
 
+    /// The saved search store of a local backend.
+    pub fn saved_searches(&self) -> Option<Arc<RwLock<SavedSearchStore>>> {
+        self.backend.saved_searches()
+    }
+
+    /// What `@name` references expand to here; none for a remote backend,
+    /// whose daemon expands them.
+    pub fn saved_search_map(&self) -> Arc<SavedSearches> {
+        self.saved_searches()
+            .map(|store| store.read().unwrap().map())
+            .unwrap_or_default()
+    }
+
+    pub fn list_saved_searches(&self) -> Result<Vec<SavedSearch>> {
+        self.backend
+            .list_saved_searches()
+            .context("Failed to list saved searches")
+    }
+
+    pub fn create_saved_search(
+        &self,
+        name: String,
+        query: String,
+        description: Option<String>,
+    ) -> Result<SavedSearch> {
+        self.backend
+            .create_saved_search(name, query, description)
+            .context("Failed to save search")
+    }
+
+    pub fn update_saved_search(
+        &self,
+        name: &str,
+        new_name: Option<String>,
+        query: Option<String>,
+        description: Option<String>,
+    ) -> Result<SavedSearch> {
+        self.backend
+            .update_saved_search(name, new_name, query, description)
+            .context("Failed to update saved search")
+    }
+
+    pub fn delete_saved_search(&self, name: &str) -> Result<()> {
+        self.backend
+            .delete_saved_search(name)
+            .context("Failed to delete saved search")
+    }
+
     /// Get the current rules configuration
     pub fn get_rules(&self) -> Result<Arc<RwLock<RulesConfig>>> {
         let rules = self
@@ -559,6 +633,43 @@ impl AppService {
         Ok(rules)
     }
 
+    /// Run the rules against existing bookmarks, or preview the changes
+    pub fn apply_rules(&self, opts: RuleApplyOpts) -> Result<RuleApplyReport> {
+        let report = self
+            .backend
+            .apply_rules_to_bookmarks(opts)
+            .context("Failed to apply rules")?;
+
+        if report.applied {
+            if let Some(ref service) = self.semantic_service {
+                service.mark_dirty();
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// Stored metadata reports of bookmark `id`, newest first
+    pub fn metadata_reports(&self, id: u64) -> Result<Vec<crate::metadata::reports::StoredReport>> {
+        self.backend
+            .metadata_reports(id)
+            .context("Failed to get metadata reports")
+    }
+
+    /// Revert the last rules apply
+    pub fn undo_rules_apply(&self) -> Result<usize> {
+        let count = self
+            .backend
+            .undo_rules_apply()
+            .context("Failed to undo rules apply")?;
+
+        if let Some(ref service) = self.semantic_service {
+            service.mark_dirty();
+        }
+
+        Ok(count)
+    }
+
     /// Update the configuration
     pub fn update_config(&self, config: Config) -> Result<()> {
         // Validate the configuration before updating
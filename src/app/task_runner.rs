@@ -16,6 +16,10 @@ use crate::{
     bookmarks,
     config::{Config, RulesConfig},
     eid::Eid,
+    metadata::reports::ReportStore,
+    rules::Trigger,
+    saved_searches::SavedSearchStore,
+    scrape::limiter::HostStatus,
     storage::{self, StorageManager},
 };
 
@@ -39,6 +43,8 @@ pub fn start_queue(
     storage_mgr: Arc<dyn storage::StorageManager>,
     config: Arc<RwLock<Config>>,
     rules_config: Arc<RwLock<RulesConfig>>,
+    reports: Arc<ReportStore>,
+    saved_searches: Arc<RwLock<SavedSearchStore>>,
 ) {
     use std::sync::atomic::Ordering;
 
@@ -67,6 +73,8 @@ pub fn start_queue(
             let thread_counter = thread_counter.clone();
             let id = id.clone();
             let rules_config = rules_config.clone();
+            let reports = reports.clone();
+            let saved_searches = saved_searches.clone();
             move || {
                 throttle(thread_counter.clone(), config.clone());
 
@@ -77,7 +85,7 @@ pub fn start_queue(
                 let mut attempt = 0u8;
 
                 loop {
-                    let status = task.run(bmark_mgr.clone(), storage_mgr.clone(), config.clone(), rules_config.clone());
+                    let status = task.run(bmark_mgr.clone(), storage_mgr.clone(), config.clone(), rules_config.clone(), &reports, &saved_searches);
 
                     match &status {
                         Status::Error(msg) if attempt < max_retries && is_retryable_error(msg) => {
@@ -130,6 +138,7 @@ pub fn read_queue_dump() -> QueueDump {
             return QueueDump {
                 queue: vec![],
                 now: now(),
+                hosts: vec![],
             };
         }
     };
@@ -144,6 +153,7 @@ pub fn read_queue_dump() -> QueueDump {
                 QueueDump {
                     queue: vec![],
                     now: now(),
+                    hosts: vec![],
                 }
             }
         }
@@ -151,6 +161,7 @@ pub fn read_queue_dump() -> QueueDump {
         QueueDump {
             queue: vec![],
             now: now(),
+            hosts: vec![],
         }
     }
 }
@@ -281,6 +292,10 @@ pub enum Status {
 pub struct QueueDump {
     pub queue: Vec<TaskDump>,
     pub now: u128,
+    /// Hosts the scrape rate limiter is holding requests for. Live state of
+    /// the serving process, filled in by `GET /api/task_queue`.
+    #[serde(default)]
+    pub hosts: Vec<HostStatus>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -311,10 +326,13 @@ impl Task {
         storage_mgr: Arc<dyn storage::StorageManager>,
         config: Arc<RwLock<Config>>,
         rules_config: Arc<RwLock<RulesConfig>>,
+        reports: &ReportStore,
+        saved_searches: &RwLock<SavedSearchStore>,
     ) -> Status {
         match self {
             Task::FetchMetadata { bmark_id, opts } => {
                 let bmark_id = *bmark_id;
+                let saved = saved_searches.read().unwrap().map();
                 let handle_metadata = || {
                     log::debug!("picked up a job...");
                     let bookmarks = bmark_mgr.search(bookmarks::SearchQuery {
@@ -330,6 +348,15 @@ impl Task {
                     let scrape_cfg = config.read().unwrap().scrape.clone();
                     opts.meta_opts.scrape_config = Some(scrape_cfg);
 
+                    let fetch = {
+                        let rules_guard = rules_config.read().unwrap();
+                        AppLocal::apply_fetch_rules(bmark, rules_guard.rules(), &saved, &mut opts.meta_opts)
+                    };
+                    if !fetch {
+                        log::info!("metadata fetch for bookmark {bmark_id} skipped by rule");
+                        return Ok(None);
+                    }
+
                     let force_overwrite = opts.force_overwrite;
                     let (meta, report) = AppLocal::fetch_metadata(&bmark.url, opts)?;
                     log::info!(
@@ -338,6 +365,7 @@ impl Task {
                         report.field_decisions.len(),
                         report.duration_ms
                     );
+                    AppLocal::record_report(reports, &config, bmark_id, &bmark.url, &report);
 
                     let img_config = &config.read().unwrap().images;
                     let bmark = AppLocal::merge_metadata(
@@ -349,13 +377,24 @@ impl Task {
                         force_overwrite,
                     )?;
 
-                    Ok(bmark) as anyhow::Result<bookmarks::Bookmark>
+                    Ok(Some(bmark)) as anyhow::Result<Option<bookmarks::Bookmark>>
                 };
 
                 let fetch_meta_result = handle_metadata();
+                // Nothing was fetched, so there is nothing for `after_metadata`
+                // rules to react to, same as a skipped sync add or refresh
+                if let Ok(None) = fetch_meta_result {
+                    return Status::Done;
+                }
 
                 let rules_guard = rules_config.read().unwrap();
-                match AppLocal::apply_rules(bmark_id, bmark_mgr.clone(), rules_guard.rules()) {
+                match AppLocal::apply_rules(
+                    bmark_id,
+                    bmark_mgr.clone(),
+                    rules_guard.rules(),
+                    &saved,
+                    &[Trigger::AfterMetadata],
+                ) {
                     Ok(_) => match fetch_meta_result {
                         Ok(_) => Status::Done,
                         Err(err) => Status::Error(err.to_string()),
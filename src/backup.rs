@@ -9,8 +9,15 @@ use flate2::write::GzEncoder;
 use flate2::Compression;
 use tar::{Archive, Builder};
 
-const BACKUP_FILES: &[&str] = &["bookmarks.csv", "config.yaml", "workspaces.yaml"];
+const BACKUP_FILES: &[&str] = &[
+    "bookmarks.csv",
+    "config.yaml",
+    "workspaces.yaml",
+    "saved_searches.yaml",
+];
 const BACKUP_DIRS: &[&str] = &["uploads"];
+/// Cookie jars hold login sessions, so they're only backed up on request.
+const COOKIES_DIR: &str = crate::scrape::cookies::COOKIES_DIR;
 
 /// Write target for backup: either a file path or stdout (when piped).
 enum BackupTarget {
@@ -18,7 +25,7 @@ enum BackupTarget {
     Stdout,
 }
 
-pub fn create_backup(output_path: Option<PathBuf>, base_path: &Path) -> Result<()> {
+pub fn create_backup(output_path: Option<PathBuf>, base_path: &Path, include_cookies: bool) -> Result<()> {
 
     let target = match output_path {
         Some(p) => BackupTarget::File(p),
@@ -57,7 +64,8 @@ pub fn create_backup(output_path: Option<PathBuf>, base_path: &Path) -> Result<(
         }
     }
 
-    for dirname in BACKUP_DIRS {
+    let cookies = include_cookies.then_some(COOKIES_DIR);
+    for dirname in BACKUP_DIRS.iter().copied().chain(cookies) {
         let dir_path = base_path.join(dirname);
         if dir_path.exists() && dir_path.is_dir() {
             append_dir_recursive(&mut archive, &dir_path, Path::new(dirname), piped)?;
@@ -234,8 +242,9 @@ fn is_whitelisted(path: &str) -> bool {
         return true;
     }
 
-    // Check if it's under a known directory
-    for dir in BACKUP_DIRS {
+    // Check if it's under a known directory; cookies are only in archives
+    // made with --include-cookies
+    for dir in BACKUP_DIRS.iter().chain(&[COOKIES_DIR]) {
         if path.starts_with(&format!("{dir}/")) {
             return true;
         }
@@ -259,6 +268,9 @@ mod tests {
         std::fs::create_dir_all(&uploads).unwrap();
         std::fs::write(uploads.join("file1.png"), b"png-data").unwrap();
         std::fs::write(uploads.join("file2.jpg"), b"jpg-data").unwrap();
+        let cookies = dir.join("cookies");
+        std::fs::create_dir_all(&cookies).unwrap();
+        std::fs::write(cookies.join("github.com.txt"), "github.com\tFALSE\t/\tTRUE\t0\tsession\tx\n").unwrap();
     }
 
     /// Helper: list entry paths in a tar.gz archive.
@@ -280,6 +292,7 @@ mod tests {
         assert!(is_whitelisted("workspaces.yaml"));
         assert!(is_whitelisted("uploads/file.png"));
         assert!(is_whitelisted("uploads/sub/deep.jpg"));
+        assert!(is_whitelisted("cookies/github.com.txt"));
         assert!(!is_whitelisted("uploads"));
         assert!(!is_whitelisted("evil.sh"));
         assert!(!is_whitelisted("../etc/passwd"));
@@ -294,7 +307,7 @@ mod tests {
         let out_dir = TempDir::new().unwrap();
         let archive_path = out_dir.path().join("test.tar.gz");
 
-        create_backup(Some(archive_path.clone()), base.path()).unwrap();
+        create_backup(Some(archive_path.clone()), base.path(), false).unwrap();
 
         assert!(archive_path.exists());
         let entries: HashSet<String> = list_archive_entries(&archive_path).into_iter().collect();
@@ -303,6 +316,25 @@ mod tests {
         assert!(entries.contains("workspaces.yaml"));
         assert!(entries.contains("uploads/file1.png"));
         assert!(entries.contains("uploads/file2.jpg"));
+        assert!(!entries.contains("cookies/github.com.txt"));
+    }
+
+    #[test]
+    fn test_backup_include_cookies() {
+        let base = TempDir::new().unwrap();
+        populate_base_dir(base.path());
+
+        let out_dir = TempDir::new().unwrap();
+        let archive_path = out_dir.path().join("cookies.tar.gz");
+        create_backup(Some(archive_path.clone()), base.path(), true).unwrap();
+
+        let entries: HashSet<String> = list_archive_entries(&archive_path).into_iter().collect();
+        assert!(entries.contains("uploads/file1.png"));
+        assert!(entries.contains("cookies/github.com.txt"));
+
+        let base_dst = TempDir::new().unwrap();
+        import_backup(Some(archive_path.as_path()), true, base_dst.path()).unwrap();
+        assert!(base_dst.path().join("cookies/github.com.txt").exists());
     }
 
     #[test]
@@ -311,7 +343,7 @@ mod tests {
         let out = TempDir::new().unwrap();
         let archive_path = out.path().join("empty.tar.gz");
 
-        let result = create_backup(Some(archive_path), base.path());
+        let result = create_backup(Some(archive_path), base.path(), false);
         assert!(result.is_err());
         assert!(result.unwrap_err().to_string().contains("No files found"));
     }
@@ -323,7 +355,7 @@ mod tests {
 
         let out = TempDir::new().unwrap();
         let archive_path = out.path().join("roundtrip.tar.gz");
-        create_backup(Some(archive_path.clone()), base_src.path()).unwrap();
+        create_backup(Some(archive_path.clone()), base_src.path(), false).unwrap();
 
         // Import into a fresh dir
         let base_dst = TempDir::new().unwrap();
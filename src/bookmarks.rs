@@ -1,4 +1,5 @@
 use crate::parse_tags;
+use crate::search_query::{Facets, MetadataSources, SavedSearches, SearchIndex};
 use anyhow::anyhow;
 use serde::{Deserialize, Serialize};
 use std::{
@@ -99,6 +100,133 @@ pub struct SearchQuery {
 
     #[serde(default)]
     pub limit: Option<usize>,
+
+    /// Retry with typo-tolerant matching when `query` finds nothing
+    #[serde(default)]
+    pub fuzzy_fallback: bool,
+
+    /// Result ordering; storage order when unset
+    #[serde(default)]
+    pub sort: Option<SortField>,
+
+    /// Defaults to ascending, or best-first for `relevance`
+    #[serde(default)]
+    pub direction: Option<SortDirection>,
+
+    /// Number of matches to skip before `limit` applies
+    #[serde(default)]
+    pub offset: Option<usize>,
+
+    /// Count tags, domains and flags over all matches, not just the page
+    #[serde(default)]
+    pub facets: bool,
+
+    /// What `@name` references in `query` expand to; filled in by the app
+    /// backend, never sent over the wire
+    #[serde(skip)]
+    pub saved_searches: Arc<SavedSearches>,
+
+    /// Which fetcher supplied each field, for `source:` terms; filled in by
+    /// the app backend like `saved_searches`
+    #[serde(skip)]
+    pub metadata_sources: Arc<MetadataSources>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SortField {
+    Id,
+    Title,
+    Domain,
+    /// BM25 relevance to the query terms; semantic searches keep their
+    /// hybrid ranking
+    Relevance,
+}
+
+impl std::str::FromStr for SortField {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "id" => Ok(Self::Id),
+            "title" => Ok(Self::Title),
+            "domain" => Ok(Self::Domain),
+            "relevance" => Ok(Self::Relevance),
+            _ => Err(format!(
+                "unknown sort field '{}', expected one of: id, title, domain, relevance",
+                s
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SortDirection {
+    Asc,
+    Desc,
+}
+
+/// One page of search results along with the number of matches overall.
+#[derive(Debug, Clone, Default)]
+pub struct SearchPage {
+    pub bookmarks: Vec<Bookmark>,
+    pub total: usize,
+    /// Set when the query asked for facets.
+    pub facets: Option<Facets>,
+}
+
+/// Sort `bookmarks` as requested by `query`, then cut out the page selected by
+/// `offset` and `limit`. `bookmarks` must be the full, unpaged match set.
+pub fn paginate(mut bookmarks: Vec<Bookmark>, query: &SearchQuery) -> SearchPage {
+    if let Some(field) = query.sort {
+        let descending = match (field, query.direction) {
+            (_, Some(direction)) => direction == SortDirection::Desc,
+            (SortField::Relevance, None) => true,
+            (_, None) => false,
+        };
+
+        match field {
+            SortField::Id => bookmarks.sort_by_key(|b| b.id),
+            SortField::Title => bookmarks.sort_by_cached_key(|b| (b.title.to_lowercase(), b.id)),
+            SortField::Domain => bookmarks.sort_by_cached_key(|b| {
+                (crate::search_query::url_host(&b.url).unwrap_or_default(), b.id)
+            }),
+            // Semantic results already arrive ranked, best first
+            SortField::Relevance if query.semantic.is_some() => {}
+            SortField::Relevance => {
+                let filter = query
+                    .query
+                    .as_deref()
+                    .and_then(|q| crate::search_query::parse_with(q, &query.saved_searches).ok());
+                if let Some(filter) = filter {
+                    crate::search_query::rank_by_relevance(&filter, &mut bookmarks);
+                }
+            }
+        }
+
+        let reverse = match field {
+            SortField::Relevance => !descending,
+            _ => descending,
+        };
+        if reverse {
+            bookmarks.reverse();
+        }
+    }
+
+    let total = bookmarks.len();
+    let facets = query.facets.then(|| crate::search_query::facets(&bookmarks));
+    let bookmarks = bookmarks
+        .into_iter()
+        .skip(query.offset.unwrap_or(0))
+        .take(query.limit.unwrap_or(usize::MAX))
+        .collect();
+
+    SearchPage {
+        bookmarks,
+        total,
+        facets,
+    }
 }
 
 pub trait BookmarkManager: Send + Sync {
@@ -108,6 +236,10 @@ pub trait BookmarkManager: Send + Sync {
     fn create(&self, bookmark: BookmarkCreate) -> anyhow::Result<Bookmark>;
     fn update(&self, id: u64, update: BookmarkUpdate) -> anyhow::Result<Bookmark>;
     fn delete(&self, id: u64) -> anyhow::Result<()>;
+    /// Store `bookmarks` as given, replacing those with the same id and
+    /// adding the others under their own id, and delete `delete`, all in one
+    /// write.
+    fn write_batch(&self, bookmarks: Vec<Bookmark>, delete: &[u64]) -> anyhow::Result<()>;
     fn version(&self) -> u64 { 0 }
 }
 
@@ -125,11 +257,31 @@ impl SearchQuery {
             .map(|tags| tags.iter().map(|t| t.to_lowercase()).collect::<Vec<_>>());
         self.query = self.query.as_ref().map(|q| q.to_lowercase());
     }
+
+    /// Whether results need sorting, an offset or facets applied after the
+    /// search.
+    pub fn is_paged(&self) -> bool {
+        self.sort.is_some() || self.offset.is_some() || self.facets
+    }
+
+    /// Copy of the query that returns the full, unsorted match set.
+    pub fn without_paging(&self) -> Self {
+        Self {
+            sort: None,
+            direction: None,
+            offset: None,
+            limit: None,
+            facets: false,
+            ..self.clone()
+        }
+    }
 }
 
 #[derive(Debug, Default)]
 pub struct BackendCsv {
     list: Arc<RwLock<Vec<Bookmark>>>,
+    /// Kept in step with `list`; lock `list` first when taking both.
+    index: Arc<RwLock<SearchIndex>>,
     path: String,
     version: Arc<AtomicU64>,
 }
@@ -138,6 +290,7 @@ impl Clone for BackendCsv {
     fn clone(&self) -> Self {
         Self {
             list: self.list.clone(),
+            index: self.index.clone(),
             path: self.path.clone(),
             version: self.version.clone(),
         }
@@ -232,8 +385,16 @@ impl BackendCsv {
             now.elapsed().as_micros() as f64 / 1000.0
         );
 
+        let now = Instant::now();
+        let index = SearchIndex::build(&bmarks);
+        log::debug!(
+            "took {}ms to index bookmarks",
+            now.elapsed().as_micros() as f64 / 1000.0
+        );
+
         let mgr = BackendCsv {
             list: Arc::new(RwLock::new(bmarks)),
+            index: Arc::new(RwLock::new(index)),
             path: path.to_string(),
             version: Arc::new(AtomicU64::new(0)),
         };
@@ -272,6 +433,7 @@ impl BackendCsv {
     pub fn wipe_database(self) -> Self {
         let _ = std::fs::remove_file(&self.path);
         *self.list.write().unwrap() = vec![];
+        *self.index.write().unwrap() = SearchIndex::default();
         self
     }
 }
@@ -303,7 +465,10 @@ impl BookmarkManager for BackendCsv {
             icon_id: bmark_create.icon_id,
         };
 
-        self.list.write().unwrap().push(bmark.clone());
+        let mut bmarks = self.list.write().unwrap();
+        self.index.write().unwrap().insert(&bmark);
+        bmarks.push(bmark.clone());
+        drop(bmarks);
 
         self.save()?;
 
@@ -313,7 +478,8 @@ impl BookmarkManager for BackendCsv {
     fn delete(&self, id: u64) -> anyhow::Result<()> {
         let mut bmarks = self.list.write().unwrap();
         let result = bmarks.iter().position(|b| b.id == id).map(|idx| {
-            bmarks.remove(idx);
+            let removed = bmarks.remove(idx);
+            self.index.write().unwrap().remove(&removed);
             true
         });
 
@@ -335,6 +501,7 @@ impl BookmarkManager for BackendCsv {
             .ok_or_else(|| anyhow::anyhow!("Bookmark with id {} not found", id))?;
 
         let bmark = &mut bmarks[bmark_idx];
+        let previous = bmark.clone();
 
         if let Some(title) = bmark_update.title {
             bmark.title = title;
@@ -372,6 +539,11 @@ impl BookmarkManager for BackendCsv {
             bmark.icon_id = Some(icon_id);
         }
 
+        let mut index = self.index.write().unwrap();
+        index.remove(&previous);
+        index.insert(bmark);
+        drop(index);
+
         let result = bmark.clone();
         drop(bmarks);
 
@@ -380,18 +552,58 @@ impl BookmarkManager for BackendCsv {
         Ok(result)
     }
 
+    fn write_batch(&self, bookmarks: Vec<Bookmark>, delete: &[u64]) -> anyhow::Result<()> {
+        let mut bmarks = self.list.write().unwrap();
+        let mut index = self.index.write().unwrap();
+
+        bmarks.retain(|b| {
+            let delete = delete.contains(&b.id);
+            if delete {
+                index.remove(b);
+            }
+            !delete
+        });
+
+        for mut bmark in bookmarks {
+            let mut seen = HashSet::new();
+            bmark.tags.retain(|item| seen.insert(item.clone()));
+
+            match bmarks.iter_mut().find(|b| b.id == bmark.id) {
+                Some(existing) => {
+                    index.remove(existing);
+                    index.insert(&bmark);
+                    *existing = bmark;
+                }
+                None => {
+                    index.insert(&bmark);
+                    let pos = bmarks.partition_point(|b| b.id < bmark.id);
+                    bmarks.insert(pos, bmark);
+                }
+            }
+        }
+
+        drop(index);
+        drop(bmarks);
+
+        self.save()
+    }
+
     fn search_delete(&self, query: SearchQuery) -> anyhow::Result<usize> {
         let results = self.search(query)?;
         let delete_ids = results;
         let count = delete_ids.len();
 
         let mut bmarks = self.list.write().unwrap();
-        *bmarks = bmarks
-            .iter()
-            .filter(|b| !delete_ids.iter().any(|bb| b.id == bb.id))
-            .cloned()
-            .collect::<Vec<Bookmark>>();
+        let mut index = self.index.write().unwrap();
+        bmarks.retain(|b| {
+            let delete = delete_ids.iter().any(|bb| b.id == bb.id);
+            if delete {
+                index.remove(b);
+            }
+            !delete
+        });
 
+        drop(index);
         drop(bmarks);
 
         self.save()?;
@@ -407,10 +619,12 @@ impl BookmarkManager for BackendCsv {
         let results = self.search(query)?;
         let count = results.len();
         let mut bmarks = self.list.write().unwrap();
+        let mut index = self.index.write().unwrap();
         for bmark in bmarks.iter_mut() {
             if !results.iter().any(|b| b.id == bmark.id) {
                 continue;
             }
+            index.remove(bmark);
 
             if let Some(ref title) = bmark_update.title {
                 bmark.title = title.to_string();
@@ -448,8 +662,11 @@ impl BookmarkManager for BackendCsv {
             if let Some(ref icon_id) = bmark_update.icon_id {
                 bmark.icon_id = Some(icon_id.to_string());
             }
+
+            index.insert(bmark);
         }
 
+        drop(index);
         drop(bmarks);
 
         self.save()?;
@@ -459,12 +676,11 @@ impl BookmarkManager for BackendCsv {
 
     fn search(&self, query: SearchQuery) -> anyhow::Result<Vec<Bookmark>> {
         let bmarks = self.list.read().unwrap();
+        let index = self.index.read().unwrap();
 
         let mut query = query;
         query.lowercase();
 
-        let mut output = vec![];
-
         // return all
         if query.description.is_none()
             && query.url.is_none()
@@ -482,7 +698,7 @@ impl BookmarkManager for BackendCsv {
                 None
             } else {
                 Some(
-                    crate::search_query::parse(query_str)
+                    crate::search_query::parse_with(query_str, &query.saved_searches)
                         .map_err(|e| anyhow::anyhow!("invalid search query: {}", e))?,
                 )
             }
@@ -508,23 +724,20 @@ impl BookmarkManager for BackendCsv {
             (None, _) => None,
         };
 
+        // `-tag` negates; the rest of the syntax (`=exact`, `*` globs) is shared
+        // with `#` query terms
         let query_tags = query.tags.map(|tags| {
-            let tags = tags.clone();
             tags.iter()
-                .cloned()
-                .map(|tag| {
-                    let unprefixed = tag.strip_prefix("-").map(String::from);
-                    (
-                        tag.clone(),
-                        format!("{tag}/"),
-                        unprefixed.clone(),
-                        format!("{}/", unprefixed.unwrap_or_default()),
-                    )
+                .map(|tag| match tag.strip_prefix('-') {
+                    Some(negated) => (true, crate::search_query::TagPattern::parse(negated)),
+                    None => (false, crate::search_query::TagPattern::parse(tag)),
                 })
                 .collect::<Vec<_>>()
         });
 
-        let evaluate_bookmark = |bookmark: &Bookmark| -> anyhow::Result<bool> {
+        let evaluate_bookmark = |bookmark: &Bookmark,
+                                 filter: Option<&crate::search_query::SearchFilter>|
+         -> anyhow::Result<bool> {
             let mut has_match = constrained_id.is_some();
 
             if let Some(url) = &query.url {
@@ -557,27 +770,11 @@ impl BookmarkManager for BackendCsv {
                 }
             };
 
-            let bmark_tags = bookmark
-                .tags
-                .iter()
-                .map(|t| t.to_lowercase())
-                .collect::<Vec<_>>();
-
             if let Some(tags) = &query_tags {
                 if !tags.is_empty() {
-                    for (tag, teg_delim, neg_tag, neg_tag_delim) in tags {
-                        let mut bmark_tags = bmark_tags.iter();
-                        if let Some(neg_tag) = neg_tag {
-                            if bmark_tags
-                                .any(|tag_b| neg_tag == tag_b || tag_b.contains(neg_tag_delim))
-                            {
-                                has_match = false;
-                                break;
-                            } else {
-                                has_match = true;
-                            }
-                        } else if !bmark_tags.any(|tag_b| tag == tag_b || tag_b.contains(teg_delim))
-                        {
+                    for (negated, pattern) in tags {
+                        let found = bookmark.tags.iter().any(|tag_b| pattern.matches(tag_b));
+                        if found == *negated {
                             has_match = false;
                             break;
                         } else {
@@ -593,8 +790,8 @@ impl BookmarkManager for BackendCsv {
 
             // Query search — structured query language with field prefixes,
             // boolean operators, quoted phrases, and parenthesized grouping.
-            if let Some(filter) = &parsed_query_filter {
-                if !crate::search_query::eval(filter, bookmark) {
+            if let Some(filter) = filter {
+                if !crate::search_query::eval(filter, bookmark, &query.metadata_sources) {
                     return Ok(false);
                 }
                 has_match = true;
@@ -603,24 +800,69 @@ impl BookmarkManager for BackendCsv {
             Ok(has_match)
         };
 
-        if let Some(id) = constrained_id {
-            if let Some(bookmark) = bmarks.iter().find(|bookmark| bookmark.id == id) {
-                if evaluate_bookmark(bookmark)? {
+        let collect = |filter: Option<&crate::search_query::SearchFilter>| -> anyhow::Result<Vec<Bookmark>> {
+            let mut output = vec![];
+
+            if let Some(id) = constrained_id {
+                if let Some(bookmark) = bmarks.iter().find(|bookmark| bookmark.id == id) {
+                    if evaluate_bookmark(bookmark, filter)? {
+                        output.push(bookmark.clone());
+                    }
+                }
+                return Ok(output);
+            }
+
+            // Fuzzy results are ordered by match quality, so the limit can only
+            // be applied after every candidate has been scored.
+            let fuzzy = filter.is_some_and(crate::search_query::has_fuzzy);
+
+            // Bookmarks the index rules out would fail `eval` anyway
+            let candidates = filter.and_then(|filter| index.candidates(filter));
+
+            for bookmark in bmarks.iter() {
+                if candidates
+                    .as_ref()
+                    .is_some_and(|ids| ids.binary_search(&bookmark.id).is_err())
+                {
+                    continue;
+                }
+
+                if evaluate_bookmark(bookmark, filter)? {
                     output.push(bookmark.clone());
                 }
+
+                let limit_reached = !fuzzy
+                    && query.limit.is_some()
+                    && output.len() >= query.limit.unwrap_or_default();
+                if limit_reached {
+                    break;
+                }
             }
-            return Ok(output);
-        }
 
-        for bookmark in bmarks.iter() {
-            if evaluate_bookmark(bookmark)? {
-                output.push(bookmark.clone());
+            if let (true, Some(filter)) = (fuzzy, filter) {
+                let mut scored: Vec<(f32, Bookmark)> = output
+                    .into_iter()
+                    .map(|b| (crate::search_query::match_quality(filter, &b, &query.metadata_sources), b))
+                    .collect();
+                scored.sort_by(|a, b| b.0.total_cmp(&a.0));
+                output = scored.into_iter().map(|(_, b)| b).collect();
+                if let Some(limit) = query.limit {
+                    output.truncate(limit);
+                }
             }
 
-            let limit_reached =
-                query.limit.is_some() && output.len() >= query.limit.unwrap_or_default();
-            if limit_reached {
-                break;
+            Ok(output)
+        };
+
+        let mut output = collect(parsed_query_filter.as_ref())?;
+
+        if output.is_empty() && query.fuzzy_fallback {
+            if let Some(filter) = &parsed_query_filter {
+                let fuzzy_filter = crate::search_query::fuzzify(filter);
+                if fuzzy_filter != *filter {
+                    log::debug!("no exact matches, retrying with fuzzy query");
+                    output = collect(Some(&fuzzy_filter))?;
+                }
             }
         }
 
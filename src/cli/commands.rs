@@ -1,12 +1,12 @@
 use crate::{
     app::service::AppService,
-    bookmarks::{BookmarkCreate, BookmarkUpdate, SearchQuery},
+    bookmarks::{BookmarkCreate, BookmarkUpdate, SearchQuery, SortDirection},
     images,
     metadata::MetaOptions,
     parse_tags,
     storage::StorageManager,
     cli::{errors::CliResult, validation::{
-        validate_search_query, validate_semantic_params, validate_bookmark_create,
+        validate_search_query, validate_semantic_params, validate_sort_params, validate_bookmark_create,
         validate_tags, validate_url, validate_rule_input,
     }},
 };
@@ -33,9 +33,15 @@ pub struct SearchCommandParams {
     pub query: Option<String>,
     pub id: Option<u64>,
     pub exact: bool,
+    pub fuzzy_fallback: bool,
     pub semantic: Option<String>,
     pub threshold: Option<f32>,
+    pub sort: Option<String>,
+    pub desc: bool,
+    pub page: Option<usize>,
+    pub page_size: usize,
     pub count: bool,
+    pub facets: bool,
     pub action: Option<ActionCommand>,
 }
 
@@ -47,6 +53,18 @@ impl SearchCommand {
         // Validate semantic search parameters
         validate_semantic_params(&params.semantic, &params.threshold)?;
 
+        let sort = validate_sort_params(&params.sort, params.page, params.page_size)?;
+        if params.page.is_some() && params.action.is_some() {
+            return Err(crate::cli::errors::CliError::invalid_input(
+                "--page cannot be combined with update or delete",
+            ));
+        }
+        if params.facets && params.action.is_some() {
+            return Err(crate::cli::errors::CliError::invalid_input(
+                "--facets cannot be combined with update or delete",
+            ));
+        }
+
         let query = SearchQuery {
             id: params.id,
             title: params.title,
@@ -57,7 +75,12 @@ impl SearchCommand {
             exact: params.exact,
             semantic: params.semantic,
             threshold: params.threshold,
-            limit: None,
+            limit: params.page.map(|_| params.page_size),
+            fuzzy_fallback: params.fuzzy_fallback,
+            sort,
+            direction: params.desc.then_some(SortDirection::Desc),
+            offset: params.page.map(|page| (page - 1) * params.page_size),
+            facets: params.facets,
             ..Default::default()
         };
 
@@ -69,6 +92,10 @@ impl SearchCommand {
     }
 
     pub fn execute(self, app_service: AppService) -> CliResult<()> {
+        if self.query.is_paged() {
+            return self.execute_paged(app_service);
+        }
+
         let bmarks = app_service.search_bookmarks(self.query.clone(), self.count_only)
             .map_err(|e| crate::cli::errors::CliError::database(e.to_string()))?;
 
@@ -95,6 +122,48 @@ impl SearchCommand {
             Ok(())
         }
     }
+
+    /// Sorted, paged and/or faceted search. The page goes to stdout, the
+    /// position in the full result set to stderr. With facets, stdout gets
+    /// `{bookmarks, facets}` like the search API.
+    fn execute_paged(self, app_service: AppService) -> CliResult<()> {
+        let page = app_service.search_page(self.query.clone())
+            .map_err(|e| crate::cli::errors::CliError::database(e.to_string()))?;
+
+        if self.count_only {
+            println!("{} bookmarks found", page.total);
+            if let Some(facets) = &page.facets {
+                println!("{}", serde_json::to_string_pretty(facets)
+                    .map_err(|e| crate::cli::errors::CliError::invalid_input(e.to_string()))?);
+            }
+            return Ok(());
+        }
+
+        if let Some(action) = self.action {
+            if page.bookmarks.is_empty() {
+                println!("0 items updated");
+                return Ok(());
+            }
+            return action.execute(page.bookmarks, self.query, app_service);
+        }
+
+        let first = self.query.offset.unwrap_or(0);
+        if page.bookmarks.is_empty() {
+            eprintln!("no results at offset {} ({} total)", first, page.total);
+        } else {
+            eprintln!("{}-{} of {}", first + 1, first + page.bookmarks.len(), page.total);
+        }
+        let output = match &page.facets {
+            Some(facets) => serde_json::to_string_pretty(&serde_json::json!({
+                "bookmarks": page.bookmarks,
+                "facets": facets,
+            })),
+            None => serde_json::to_string_pretty(&page.bookmarks),
+        };
+        println!("{}", output
+            .map_err(|e| crate::cli::errors::CliError::invalid_input(e.to_string()))?);
+        Ok(())
+    }
 }
 
 /// Command for adding bookmarks
@@ -159,9 +228,11 @@ impl AddCommand {
                 .map_err(|e| crate::cli::errors::CliError::configuration(e.to_string()))?;
             let rules_guard = rules_config.read().unwrap();
             let rules = rules_guard.rules();
+            let saved = app_service.saved_search_map();
 
             if let Some(u) = url {
-                for rule in rules.iter() {
+                let triggers = [crate::rules::Trigger::OnCreate, crate::rules::Trigger::AfterMetadata];
+                for (_, rule) in crate::rules::in_order(rules, &triggers) {
                     let record = crate::rules::Record {
                         url: u.clone(),
                         title: title.clone(),
@@ -169,30 +240,35 @@ impl AddCommand {
                         tags: tags.clone().map(parse_tags),
                     };
 
-                    if !rule.is_match(&record) {
+                    if !rule.is_match(&record, &saved) {
                         continue;
                     }
 
-                    match &rule.action {
-                        crate::rules::Action::UpdateBookmark {
-                            title: rule_title,
-                            description: rule_description,
-                            tags: rule_tags,
-                        } => {
-                            if let Some(title) = rule_title {
-                                editor_defaults.title = Some(title.clone());
-                            }
-                            if let Some(description) = rule_description {
-                                editor_defaults.description = Some(description.clone());
-                            }
-                            if let Some(tags) = rule_tags {
-                                let mut curr_tags = editor_defaults.tags
-                                    .map(parse_tags)
-                                    .unwrap_or_default();
-                                curr_tags.append(&mut tags.clone());
-                                editor_defaults.tags = Some(curr_tags.join(" "));
-                            }
+                    // the other actions run once the bookmark is saved
+                    if let crate::rules::Action::UpdateBookmark {
+                        title: rule_title,
+                        description: rule_description,
+                        tags: rule_tags,
+                    } = &rule.action
+                    {
+                        let ctx = rule.template_context(&record);
+                        if let Some(title) = rule_title {
+                            editor_defaults.title = Some(ctx.render(title));
+                        }
+                        if let Some(description) = rule_description {
+                            editor_defaults.description = Some(ctx.render(description));
                         }
+                        if let Some(tags) = rule_tags {
+                            let mut curr_tags = editor_defaults.tags
+                                .map(parse_tags)
+                                .unwrap_or_default();
+                            curr_tags.extend(tags.iter().map(|tag| ctx.render(tag)).filter(|tag| !tag.is_empty()));
+                            editor_defaults.tags = Some(curr_tags.join(" "));
+                        }
+                    }
+
+                    if rule.stop {
+                        break;
                     }
                 }
             }
@@ -337,9 +413,14 @@ pub enum RuleAction {
         title: Option<String>,
         description: Option<String>,
         tags: Option<String>,
+        query: Option<String>,
+        comment: Option<String>,
         update_action: RuleUpdateAction,
     },
-    Delete,
+    Delete { id: u64 },
+    Edit,
+    Move { id: u64, position: usize },
+    SetEnabled { id: u64, enabled: bool },
     List,
 }
 
@@ -353,48 +434,108 @@ pub struct RuleUpdateAction {
 impl RuleCommand {
     pub fn new(action: RuleAction) -> CliResult<Self> {
         // Validate rule input if it's an Add action
-        if let RuleAction::Add { url, title, description, tags, .. } = &action {
-            validate_rule_input(url, title, description, tags)?;
+        if let RuleAction::Add { url, title, description, tags, query, .. } = &action {
+            validate_rule_input(url, title, description, tags, query)?;
         }
         
         Ok(Self { action })
     }
 
-    pub fn execute(self, rules_config: &mut crate::config::RulesConfig) -> CliResult<()> {
+    /// `saved` resolves `@name` references in rule queries.
+    pub fn execute(
+        self,
+        rules_config: &mut crate::config::RulesConfig,
+        saved: &crate::search_query::SavedSearches,
+    ) -> CliResult<()> {
         match self.action {
             RuleAction::Add {
                 url,
                 title,
                 description,
                 tags,
+                query,
+                comment,
                 update_action,
             } => {
                 let rule = crate::rules::Rule {
-                    url: url.map(|u| u.to_lowercase()),
-                    description: description.map(|d| d.to_lowercase()),
-                    title: title.map(|d| d.to_lowercase()),
+                    // Kept as typed: plain patterns match case-insensitively
+                    // anyway, and lowercasing would change regex classes
+                    // like `\D` and `${Name}` group references
+                    url,
+                    description,
+                    title,
                     tags: tags.clone().map(parse_tags),
-                    query: None,
+                    query,
                     action: crate::rules::Action::UpdateBookmark {
-                        title: update_action.title.map(|u| u.to_lowercase()),
-                        description: update_action.description.map(|d| d.to_lowercase()),
+                        title: update_action.title,
+                        description: update_action.description,
                         tags: update_action.tags.clone().map(parse_tags),
                     },
-                    comment: None,
+                    comment,
+                    when: vec![],
+                    stop: false,
+                    priority: 0,
+                    id: 0,
+                    enabled: true,
+                    compiled: Default::default(),
                 };
+                if let Some(query) = &rule.query {
+                    crate::search_query::parse_with(query, saved)
+                        .map_err(|e| crate::cli::errors::CliError::validation("query", e.to_string()))?;
+                }
+                rule.compile()
+                    .map_err(|e| crate::cli::errors::CliError::validation("rule", e))?;
                 rules_config.rules_mut().insert(0, rule);
+                rules_config.assign_ids();
                 rules_config.save()
                     .map_err(|e| crate::cli::errors::CliError::storage(e.to_string()))?;
+                println!("Added rule {}", rules_config.rules()[0].id);
             }
-            RuleAction::Delete => {
-                return Err(crate::cli::errors::CliError::not_supported("Delete rule"));
+            RuleAction::Delete { id } => {
+                let idx = rule_position(rules_config, id)?;
+                rules_config.rules_mut().remove(idx);
+                rules_config.save()
+                    .map_err(|e| crate::cli::errors::CliError::storage(e.to_string()))?;
+            }
+            RuleAction::Edit => {
+                let Some(edited) = edit_rules(rules_config, saved)? else {
+                    println!("Rules left unchanged");
+                    return Ok(());
+                };
+                *rules_config.rules_mut() = edited.rules;
+                rules_config.assign_ids();
+                rules_config.save()
+                    .map_err(|e| crate::cli::errors::CliError::storage(e.to_string()))?;
+            }
+            RuleAction::Move { id, position } => {
+                let idx = rule_position(rules_config, id)?;
+                let len = rules_config.rules().len();
+                if position == 0 || position > len {
+                    return Err(crate::cli::errors::CliError::invalid_input(format!(
+                        "position must be between 1 and {len}"
+                    )));
+                }
+                let rule = rules_config.rules_mut().remove(idx);
+                rules_config.rules_mut().insert(position - 1, rule);
+                rules_config.save()
+                    .map_err(|e| crate::cli::errors::CliError::storage(e.to_string()))?;
+            }
+            RuleAction::SetEnabled { id, enabled } => {
+                let idx = rule_position(rules_config, id)?;
+                rules_config.rules_mut()[idx].enabled = enabled;
+                rules_config.save()
+                    .map_err(|e| crate::cli::errors::CliError::storage(e.to_string()))?;
             }
             RuleAction::List => {
-                for (idx, rule) in rules_config.rules().iter().enumerate() {
+                for rule in rules_config.rules() {
+                    let disabled = if rule.enabled { "" } else { " (disabled)" };
                     if let Some(comment) = &rule.comment {
-                        println!("Rule #{} // {comment}", idx + 1);
+                        println!("Rule {}{disabled} // {comment}", rule.id);
                     } else {
-                        println!("Rule #{}", idx + 1);
+                        println!("Rule {}{disabled}", rule.id);
+                    }
+                    if let Some(query) = &rule.query {
+                        println!("  query: {query:#?}");
                     }
                     if let Some(url) = &rule.url {
                         println!("  url: {url:#?}");
@@ -408,6 +549,15 @@ impl RuleCommand {
                     if let Some(tags) = &rule.tags {
                         println!("  tags: {tags:?}");
                     }
+                    if !rule.when.is_empty() {
+                        println!("  when: {:?}", rule.when);
+                    }
+                    if rule.priority != 0 {
+                        println!("  priority: {}", rule.priority);
+                    }
+                    if rule.stop {
+                        println!("  stop: true");
+                    }
 
                     match &rule.action {
                         crate::rules::Action::UpdateBookmark {
@@ -426,6 +576,21 @@ impl RuleCommand {
                                 println!("    tags: {tags:?}");
                             }
                         }
+                        crate::rules::Action::RemoveTags { tags } => {
+                            println!("  RemoveTags: {tags:?}");
+                        }
+                        crate::rules::Action::RewriteUrl { pattern, replacement } => {
+                            println!("  RewriteUrl: {pattern:?} -> {replacement:?}");
+                        }
+                        crate::rules::Action::ReplaceDescription { pattern, replacement } => {
+                            match pattern {
+                                Some(pattern) => println!("  ReplaceDescription: {pattern:?} -> {replacement:?}"),
+                                None => println!("  ReplaceDescription: {replacement:?}"),
+                            }
+                        }
+                        crate::rules::Action::Delete => println!("  Delete"),
+                        crate::rules::Action::SkipMetadata => println!("  SkipMetadata"),
+                        crate::rules::Action::ForceHeadless => println!("  ForceHeadless"),
                     }
                     println!();
                 }
@@ -435,6 +600,63 @@ impl RuleCommand {
     }
 }
 
+fn rule_position(rules_config: &crate::config::RulesConfig, id: u64) -> CliResult<usize> {
+    rules_config
+        .position(id)
+        .ok_or_else(|| crate::cli::errors::CliError::invalid_input(format!("rule {id} not found")))
+}
+
+/// Open the rules in $EDITOR until they parse and validate, or the user
+/// gives up. Returns `None` when nothing should be saved.
+fn edit_rules(
+    rules_config: &crate::config::RulesConfig,
+    saved: &crate::search_query::SavedSearches,
+) -> CliResult<Option<crate::config::RulesConfig>> {
+    let original = serde_yml::to_string(rules_config)
+        .map_err(|e| crate::cli::errors::CliError::configuration(e.to_string()))?;
+    let mut content = original.clone();
+
+    loop {
+        let edited = crate::editor::edit_text(&content, "yaml")?;
+        if edited == original {
+            return Ok(None);
+        }
+
+        let errors = match serde_yml::from_str::<crate::config::RulesConfig>(&edited) {
+            Ok(parsed) => match parsed.validate(saved) {
+                Ok(()) => return Ok(Some(parsed)),
+                Err(errors) => errors,
+            },
+            Err(err) => vec![err.to_string()],
+        };
+
+        for error in &errors {
+            eprintln!("{error}");
+        }
+        match inquire::prompt_confirmation("rules.yaml is invalid. Reopen the editor?") {
+            Ok(true) => {}
+            Ok(false) => return Ok(None),
+            Err(e) => return Err(crate::cli::errors::CliError::invalid_input(e.to_string())),
+        }
+
+        // errors go on top as comments; drop the ones from the previous round
+        let body = edited
+            .lines()
+            .skip_while(|line| line.starts_with(EDIT_ERROR_PREFIX))
+            .collect::<Vec<_>>()
+            .join("\n");
+        content = errors
+            .iter()
+            .flat_map(|error| error.lines())
+            .map(|line| format!("{EDIT_ERROR_PREFIX}{line}\n"))
+            .collect::<String>()
+            + &body
+            + "\n";
+    }
+}
+
+const EDIT_ERROR_PREFIX: &str = "# ERROR: ";
+
 /// Command for actions on search results
 #[derive(Debug, Clone)]
 pub enum ActionCommand {
@@ -830,9 +1052,15 @@ mod tests {
             query: None,
             id: None,
             exact: false,
+            fuzzy_fallback: false,
             semantic: Some("machine learning AI".to_string()),
             threshold: None,
+            sort: None,
+            desc: false,
+            page: None,
+            page_size: 50,
             count: false,
+            facets: false,
             action: None,
         };
 
@@ -855,9 +1083,15 @@ mod tests {
             query: None,
             id: None,
             exact: false,
+            fuzzy_fallback: false,
             semantic: Some("test query".to_string()),
             threshold: Some(0.7),
+            sort: None,
+            desc: false,
+            page: None,
+            page_size: 50,
             count: false,
+            facets: false,
             action: None,
         };
 
@@ -880,9 +1114,15 @@ mod tests {
             query: None,
             id: None,
             exact: false,
+            fuzzy_fallback: false,
             semantic: Some("systems programming".to_string()),
             threshold: Some(0.5),
+            sort: None,
+            desc: false,
+            page: None,
+            page_size: 50,
             count: false,
+            facets: false,
             action: None,
         };
 
@@ -905,9 +1145,15 @@ mod tests {
             query: None,
             id: None,
             exact: false,
+            fuzzy_fallback: false,
             semantic: Some("query".to_string()),
             threshold: Some(1.5), // Invalid: > 1.0
+            sort: None,
+            desc: false,
+            page: None,
+            page_size: 50,
             count: false,
+            facets: false,
             action: None,
         };
 
@@ -925,9 +1171,15 @@ mod tests {
             query: None,
             id: None,
             exact: false,
+            fuzzy_fallback: false,
             semantic: Some("query".to_string()),
             threshold: Some(-0.5), // Invalid: < 0.0
+            sort: None,
+            desc: false,
+            page: None,
+            page_size: 50,
             count: false,
+            facets: false,
             action: None,
         };
 
@@ -945,9 +1197,15 @@ mod tests {
             query: None,
             id: None,
             exact: false,
+            fuzzy_fallback: false,
             semantic: None,
             threshold: None,
+            sort: None,
+            desc: false,
+            page: None,
+            page_size: 50,
             count: false,
+            facets: false,
             action: None,
         };
 
@@ -956,4 +1214,44 @@ mod tests {
         assert!(cmd.query.semantic.is_none(), "No semantic should be set");
         assert!(cmd.query.threshold.is_none(), "No threshold should be set");
     }
+
+    #[test]
+    fn test_rule_add_keeps_case_for_named_groups() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut rules_config = crate::config::RulesConfig::load_with(dir.path().to_str().unwrap()).unwrap();
+
+        let cmd = RuleCommand::new(RuleAction::Add {
+            url: Some(r"r/github\.com/(?P<Owner>\S+)/(?P<Repo>[^/]+)/".to_string()),
+            title: None,
+            description: None,
+            tags: None,
+            query: None,
+            comment: None,
+            update_action: RuleUpdateAction {
+                title: Some("${Owner}/${Repo} on GitHub".to_string()),
+                description: None,
+                tags: Some("gh".to_string()),
+            },
+        })
+        .unwrap();
+        cmd.execute(&mut rules_config, &Default::default()).unwrap();
+
+        let bmark = crate::bookmarks::Bookmark {
+            id: 1,
+            url: "https://github.com/Rust-Lang/Cargo".to_string(),
+            title: String::new(),
+            description: String::new(),
+            tags: vec![],
+            image_id: None,
+            icon_id: None,
+        };
+        let outcome = crate::app::local::AppLocal::evaluate_rules(
+            &bmark,
+            rules_config.rules(),
+            &Default::default(),
+            &[crate::rules::Trigger::OnCreate],
+        )
+        .unwrap();
+        assert_eq!(outcome.update.unwrap().title.as_deref(), Some("Rust-Lang/Cargo on GitHub"));
+    }
 }
@@ -48,10 +48,6 @@ impl CliError {
         }
     }
 
-    pub fn not_supported(operation: impl Into<String>) -> Self {
-        Self::NotSupported { operation: operation.into() }
-    }
-
     pub fn database(message: impl Into<String>) -> Self {
         Self::Database { message: message.into() }
     }
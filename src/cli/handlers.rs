@@ -1,7 +1,12 @@
 use crate::{
-    app::service::AppService,
-    bookmarks::{BookmarkManager, SearchQuery},
-    config::{Config, RulesConfig},
+    app::{
+        backend::{RuleApplyOpts, RuleChange},
+        local::AppLocal,
+        service::AppService,
+    },
+    bookmarks::{Bookmark, BookmarkManager, SearchQuery},
+    config::Config,
+    rules::Trigger,
     storage::StorageManager,
     cli::commands::{SearchCommand, AddCommand, MetaCommand, RuleCommand, CompressCommand, AddOptions, RuleAction, RuleUpdateAction, SearchCommandParams},
 };
@@ -20,9 +25,15 @@ pub struct SearchParams {
     pub query: Option<String>,
     pub id: Option<u64>,
     pub exact: bool,
+    pub fuzzy_fallback: bool,
     pub semantic: Option<String>,
     pub threshold: Option<f32>,
+    pub sort: Option<String>,
+    pub desc: bool,
+    pub page: Option<usize>,
+    pub page_size: usize,
     pub count: bool,
+    pub facets: bool,
     pub action: Option<ActionArgs>,
 }
 
@@ -61,9 +72,15 @@ pub fn handle_search(params: SearchParams, app_service: AppService) -> Result<()
         query: params.query,
         id: params.id,
         exact: params.exact,
+        fuzzy_fallback: params.fuzzy_fallback,
         semantic: params.semantic,
         threshold: params.threshold,
+        sort: params.sort,
+        desc: params.desc,
+        page: params.page,
+        page_size: params.page_size,
         count: params.count,
+        facets: params.facets,
         action: action_command,
     })?;
     
@@ -85,27 +102,96 @@ pub fn handle_add(params: AddParams, app_service: AppService) -> Result<()> {
 
 pub fn handle_meta(url: String, no_headless: bool, always_headless: bool, scrape_config: Option<crate::config::ScrapeConfig>, output_dir: Option<String>) -> Result<()> {
     let scrape_config = if always_headless {
-        Some(scrape_config.map(|mut c| { c.always_headless = true; c })
+        Some(scrape_config.map(|mut c| { c.force_headless(); c })
             .unwrap_or_else(|| {
                 let mut c = crate::config::ScrapeConfig::default();
-                c.always_headless = true;
+                c.force_headless();
                 c
             }))
     } else {
         scrape_config
     };
-    let meta_opts = crate::metadata::MetaOptions { no_headless, scrape_config, ..Default::default() };
+    // always ask the fetchers, this is for seeing what they return
+    let meta_opts = crate::metadata::MetaOptions { no_headless, scrape_config, no_cache: true, ..Default::default() };
     let meta_command = MetaCommand::new(url, meta_opts, output_dir)?;
     meta_command.execute().map_err(|e| anyhow::anyhow!(e))
 }
 
-pub fn handle_rule(action: super::types::RulesArgs, rules_config: &mut RulesConfig) -> Result<()> {
+pub fn handle_meta_action(action: super::types::MetaAction, app_service: &AppService) -> Result<()> {
+    use super::types::MetaAction;
+    use crate::metadata::types::FetcherStatus;
+
+    match action {
+        MetaAction::Report { id, all } => {
+            let reports = app_service.metadata_reports(id)?;
+            if reports.is_empty() {
+                eprintln!("no metadata reports stored for bookmark {id}");
+                return Ok(());
+            }
+            let shown = if all { &reports[..] } else { &reports[..1] };
+
+            for stored in shown {
+                eprintln!("{} {} ({}ms)", stored.fetched_at, stored.url, stored.report.duration_ms);
+                for fetcher in &stored.report.fetchers {
+                    let mut status = match &fetcher.status {
+                        FetcherStatus::Success => "ok".to_string(),
+                        FetcherStatus::Skip(reason) => format!("skipped: {reason}"),
+                        FetcherStatus::Error(err) => format!("error: {err}"),
+                    };
+                    if fetcher.cached {
+                        status.push_str(" (cached)");
+                    }
+                    eprintln!("  {:<14} {:>6}ms  {status}", fetcher.name, fetcher.duration_ms);
+                }
+                for decision in &stored.report.field_decisions {
+                    eprintln!("  {} <- {} ({})", decision.field, decision.winner, decision.reason);
+                }
+                if let Some(headless) = stored.report.headless_fallback.as_ref().filter(|h| h.triggered) {
+                    eprintln!("  headless fallback: {}", headless.reason);
+                }
+            }
+
+            println!("{}", serde_json::to_string_pretty(shown)?);
+        }
+    }
+
+    Ok(())
+}
+
+pub fn handle_rule(action: super::types::RulesArgs, app_service: &AppService) -> Result<()> {
     let rule_action = match action {
+        super::types::RulesArgs::Apply { rule, query, dry_run } => {
+            let report = app_service.apply_rules(RuleApplyOpts { rule, query, dry_run })?;
+
+            for change in &report.changes {
+                print_rule_change(change);
+            }
+            match (report.applied, report.changes.len()) {
+                (_, 0) => eprintln!("no bookmarks to change"),
+                (true, n) => eprintln!("changed {n} bookmarks, `bb rule undo` reverts"),
+                (false, n) => eprintln!("would change {n} bookmarks"),
+            }
+            return Ok(());
+        }
+        super::types::RulesArgs::Undo {} => {
+            let count = app_service.undo_rules_apply()?;
+            eprintln!("restored {count} bookmarks");
+            return Ok(());
+        }
+        super::types::RulesArgs::Test { target } => {
+            return test_rules(target, app_service);
+        }
+        super::types::RulesArgs::Suggest { min_support, min_confidence, limit, append } => {
+            let opts = crate::rules::SuggestOpts { min_support, min_confidence, limit };
+            return suggest_rules(opts, append, app_service);
+        }
         super::types::RulesArgs::Add {
             url,
             title,
             description,
             tags,
+            query,
+            comment,
             action,
         } => {
             let update_action = match action {
@@ -125,15 +211,193 @@ pub fn handle_rule(action: super::types::RulesArgs, rules_config: &mut RulesConf
                 title,
                 description,
                 tags,
+                query,
+                comment,
                 update_action,
             }
         }
-        super::types::RulesArgs::Delete {} => RuleAction::Delete,
+        super::types::RulesArgs::Delete { id } => RuleAction::Delete { id },
+        super::types::RulesArgs::Edit {} => RuleAction::Edit,
+        super::types::RulesArgs::Move { id, position } => RuleAction::Move { id, position },
+        super::types::RulesArgs::Enable { id } => RuleAction::SetEnabled { id, enabled: true },
+        super::types::RulesArgs::Disable { id } => RuleAction::SetEnabled { id, enabled: false },
         super::types::RulesArgs::List {} => RuleAction::List,
     };
 
+    let rules_config = app_service.get_rules()?;
+    let mut rules_config = rules_config.write().unwrap();
     let rule_command = RuleCommand::new(rule_action)?;
-    rule_command.execute(rules_config).map_err(|e| anyhow::anyhow!(e))
+    rule_command
+        .execute(&mut rules_config, &app_service.saved_search_map())
+        .map_err(|e| anyhow::anyhow!(e))
+}
+
+/// Evaluate every enabled rule against a bookmark id or a bare URL.
+fn test_rules(target: String, app_service: &AppService) -> Result<()> {
+    let bmark = match target.parse::<u64>() {
+        Ok(id) => app_service
+            .search_bookmarks(SearchQuery { id: Some(id), ..Default::default() }, false)?
+            .into_iter()
+            .next()
+            .ok_or_else(|| anyhow::anyhow!("bookmark {id} not found"))?,
+        Err(_) => Bookmark {
+            url: target,
+            ..Default::default()
+        },
+    };
+
+    let rules_config = app_service.get_rules()?;
+    let rules_config = rules_config.read().unwrap();
+    let rules = rules_config.rules();
+    let saved = app_service.saved_search_map();
+    let outcome = AppLocal::evaluate_rules(&bmark, rules, &saved, &[Trigger::Manual])?;
+
+    if outcome.matched.is_empty() {
+        eprintln!("no rules match");
+        return Ok(());
+    }
+    for idx in &outcome.matched {
+        let rule = &rules[*idx];
+        match &rule.comment {
+            Some(comment) => eprintln!("  matches rule {} // {comment}", rule.id),
+            None => eprintln!("  matches rule {}", rule.id),
+        }
+    }
+
+    match outcome.preview(&bmark) {
+        Some(after) => println!("{}", serde_json::to_string_pretty(&after)?),
+        None => eprintln!("  the bookmark would be deleted"),
+    }
+
+    Ok(())
+}
+
+fn suggest_rules(opts: crate::rules::SuggestOpts, append: bool, app_service: &AppService) -> Result<()> {
+    let bookmarks = app_service.search_bookmarks(SearchQuery::default(), false)?;
+    let rules_config = app_service.get_rules()?;
+    let mut rules_config = rules_config.write().unwrap();
+
+    let suggestions = crate::rules::suggest(&bookmarks, rules_config.rules(), &opts);
+    if suggestions.is_empty() {
+        eprintln!("no suggestions, try a lower --min-support or --min-confidence");
+        return Ok(());
+    }
+
+    let mut accepted = 0;
+    for suggestion in suggestions {
+        eprintln!(
+            "# support {}, confidence {:.0}%",
+            suggestion.support,
+            suggestion.confidence * 100.0
+        );
+        println!("{}", serde_yml::to_string(&[&suggestion.rule])?);
+
+        if append {
+            match inquire::prompt_confirmation("Append this rule?") {
+                Ok(true) => {
+                    rules_config.rules_mut().push(suggestion.rule);
+                    accepted += 1;
+                }
+                Ok(false) => {}
+                Err(err) => return Err(anyhow::anyhow!(err)),
+            }
+        }
+    }
+
+    if accepted > 0 {
+        rules_config.assign_ids();
+        rules_config.save()?;
+        eprintln!("appended {accepted} rules");
+    }
+
+    Ok(())
+}
+
+fn print_rule_change(change: &RuleChange) {
+    let before = &change.before;
+    let rules = change.rules.iter().map(|id| id.to_string()).collect::<Vec<_>>().join(", ");
+    println!("bookmark {} ({}) by rule {rules}", before.id, before.url);
+
+    let Some(after) = &change.after else {
+        println!("  deleted");
+        return;
+    };
+
+    if before.url != after.url {
+        println!("  url: {} -> {}", before.url, after.url);
+    }
+    if before.title != after.title {
+        println!("  title: {:?} -> {:?}", before.title, after.title);
+    }
+    if before.description != after.description {
+        println!("  description: {:?} -> {:?}", before.description, after.description);
+    }
+    if before.tags != after.tags {
+        println!("  tags: {} -> {}", before.tags.join(","), after.tags.join(","));
+    }
+}
+
+pub fn handle_query(action: super::types::QueryArgs, app_service: AppService) -> Result<()> {
+    use super::types::QueryArgs;
+
+    match action {
+        QueryArgs::Explain { query, id } => {
+            let explanation = app_service.explain_query(&query, id)?;
+
+            eprintln!("  normalized: {}", explanation.normalized);
+            if let Some(error) = &explanation.strict_error {
+                eprintln!("  strict:     {error}");
+            }
+            if let Some(bookmark) = &explanation.bookmark {
+                eprintln!("  bookmark {}: {}", bookmark.id, if bookmark.matched { "matches" } else { "no match" });
+                for term in &bookmark.terms {
+                    eprintln!(
+                        "    {} {}{}:{}{} {}",
+                        if term.matched { "+" } else { "-" },
+                        if term.negated { "not " } else { "" },
+                        term.field,
+                        if term.fuzzy { "~" } else { "" },
+                        term.value,
+                        term.fields.join(","),
+                    );
+                }
+            }
+
+            println!("{}", serde_json::to_string_pretty(&explanation)?);
+        }
+    }
+
+    Ok(())
+}
+
+pub fn handle_saved(action: super::types::SavedArgs, app_service: &AppService) -> Result<()> {
+    use super::types::SavedArgs;
+
+    match action {
+        SavedArgs::List {} => {
+            for search in app_service.list_saved_searches()? {
+                match &search.description {
+                    Some(description) => println!("@{} // {description}", search.name),
+                    None => println!("@{}", search.name),
+                }
+                println!("  {}", search.query);
+            }
+        }
+        SavedArgs::Add { name, query, description } => {
+            let search = app_service.create_saved_search(name, query, description)?;
+            println!("saved @{}", search.name);
+        }
+        SavedArgs::Edit { name, rename, query, description } => {
+            let search = app_service.update_saved_search(&name, rename, query, description)?;
+            println!("updated @{}", search.name);
+        }
+        SavedArgs::Delete { name } => {
+            app_service.delete_saved_search(&name)?;
+            println!("deleted @{}", name.trim_start_matches('@'));
+        }
+    }
+
+    Ok(())
 }
 
 pub fn handle_compress<S: StorageManager>(
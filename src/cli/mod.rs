@@ -58,6 +58,10 @@ pub enum Command {
         #[clap(short, long, default_value = "false")]
         exact: bool,
 
+        /// Retry the query with typo-tolerant matching when nothing matches
+        #[clap(long, default_value = "false")]
+        fuzzy_fallback: bool,
+
         /// Semantic search query (find bookmarks by meaning)
         #[clap(short = 's', long = "sem")]
         semantic: Option<String>,
@@ -66,10 +70,30 @@ pub enum Command {
         #[clap(long)]
         threshold: Option<f32>,
 
+        /// Sort results by id, title, domain or relevance
+        #[clap(long)]
+        sort: Option<String>,
+
+        /// Reverse the sort order
+        #[clap(long, default_value = "false")]
+        desc: bool,
+
+        /// Print only this page of results (starting at 1)
+        #[clap(long)]
+        page: Option<usize>,
+
+        /// Results per page
+        #[clap(long, default_value = "50")]
+        page_size: usize,
+
         /// Print the count
         #[clap(short = 'c', long, default_value = "false")]
         count: bool,
 
+        /// Also count tags, domains and flags over all matches
+        #[clap(long, default_value = "false")]
+        facets: bool,
+
         #[clap(subcommand)]
         action: Option<ActionArgs>,
     },
@@ -101,10 +125,14 @@ pub enum Command {
         meta_args: MetaArgs,
     },
     /// Extract metadata from a URL without creating a bookmark (for testing/debugging)
+    #[clap(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
     Meta {
+        #[clap(subcommand)]
+        action: Option<MetaAction>,
+
         /// URL to extract metadata from
-        #[clap(allow_hyphen_values = true)]
-        url: String,
+        #[clap(allow_hyphen_values = true, required = true)]
+        url: Option<String>,
 
         /// Save images/icons to a directory (default: don't save)
         #[clap(long, short = 'o')]
@@ -118,6 +146,16 @@ pub enum Command {
         #[clap(subcommand)]
         action: RulesArgs,
     },
+    /// Inspect search queries
+    Query {
+        #[clap(subcommand)]
+        action: QueryArgs,
+    },
+    /// Manage saved searches, referenced in queries as `@name`
+    Saved {
+        #[clap(subcommand)]
+        action: SavedArgs,
+    },
     /// Compress existing images to WebP format
     Compress {
         /// Show what would be done without making changes
@@ -132,6 +170,10 @@ pub enum Command {
     Backup {
         /// Output path for the archive (default: ./bb-backup-{timestamp}.tar.gz)
         path: Option<std::path::PathBuf>,
+
+        /// Also back up the login cookie jars in cookies/
+        #[clap(long)]
+        include_cookies: bool,
     },
     /// Import data from a backup archive
     Import {
@@ -108,12 +108,149 @@ pub enum RulesArgs {
         #[clap(long)]
         tags: Option<String>,
 
+        /// A search query the bookmark has to match, AND-ed with other conditions
+        #[clap(long)]
+        query: Option<String>,
+
+        /// A note shown next to the rule
+        #[clap(long)]
+        comment: Option<String>,
+
         /// A list of tags bookmark will be matched by (all tags has to match)
         #[clap(subcommand)]
         action: RuleAction,
     },
     /// List all rules.
     List {},
-    /// UNIMPLEMENTED! Edit config.yaml directly.
-    Delete {},
+    /// Delete a rule.
+    Delete {
+        /// Rule id
+        id: u64,
+    },
+    /// Open rules.yaml in $EDITOR.
+    Edit {},
+    /// Move a rule to another position in rules.yaml.
+    Move {
+        /// Rule id
+        id: u64,
+
+        /// New 1-based position
+        position: usize,
+    },
+    /// Enable a disabled rule.
+    Enable {
+        /// Rule id
+        id: u64,
+    },
+    /// Keep a rule in rules.yaml without running it.
+    Disable {
+        /// Rule id
+        id: u64,
+    },
+    /// Suggest rules from tags that follow domains, URL paths or title words.
+    ///
+    /// Each candidate is printed as rules.yaml, preceded by its support (bookmarks with both the
+    /// condition and the tag) and confidence (share of the bookmarks with the condition that have
+    /// the tag). Associations existing rules already produce are left out.
+    Suggest {
+        /// Fewest bookmarks that must share the condition and the tag
+        #[clap(long, default_value = "5")]
+        min_support: usize,
+
+        /// Least share of the bookmarks with the condition that have the tag
+        #[clap(long, default_value = "0.8")]
+        min_confidence: f32,
+
+        /// Most suggestions to show
+        #[clap(long, default_value = "20")]
+        limit: usize,
+
+        /// Ask about each suggestion and append the accepted ones to rules.yaml
+        #[clap(long)]
+        append: bool,
+    },
+    /// Show which rules match a URL or bookmark and what they'd change.
+    Test {
+        /// URL, or id of an existing bookmark
+        target: String,
+    },
+    /// Run rules against existing bookmarks.
+    Apply {
+        /// Only run the rule with this id
+        #[clap(long)]
+        rule: Option<u64>,
+
+        /// Only touch bookmarks matching this query
+        #[clap(long)]
+        query: Option<String>,
+
+        /// Show what would change without writing anything
+        #[clap(long)]
+        dry_run: bool,
+    },
+    /// Revert the last `bb rule apply`.
+    Undo {},
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum MetaAction {
+    /// Show the stored metadata reports of a bookmark: which fetchers ran and
+    /// which one supplied each field.
+    Report {
+        /// Bookmark id
+        id: u64,
+
+        /// Show every stored report instead of only the latest
+        #[clap(long)]
+        all: bool,
+    },
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum QueryArgs {
+    /// Show how a query parses and which of its terms match a bookmark.
+    Explain {
+        /// Search query
+        query: String,
+
+        /// Bookmark to match the query against
+        #[clap(long)]
+        id: Option<u64>,
+    },
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum SavedArgs {
+    /// List saved searches.
+    List {},
+    /// Save a query under a name.
+    Add {
+        /// Name used to reference the search (`@name`)
+        name: String,
+
+        /// Search query
+        query: String,
+
+        #[clap(short, long)]
+        description: Option<String>,
+    },
+    /// Change a saved search.
+    Edit {
+        name: String,
+
+        /// Rename the search
+        #[clap(long)]
+        rename: Option<String>,
+
+        /// Replace the query
+        #[clap(short = 'k', long)]
+        query: Option<String>,
+
+        #[clap(short, long)]
+        description: Option<String>,
+    },
+    /// Delete a saved search.
+    Delete {
+        name: String,
+    },
 }
@@ -1,3 +1,4 @@
+use crate::bookmarks::SortField;
 use crate::cli::errors::{CliError, CliResult};
 use url::Url;
 
@@ -144,12 +145,32 @@ pub fn validate_semantic_params(
     Ok(())
 }
 
+/// Validates search sort field and paging
+pub fn validate_sort_params(
+    sort: &Option<String>,
+    page: Option<usize>,
+    page_size: usize,
+) -> CliResult<Option<SortField>> {
+    if page == Some(0) {
+        return Err(CliError::validation("page", "Pages are numbered from 1"));
+    }
+    if page_size == 0 {
+        return Err(CliError::validation("page-size", "Page size must be at least 1"));
+    }
+
+    sort.as_deref()
+        .map(|s| s.parse::<SortField>())
+        .transpose()
+        .map_err(|e| CliError::validation("sort", e))
+}
+
 /// Validates rule input
 pub fn validate_rule_input(
     url: &Option<String>,
     title: &Option<String>,
     description: &Option<String>,
     tags: &Option<String>,
+    query: &Option<String>,
 ) -> CliResult<()> {
     if let Some(url) = url {
         if !url.trim().is_empty() {
@@ -179,8 +200,8 @@ pub fn validate_rule_input(
     }
 
     // At least one field must be specified
-    if url.is_none() && title.is_none() && description.is_none() && tags.is_none() {
-        return Err(CliError::validation("rule", "At least one field (url, title, description, or tags) must be specified"));
+    if url.is_none() && title.is_none() && description.is_none() && tags.is_none() && query.is_none() {
+        return Err(CliError::validation("rule", "At least one field (url, title, description, tags, or query) must be specified"));
     }
 
     Ok(())
@@ -190,6 +211,26 @@ pub fn validate_rule_input(
 mod tests {
     use super::*;
 
+    // =========================================================================
+    // Sort and paging validation tests
+    // =========================================================================
+
+    #[test]
+    fn test_validate_sort_params() {
+        assert_eq!(validate_sort_params(&None, None, 50).unwrap(), None);
+        assert_eq!(
+            validate_sort_params(&Some("Title".to_string()), Some(2), 10).unwrap(),
+            Some(SortField::Title)
+        );
+
+        let err = validate_sort_params(&Some("date".to_string()), None, 50).unwrap_err();
+        assert!(matches!(err, CliError::Validation { field, .. } if field == "sort"));
+        let err = validate_sort_params(&None, Some(0), 50).unwrap_err();
+        assert!(matches!(err, CliError::Validation { field, .. } if field == "page"));
+        let err = validate_sort_params(&None, Some(1), 0).unwrap_err();
+        assert!(matches!(err, CliError::Validation { field, .. } if field == "page-size"));
+    }
+
     // =========================================================================
     // Semantic parameter validation tests (E.4)
     // =========================================================================
@@ -1,10 +1,14 @@
 use crate::{
     rules::Rule,
+    saved_searches::SavedSearchStore,
+    search_query::SavedSearches,
     storage::{self, StorageManager},
 };
 use anyhow::Context;
 use serde::{Deserialize, Serialize};
 use serde_yml::Value;
+use std::collections::BTreeMap;
+use std::time::Duration;
 
 const TASK_QUEUE_MAX_THREADS: u16 = 4;
 const DEFAULT_TASK_QUEUE_MAX_RETRIES: u8 = 3;
@@ -50,6 +54,232 @@ pub struct ScrapeConfig {
     /// Absent or empty = default order.
     #[serde(default = "default_fetcher_order")]
     pub fetcher_order: Vec<String>,
+
+    /// Tag bookmarks with what site extractors find, e.g. a GitHub repo's
+    /// language and topics or a question's tags (default: false)
+    #[serde(default)]
+    pub site_tags: bool,
+
+    /// Metadata reports kept per bookmark in `metadata_reports.json`, newest
+    /// first; 0 keeps none (default: 5)
+    #[serde(default = "default_report_history")]
+    pub report_history: usize,
+
+    /// How long fetcher results stay in the metadata cache, in seconds; 0
+    /// disables it (default: 86400)
+    #[serde(default = "default_cache_ttl_secs")]
+    pub cache_ttl_secs: u64,
+
+    /// Requests in flight to one host at a time, across task threads and
+    /// fetchers; 0 is unlimited (default: 2)
+    #[serde(default = "default_host_concurrency")]
+    pub host_concurrency: usize,
+
+    /// Minimum milliseconds between the starts of two requests to one host
+    /// (default: 500)
+    #[serde(default = "default_host_interval_ms")]
+    pub host_interval_ms: u64,
+
+    /// Longest `Retry-After` a host gets to hold its requests back, in
+    /// seconds (default: 300)
+    #[serde(default = "default_max_retry_after_secs")]
+    pub max_retry_after_secs: u64,
+
+    /// Skip the Plain and headless fetches robots.txt disallows, and keep no
+    /// images or snapshots of pages marked `noimageindex`/`noarchive`
+    /// (default: false)
+    #[serde(default)]
+    pub respect_robots: bool,
+
+    /// Per-host overrides, keyed by host pattern: `example.com` matches the
+    /// host and its subdomains, `*.example.com` only its subdomains. The
+    /// longest matching pattern wins.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub domains: BTreeMap<String, DomainScrapeConfig>,
+
+    /// External commands that print a bookmark's metadata as JSON, keyed by
+    /// the name `fetcher_order` refers to them by
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub plugins: BTreeMap<String, PluginConfig>,
+
+    /// Where the per-domain cookie jars live (`<data dir>/cookies`), set on
+    /// load. Never serialized, so it stays out of config.yaml and the API.
+    #[serde(skip)]
+    pub cookies_dir: Option<std::path::PathBuf>,
+
+    /// Where cached fetcher results live (`<data dir>/cache/metadata`), set
+    /// on load like `cookies_dir`.
+    #[serde(skip)]
+    pub cache_dir: Option<std::path::PathBuf>,
+}
+
+/// Scrape settings for the hosts matching a `scrape.domains` pattern. Unset
+/// fields fall back to the global ones.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct DomainScrapeConfig {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fetcher_order: Option<Vec<String>>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub always_headless: Option<bool>,
+
+    /// Proxy for every request, instead of `OPT_PROXY` only after a failure
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proxy: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_agent: Option<String>,
+
+    /// Extra request headers
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub headers: BTreeMap<String, String>,
+
+    /// Request timeout in seconds (default: 10)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_secs: Option<u64>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub host_concurrency: Option<usize>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub host_interval_ms: Option<u64>,
+}
+
+/// A fetcher plugin: `command` runs with `args` and the URL as its last
+/// argument, and prints a metadata JSON object, or `null`, on stdout.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PluginConfig {
+    pub command: String,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub args: Vec<String>,
+
+    /// Regexes of the URLs the plugin handles; empty matches every URL
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub urls: Vec<String>,
+
+    /// Seconds before the command is killed (default: 10)
+    #[serde(default = "default_plugin_timeout_secs")]
+    pub timeout_secs: u64,
+
+    /// Largest output read from stdout (default: 1 MiB)
+    #[serde(default = "default_plugin_max_output_bytes")]
+    pub max_output_bytes: usize,
+}
+
+fn default_plugin_timeout_secs() -> u64 {
+    10
+}
+
+fn default_plugin_max_output_bytes() -> usize {
+    1024 * 1024
+}
+
+/// Most plugins a config may define, as all of them can run in parallel.
+pub const MAX_PLUGINS: usize = 8;
+
+impl ScrapeConfig {
+    /// The `domains` override for the host of `url`, if any.
+    pub fn domain(&self, url: &str) -> Option<&DomainScrapeConfig> {
+        let host = reqwest::Url::parse(url).ok()?.host_str()?.to_lowercase();
+        self.domains
+            .iter()
+            .filter(|(pattern, _)| host_matches(pattern, &host))
+            .max_by_key(|(pattern, _)| pattern.len())
+            .map(|(_, domain)| domain)
+    }
+
+    pub fn fetcher_order_for(&self, url: &str) -> Vec<String> {
+        self.domain(url)
+            .and_then(|d| d.fetcher_order.clone())
+            .unwrap_or_else(|| self.fetcher_order.clone())
+    }
+
+    pub fn always_headless_for(&self, url: &str) -> bool {
+        self.domain(url)
+            .and_then(|d| d.always_headless)
+            .unwrap_or(self.always_headless)
+    }
+
+    pub fn host_concurrency_for(&self, url: &str) -> usize {
+        self.domain(url)
+            .and_then(|d| d.host_concurrency)
+            .unwrap_or(self.host_concurrency)
+    }
+
+    pub fn host_interval_for(&self, url: &str) -> Duration {
+        let ms = self
+            .domain(url)
+            .and_then(|d| d.host_interval_ms)
+            .unwrap_or(self.host_interval_ms);
+        Duration::from_millis(ms)
+    }
+
+    /// Run headless for every host, whatever `domains` says.
+    pub fn force_headless(&mut self) {
+        self.always_headless = true;
+        for domain in self.domains.values_mut() {
+            domain.always_headless = None;
+        }
+    }
+
+    fn validate_plugins(&self, errors: &mut Vec<String>) {
+        if self.plugins.len() > MAX_PLUGINS {
+            errors.push(format!("scrape.plugins: at most {MAX_PLUGINS} plugins are supported"));
+        }
+        for (name, plugin) in &self.plugins {
+            let key = format!("scrape.plugins.{name}");
+            if default_fetcher_order().contains(name) || name == "Headless" {
+                errors.push(format!("{key}: name is taken by a built-in fetcher"));
+            }
+            if plugin.command.trim().is_empty() {
+                errors.push(format!("{key}.command must not be empty"));
+            }
+            if plugin.timeout_secs == 0 {
+                errors.push(format!("{key}.timeout_secs must be greater than 0"));
+            }
+            if plugin.max_output_bytes == 0 {
+                errors.push(format!("{key}.max_output_bytes must be greater than 0"));
+            }
+            for pattern in &plugin.urls {
+                if let Err(err) = regex::Regex::new(pattern) {
+                    errors.push(format!("{key}.urls: invalid pattern '{pattern}': {err}"));
+                }
+            }
+        }
+    }
+
+    fn validate_domains(&self, errors: &mut Vec<String>) {
+        for (pattern, domain) in &self.domains {
+            let key = format!("scrape.domains.{pattern}");
+            if pattern.trim_start_matches("*.").is_empty() || pattern.contains(['/', ':']) {
+                errors.push(format!("{key}: expected a host like example.com or *.example.com"));
+            }
+            if domain.timeout_secs == Some(0) {
+                errors.push(format!("{key}.timeout_secs must be greater than 0"));
+            }
+            if let Some(proxy) = &domain.proxy {
+                if let Err(err) = reqwest::Proxy::all(proxy) {
+                    errors.push(format!("{key}.proxy is invalid: {err}"));
+                }
+            }
+            for (name, value) in &domain.headers {
+                if reqwest::header::HeaderName::from_bytes(name.as_bytes()).is_err()
+                    || reqwest::header::HeaderValue::from_str(value).is_err()
+                {
+                    errors.push(format!("{key}.headers: invalid header '{name}'"));
+                }
+            }
+        }
+    }
+}
+
+fn host_matches(pattern: &str, host: &str) -> bool {
+    let pattern = pattern.to_lowercase();
+    match pattern.strip_prefix("*.") {
+        Some(parent) => host.ends_with(&format!(".{parent}")),
+        None => host == pattern || host.ends_with(&format!(".{pattern}")),
+    }
 }
 
 impl Default for ScrapeConfig {
@@ -62,16 +292,47 @@ impl Default for ScrapeConfig {
             always_headless: false,
             test_chrome_on_startup: true,
             fetcher_order: default_fetcher_order(),
+            site_tags: false,
+            report_history: default_report_history(),
+            cache_ttl_secs: default_cache_ttl_secs(),
+            host_concurrency: default_host_concurrency(),
+            host_interval_ms: default_host_interval_ms(),
+            max_retry_after_secs: default_max_retry_after_secs(),
+            respect_robots: false,
+            domains: BTreeMap::new(),
+            plugins: BTreeMap::new(),
+            cookies_dir: None,
+            cache_dir: None,
         }
     }
 }
 
+fn default_report_history() -> usize {
+    5
+}
+
+fn default_cache_ttl_secs() -> u64 {
+    24 * 60 * 60
+}
+
+fn default_host_concurrency() -> usize {
+    2
+}
+
+fn default_host_interval_ms() -> u64 {
+    500
+}
+
+fn default_max_retry_after_secs() -> u64 {
+    300
+}
+
 fn default_test_chrome_on_startup() -> bool {
     true
 }
 
 pub fn default_fetcher_order() -> Vec<String> {
-    ["oEmbed", "Wayback", "Plain", "Microlink", "Peekalink", "Iframely", "DDG"]
+    ["Site", "oEmbed", "Wayback", "Plain", "Microlink", "Peekalink", "Iframely", "DDG"]
         .iter()
         .map(|s| s.to_string())
         .collect()
@@ -212,6 +473,18 @@ impl Default for Config {
 }
 
 impl Config {
+    pub fn base_path(&self) -> &str {
+        &self.base_path
+    }
+
+    /// Point the config, and the paths derived from it, at `base_path`.
+    pub fn set_base_path(&mut self, base_path: &str) {
+        self.base_path = base_path.to_string();
+        let base = std::path::Path::new(base_path);
+        self.scrape.cookies_dir = Some(base.join(crate::scrape::cookies::COOKIES_DIR));
+        self.scrape.cache_dir = Some(base.join(crate::metadata::cache::CACHE_DIR));
+    }
+
     pub fn validate(&self) -> Result<(), Vec<String>> {
         let mut errors = Vec::new();
 
@@ -266,6 +539,9 @@ impl Config {
             ));
         }
 
+        self.scrape.validate_domains(&mut errors);
+        self.scrape.validate_plugins(&mut errors);
+
         if errors.is_empty() {
             Ok(())
         } else {
@@ -291,7 +567,7 @@ impl Config {
         let mut config: Self =
             serde_yml::from_str(&config_str).context("config is malformed")?;
 
-        config.base_path = base_path.to_string();
+        config.set_base_path(base_path);
 
         if let Err(errors) = config.validate() {
             anyhow::bail!("config validation failed:\n{}", errors.join("\n"));
@@ -368,10 +644,16 @@ impl RulesConfig {
                 serde_yml::from_str(&rules_str).context("rules.yaml is malformed")?;
             rules_config.base_path = base_path.to_string();
 
-            if let Err(errors) = rules_config.validate() {
+            // rule queries can reference the saved searches next to them
+            let saved = SavedSearchStore::load(base_path).context("failed to load saved searches")?;
+            if let Err(errors) = rules_config.validate(&saved.map()) {
                 anyhow::bail!("rules validation failed:\n{}", errors.join("\n"));
             }
 
+            if rules_config.assign_ids() {
+                rules_config.save().context("failed to write rule ids to rules.yaml")?;
+            }
+
             return Ok(rules_config);
         }
 
@@ -394,10 +676,11 @@ impl RulesConfig {
                     let rules: Vec<Rule> = serde_yml::from_value(rules_value)
                         .unwrap_or_default();
 
-                    let rules_config = Self {
+                    let mut rules_config = Self {
                         rules,
                         base_path: base_path.to_string(),
                     };
+                    rules_config.assign_ids();
 
                     // Save rules.yaml
                     rules_config.save().context("failed to write migrated rules.yaml")?;
@@ -442,10 +725,36 @@ impl RulesConfig {
         &mut self.rules
     }
 
-    pub fn validate(&self) -> Result<(), Vec<String>> {
+    /// Give each rule without an id the next unused one. Returns whether any
+    /// id was assigned.
+    pub fn assign_ids(&mut self) -> bool {
+        let next_id = self.rules.iter().map(|rule| rule.id).max().unwrap_or(0) + 1;
+        let mut assigned = false;
+
+        for (rule, id) in self.rules.iter_mut().filter(|rule| rule.id == 0).zip(next_id..) {
+            rule.id = id;
+            assigned = true;
+        }
+
+        assigned
+    }
+
+    /// Index of the rule with `id`.
+    pub fn position(&self, id: u64) -> Option<usize> {
+        self.rules.iter().position(|rule| rule.id == id)
+    }
+
+    /// Check every rule; `saved` resolves `@name` references in queries.
+    pub fn validate(&self, saved: &SavedSearches) -> Result<(), Vec<String>> {
         let mut errors = Vec::new();
+        let mut ids = std::collections::HashSet::new();
 
         for (idx, rule) in self.rules.iter().enumerate() {
+            if rule.id != 0 && !ids.insert(rule.id) {
+                let idx = idx + 1;
+                errors.push(format!("rule #{idx} reuses id {}", rule.id));
+            }
+
             if rule.url.is_none()
                 && rule.title.is_none()
                 && rule.description.is_none()
@@ -457,15 +766,26 @@ impl RulesConfig {
             }
 
             if let Some(q) = &rule.query {
-                if let Err(err) = crate::search_query::parse(q) {
+                if let Err(err) = crate::search_query::parse_with(q, saved) {
                     let idx = idx + 1;
                     errors.push(format!("rule #{idx} has invalid query: {err}"));
                 }
             }
 
-            Rule::is_string_matches(&rule.url.clone().unwrap_or_default(), "");
-            Rule::is_string_matches(&rule.title.clone().unwrap_or_default(), "");
-            Rule::is_string_matches(&rule.description.clone().unwrap_or_default(), "");
+            if let Err(err) = rule.action.validate() {
+                let idx = idx + 1;
+                errors.push(format!("rule #{idx} has invalid action: {err}"));
+            }
+
+            if let Err(err) = rule.validate_triggers() {
+                let idx = idx + 1;
+                errors.push(format!("rule #{idx} has invalid when: {err}"));
+            }
+
+            if let Err(err) = rule.compile() {
+                let idx = idx + 1;
+                errors.push(format!("rule #{idx} has invalid {err}"));
+            }
         }
 
         if errors.is_empty() {
@@ -142,12 +142,8 @@ pub struct EditorDefaults {
 }
 
 pub fn edit(opts: EditorDefaults) -> anyhow::Result<EditorBookmark> {
-    let editor = std::env::var("EDITOR").unwrap_or("vim".into());
-
-    let temp_file = format!("/tmp/bb-{}.md", Eid::new());
-    std::fs::write(
-        &temp_file,
-        format!(
+    let content = edit_text(
+        &format!(
             r###"# URL (one line):
 {}
 # TITLE (one line, leave "-" to prevent auto-fill)
@@ -175,8 +171,19 @@ pub fn edit(opts: EditorDefaults) -> anyhow::Result<EditorBookmark> {
             opts.description.unwrap_or_default(),
             opts.current_tags.join(" ")
         ),
-    )
-    .expect("error writing temp file");
+        "md",
+    )?;
+
+    parse_editor_bookmark(&content)
+}
+
+/// Open `content` in $EDITOR and return what was saved. `extension` lets the
+/// editor pick a syntax.
+pub fn edit_text(content: &str, extension: &str) -> anyhow::Result<String> {
+    let editor = std::env::var("EDITOR").unwrap_or("vim".into());
+
+    let temp_file = format!("/tmp/bb-{}.{extension}", Eid::new());
+    std::fs::write(&temp_file, content).expect("error writing temp file");
 
     let shell = std::env::var("SHELL").unwrap_or("/usr/sbin/bash".into());
     std::process::Command::new(shell)
@@ -191,7 +198,7 @@ pub fn edit(opts: EditorDefaults) -> anyhow::Result<EditorBookmark> {
 
     std::fs::remove_file(&temp_file).expect("error deleting temp file");
 
-    parse_editor_bookmark(&content)
+    Ok(content)
 }
 
 #[cfg(test)]
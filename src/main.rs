@@ -13,6 +13,7 @@ mod images;
 mod lock;
 mod metadata;
 mod rules;
+mod saved_searches;
 mod scrape;
 mod search_query;
 mod semantic;
@@ -121,9 +122,15 @@ fn run() -> anyhow::Result<()> {
             query,
             id,
             exact,
+            fuzzy_fallback,
             semantic,
             threshold,
+            sort,
+            desc,
+            page,
+            page_size,
             count,
+            facets,
             action,
         } => {
             let _lock = if action.as_ref().map_or(false, |a| a.is_write()) {
@@ -140,9 +147,15 @@ fn run() -> anyhow::Result<()> {
                 query,
                 id,
                 exact,
+                fuzzy_fallback,
                 semantic,
                 threshold,
+                sort,
+                desc,
+                page,
+                page_size,
                 count,
+                facets,
                 action,
             };
             cli::handle_search(params, app_service)
@@ -174,7 +187,15 @@ fn run() -> anyhow::Result<()> {
             cli::handle_add(params, app_service)
         }
 
-        Command::Meta { url, output_dir, meta_args } => {
+        Command::Meta { action: Some(action), .. } => {
+            let app_service = app::AppFactory::create_app_service(&paths)?;
+            cli::handle_meta_action(action, &app_service)
+        }
+
+        Command::Meta { action: None, url, output_dir, meta_args } => {
+            let Some(url) = url else {
+                anyhow::bail!("a URL is required");
+            };
             let scrape_config = config::Config::load_with(&paths.base_path)
                 .map(|c| c.scrape)
                 .ok();
@@ -184,9 +205,18 @@ fn run() -> anyhow::Result<()> {
         Command::Rule { action } => {
             let _lock = acquire_cli_lock(base_path)?;
             let app_service = app::AppFactory::create_app_service(&paths)?;
-            let rules_config = app_service.get_rules()?;
-            let mut rules = rules_config.write().unwrap();
-            cli::handle_rule(action, &mut rules)
+            cli::handle_rule(action, &app_service)
+        }
+
+        Command::Query { action } => {
+            let app_service = app::AppFactory::create_app_service(&paths)?;
+            cli::handle_query(action, app_service)
+        }
+
+        Command::Saved { action } => {
+            let _lock = acquire_cli_lock(base_path)?;
+            let app_service = app::AppFactory::create_app_service(&paths)?;
+            cli::handle_saved(action, &app_service)
         }
 
         Command::Compress { dry_run, yes } => {
@@ -199,7 +229,9 @@ fn run() -> anyhow::Result<()> {
             cli::handle_compress(dry_run, yes, &storage, bmark_mgr, &config)
         }
 
-        Command::Backup { path } => backup::create_backup(path, base_path),
+        Command::Backup { path, include_cookies } => {
+            backup::create_backup(path, base_path, include_cookies)
+        }
 
         Command::Import { path, yes } => {
             let _lock = acquire_cli_lock(base_path)?;
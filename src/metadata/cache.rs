@@ -0,0 +1,297 @@
+//! Per-fetcher results kept on disk under `<data dir>/cache/metadata`, one
+//! JSON file per normalized URL, so re-adding or re-importing a URL does not
+//! hit every provider again. Errors are never cached. The first store of a
+//! process deletes the files with nothing fresh left in them.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Once;
+
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use super::image_validation::validate_image;
+use super::normalize::normalize_url;
+use super::types::{FetchOutcome, MetaOptions, Metadata};
+use crate::storage::{self, StorageManager};
+
+pub const CACHE_DIR: &str = "cache/metadata";
+
+static PRUNE: Once = Once::new();
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CacheFile {
+    url: String,
+    fetchers: BTreeMap<String, CachedFetch>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CachedFetch {
+    /// Unix time
+    fetched_at: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    skip: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    metadata: Option<Metadata>,
+    /// Base64, only kept when the bytes pass image validation
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    image: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    icon: Option<String>,
+}
+
+impl CachedFetch {
+    fn new(outcome: &FetchOutcome, now: i64) -> Self {
+        let encode = |bytes: &Vec<u8>| base64::engine::general_purpose::STANDARD.encode(bytes);
+        match outcome {
+            FetchOutcome::Data(m) => Self {
+                fetched_at: now,
+                skip: None,
+                metadata: Some(m.clone()),
+                image: m.image.as_ref().filter(|b| validate_image(b)).map(encode),
+                icon: m.icon.as_ref().map(encode),
+            },
+            FetchOutcome::Skip(reason) => Self {
+                fetched_at: now,
+                skip: Some(reason.clone()),
+                metadata: None,
+                image: None,
+                icon: None,
+            },
+        }
+    }
+
+    fn is_fresh(&self, now: i64, ttl_secs: i64) -> bool {
+        now.saturating_sub(self.fetched_at) < ttl_secs
+    }
+
+    fn to_outcome(&self) -> Option<FetchOutcome> {
+        if let Some(reason) = &self.skip {
+            return Some(FetchOutcome::Skip(reason.clone()));
+        }
+        let decode = |data: &String| base64::engine::general_purpose::STANDARD.decode(data).ok();
+        let mut meta = self.metadata.clone()?;
+        meta.image = self.image.as_ref().and_then(decode);
+        meta.icon = self.icon.as_ref().and_then(decode);
+        Some(FetchOutcome::Data(meta))
+    }
+}
+
+/// The cache entry of one URL.
+pub struct MetadataCache {
+    store: storage::BackendLocal,
+    file: String,
+    url: String,
+    ttl_secs: i64,
+}
+
+impl MetadataCache {
+    /// The entry for `url`, or `None` when caching is off: no cache dir,
+    /// `scrape.cache_ttl_secs` is 0 or the caller asked for fresh results.
+    pub fn for_url(url: &str, opts: &MetaOptions) -> Option<Self> {
+        let config = opts.scrape_config.as_ref()?;
+        if opts.no_cache || config.cache_ttl_secs == 0 {
+            return None;
+        }
+        let dir = config.cache_dir.as_ref()?;
+        let store = match storage::BackendLocal::new(&dir.to_string_lossy()) {
+            Ok(store) => store,
+            Err(err) => {
+                log::warn!("metadata cache disabled, cannot create {}: {err}", dir.display());
+                return None;
+            }
+        };
+
+        let url = normalize_url(url);
+        let file = format!("{:x}.json", Sha256::digest(url.as_bytes()));
+        Some(Self {
+            store,
+            file,
+            url,
+            ttl_secs: config.cache_ttl_secs.try_into().unwrap_or(i64::MAX),
+        })
+    }
+
+    fn read(&self) -> CacheFile {
+        if !self.store.exists(&self.file) {
+            return CacheFile::default();
+        }
+        match self.store.read(&self.file).map_err(anyhow::Error::from).and_then(|data| {
+            serde_json::from_slice::<CacheFile>(&data).map_err(anyhow::Error::from)
+        }) {
+            // hash collisions are not worth handling beyond not using the entry
+            Ok(file) if file.url == self.url => file,
+            Ok(_) => CacheFile::default(),
+            Err(err) => {
+                log::warn!("ignoring metadata cache entry {}: {err:#}", self.file);
+                CacheFile::default()
+            }
+        }
+    }
+
+    /// Fresh cached outcomes, keyed by fetcher name.
+    pub fn load(&self) -> HashMap<String, FetchOutcome> {
+        let now = chrono::Utc::now().timestamp();
+        self.read()
+            .fetchers
+            .into_iter()
+            .filter(|(_, entry)| entry.is_fresh(now, self.ttl_secs))
+            .filter_map(|(name, entry)| Some((name, entry.to_outcome()?)))
+            .collect()
+    }
+
+    /// Save fresh outcomes next to the still valid cached ones.
+    pub fn store(&self, outcomes: &[(&str, &FetchOutcome)]) {
+        if outcomes.is_empty() {
+            return;
+        }
+        PRUNE.call_once(|| {
+            let store = self.store.clone();
+            let ttl_secs = self.ttl_secs;
+            std::thread::spawn(move || {
+                let pruned = prune(&store, ttl_secs);
+                if pruned > 0 {
+                    log::info!("pruned {pruned} expired metadata cache entries");
+                }
+            });
+        });
+
+        let now = chrono::Utc::now().timestamp();
+        let mut file = self.read();
+        file.url.clone_from(&self.url);
+        file.fetchers.retain(|_, entry| entry.is_fresh(now, self.ttl_secs));
+        for (name, outcome) in outcomes {
+            file.fetchers.insert(name.to_string(), CachedFetch::new(outcome, now));
+        }
+
+        let written = serde_json::to_vec(&file)
+            .map_err(anyhow::Error::from)
+            .and_then(|data| Ok(self.store.write(&self.file, &data)?));
+        if let Err(err) = written {
+            log::warn!("failed to write metadata cache entry {}: {err:#}", self.file);
+        }
+    }
+}
+
+/// Delete the cache files in `store` without a fresh result, and those that
+/// can't be read. Returns how many were deleted.
+fn prune(store: &storage::BackendLocal, ttl_secs: i64) -> usize {
+    let now = chrono::Utc::now().timestamp();
+    let mut pruned = 0;
+
+    for file in store.list() {
+        // leaves the temporary files of writes in progress alone
+        let is_entry = file
+            .strip_suffix(".json")
+            .is_some_and(|hash| hash.len() == 64 && hash.chars().all(|c| c.is_ascii_hexdigit()));
+        if !is_entry {
+            continue;
+        }
+
+        let expired = store
+            .read(&file)
+            .ok()
+            .and_then(|data| serde_json::from_slice::<CacheFile>(&data).ok())
+            .is_none_or(|entry| !entry.fetchers.values().any(|f| f.is_fresh(now, ttl_secs)));
+        if expired {
+            match store.delete(&file) {
+                Ok(()) => pruned += 1,
+                Err(err) => log::warn!("failed to delete metadata cache entry {file}: {err}"),
+            }
+        }
+    }
+
+    pruned
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ScrapeConfig;
+
+    fn opts(dir: &std::path::Path, ttl: u64) -> MetaOptions {
+        MetaOptions {
+            scrape_config: Some(ScrapeConfig {
+                cache_ttl_secs: ttl,
+                cache_dir: Some(dir.to_path_buf()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_cache_round_trip_by_normalized_url() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let cache = MetadataCache::for_url("https://example.com/page?utm_source=x", &opts(dir.path(), 60)).unwrap();
+        let data = FetchOutcome::Data(Metadata {
+            title: Some("Example".into()),
+            // not a valid image, so it is not cached
+            image: Some(vec![1, 2, 3]),
+            icon: Some(vec![4, 5, 6]),
+            ..Default::default()
+        });
+        let skip = FetchOutcome::Skip("no oEmbed provider".into());
+        cache.store(&[("Plain", &data), ("oEmbed", &skip)]);
+
+        let cache = MetadataCache::for_url("https://example.com/page", &opts(dir.path(), 60)).unwrap();
+        let loaded = cache.load();
+        assert_eq!(loaded.len(), 2);
+        let Some(FetchOutcome::Data(m)) = loaded.get("Plain") else {
+            panic!("expected cached data");
+        };
+        assert_eq!(m.title.as_deref(), Some("Example"));
+        assert_eq!(m.image, None);
+        assert_eq!(m.icon, Some(vec![4, 5, 6]));
+        assert!(matches!(loaded.get("oEmbed"), Some(FetchOutcome::Skip(r)) if r == "no oEmbed provider"));
+    }
+
+    #[test]
+    fn test_cache_disabled() {
+        let dir = tempfile::TempDir::new().unwrap();
+        assert!(MetadataCache::for_url("https://example.com/", &opts(dir.path(), 0)).is_none());
+
+        let mut no_cache = opts(dir.path(), 60);
+        no_cache.no_cache = true;
+        assert!(MetadataCache::for_url("https://example.com/", &no_cache).is_none());
+        assert!(MetadataCache::for_url("https://example.com/", &MetaOptions::default()).is_none());
+    }
+
+    #[test]
+    fn test_cache_expired_entries_are_ignored() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let cache = MetadataCache::for_url("https://example.com/", &opts(dir.path(), 60)).unwrap();
+        let old = CacheFile {
+            url: cache.url.clone(),
+            fetchers: BTreeMap::from([(
+                "Plain".to_string(),
+                CachedFetch {
+                    fetched_at: chrono::Utc::now().timestamp() - 120,
+                    skip: Some("stale".into()),
+                    metadata: None,
+                    image: None,
+                    icon: None,
+                },
+            )]),
+        };
+        cache.store.write(&cache.file, &serde_json::to_vec(&old).unwrap()).unwrap();
+
+        assert!(cache.load().is_empty());
+
+        let fresh = MetadataCache::for_url("https://example.org/", &opts(dir.path(), 60)).unwrap();
+        let entry = CacheFile {
+            url: fresh.url.clone(),
+            fetchers: BTreeMap::from([(
+                "Plain".to_string(),
+                CachedFetch::new(&FetchOutcome::Skip("nothing".into()), chrono::Utc::now().timestamp()),
+            )]),
+        };
+        fresh.store.write(&fresh.file, &serde_json::to_vec(&entry).unwrap()).unwrap();
+        cache.store.write("unrelated.txt", b"kept").unwrap();
+
+        assert_eq!(prune(&cache.store, 60), 1);
+        assert!(!cache.store.exists(&cache.file));
+        assert!(fresh.store.exists(&fresh.file));
+        assert!(cache.store.exists("unrelated.txt"));
+    }
+}
@@ -0,0 +1,205 @@
+use std::collections::HashSet;
+use std::io::Read;
+use std::process::{Command, Stdio};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Context};
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use crate::config::{PluginConfig, ScrapeConfig};
+use crate::metadata::fetchers::MetadataFetcher;
+use crate::metadata::types::{FetchOutcome, Metadata};
+
+/// stderr kept for the error of a failed run
+const MAX_STDERR_BYTES: u64 = 4096;
+
+/// Fetcher names are `&'static str`; plugin names come from the config, so
+/// each distinct one is leaked once.
+static NAMES: Lazy<Mutex<HashSet<&'static str>>> = Lazy::new(Default::default);
+
+fn static_name(name: &str) -> &'static str {
+    let mut names = NAMES.lock().unwrap();
+    match names.get(name).copied() {
+        Some(name) => name,
+        None => {
+            let name: &'static str = Box::leak(name.to_string().into_boxed_str());
+            names.insert(name);
+            name
+        }
+    }
+}
+
+/// A `scrape.plugins` entry: runs its command with the URL and reads the
+/// metadata JSON it prints.
+pub struct CommandFetcher {
+    name: &'static str,
+    plugin: PluginConfig,
+    /// `plugin.urls`, compiled; `None` handles every URL
+    urls: Option<Vec<Regex>>,
+}
+
+impl CommandFetcher {
+    pub fn new(name: &str, plugin: PluginConfig) -> Self {
+        let urls = (!plugin.urls.is_empty()).then(|| {
+            plugin
+                .urls
+                .iter()
+                .filter_map(|pattern| match Regex::new(pattern) {
+                    Ok(regex) => Some(regex),
+                    Err(err) => {
+                        log::warn!("plugin {name}: invalid URL pattern '{pattern}': {err}");
+                        None
+                    }
+                })
+                .collect()
+        });
+
+        Self {
+            name: static_name(name),
+            plugin,
+            urls,
+        }
+    }
+
+    fn handles(&self, url: &str) -> bool {
+        self.urls
+            .as_ref()
+            .is_none_or(|urls| urls.iter().any(|regex| regex.is_match(url)))
+    }
+
+    /// stdout of the command, once it exits successfully.
+    fn run(&self, url: &str) -> anyhow::Result<Vec<u8>> {
+        let mut child = Command::new(&self.plugin.command)
+            .args(&self.plugin.args)
+            .arg(url)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .with_context(|| format!("failed to run {}", self.plugin.command))?;
+
+        // read on other threads so a full pipe can't stall the command
+        let limit = self.plugin.max_output_bytes as u64;
+        let stdout = child.stdout.take().map(|out| {
+            std::thread::spawn(move || {
+                let mut buf = Vec::new();
+                out.take(limit + 1).read_to_end(&mut buf).map(|_| buf)
+            })
+        });
+        let stderr = child.stderr.take().map(|err| {
+            std::thread::spawn(move || {
+                let mut buf = Vec::new();
+                let _ = err.take(MAX_STDERR_BYTES).read_to_end(&mut buf);
+                buf
+            })
+        });
+
+        let timeout = Duration::from_secs(self.plugin.timeout_secs);
+        let start = Instant::now();
+        let status = loop {
+            if let Some(status) = child.try_wait()? {
+                break status;
+            }
+            if start.elapsed() >= timeout {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(anyhow!("timed out after {}s", timeout.as_secs()));
+            }
+            std::thread::sleep(Duration::from_millis(20));
+        };
+
+        let output = match stdout {
+            Some(reader) => reader.join().map_err(|_| anyhow!("stdout reader panicked"))??,
+            None => Vec::new(),
+        };
+        // checked first: a command still writing when reading stopped dies of SIGPIPE
+        if output.len() as u64 > limit {
+            return Err(anyhow!("output exceeds {limit} bytes"));
+        }
+        if !status.success() {
+            let stderr = stderr.and_then(|reader| reader.join().ok()).unwrap_or_default();
+            return Err(anyhow!(
+                "exited with {status}: {}",
+                String::from_utf8_lossy(&stderr).trim()
+            ));
+        }
+
+        Ok(output)
+    }
+}
+
+impl MetadataFetcher for CommandFetcher {
+    fn fetch(&self, url: &str, _scrape_config: Option<&ScrapeConfig>) -> anyhow::Result<FetchOutcome> {
+        if !self.handles(url) {
+            return Ok(FetchOutcome::Skip("URL not handled by this plugin".into()));
+        }
+
+        let output = self.run(url).with_context(|| format!("plugin {}", self.name))?;
+        if output.iter().all(u8::is_ascii_whitespace) {
+            return Ok(FetchOutcome::Skip("plugin printed nothing".into()));
+        }
+
+        let meta: Option<Metadata> = serde_json::from_slice(&output)
+            .with_context(|| format!("plugin {} printed invalid metadata JSON", self.name))?;
+        Ok(match meta {
+            Some(meta) => FetchOutcome::Data(meta),
+            None => FetchOutcome::Skip("plugin has no metadata for this URL".into()),
+        })
+    }
+
+    fn name(&self) -> &'static str {
+        self.name
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    fn plugin(script: &str) -> PluginConfig {
+        PluginConfig {
+            command: "sh".into(),
+            // the URL ends up in $1
+            args: vec!["-c".into(), script.into(), "plugin".into()],
+            urls: vec![r"^https://intranet\.example\.com/".into()],
+            timeout_secs: 1,
+            max_output_bytes: 256,
+        }
+    }
+
+    fn fetch(script: &str, url: &str) -> anyhow::Result<FetchOutcome> {
+        CommandFetcher::new("intranet", plugin(script)).fetch(url, None)
+    }
+
+    #[test]
+    fn test_plugin_metadata() {
+        let script = r#"printf '{"title": "Wiki: %s", "fields": {"team": "docs"}}' "$1""#;
+        let Ok(FetchOutcome::Data(meta)) = fetch(script, "https://intranet.example.com/wiki") else {
+            panic!("expected plugin metadata");
+        };
+        assert_eq!(meta.title.as_deref(), Some("Wiki: https://intranet.example.com/wiki"));
+        assert_eq!(meta.fields["team"], "docs");
+
+        assert!(matches!(fetch("echo null", "https://intranet.example.com/"), Ok(FetchOutcome::Skip(_))));
+        // outside its URL patterns the command doesn't run
+        assert!(matches!(fetch("exit 1", "https://example.com/"), Ok(FetchOutcome::Skip(_))));
+        assert_eq!(CommandFetcher::new("intranet", plugin("")).name(), "intranet");
+    }
+
+    #[test]
+    fn test_plugin_failures() {
+        let url = "https://intranet.example.com/";
+        let err = fetch("echo 'no access' >&2; exit 3", url).unwrap_err();
+        assert!(format!("{err:#}").contains("no access"), "{err:#}");
+
+        let err = fetch("sleep 5", url).unwrap_err();
+        assert!(format!("{err:#}").contains("timed out"), "{err:#}");
+
+        let err = fetch("head -c 1000 /dev/zero | tr '\\0' a", url).unwrap_err();
+        assert!(format!("{err:#}").contains("exceeds 256 bytes"), "{err:#}");
+
+        assert!(fetch("echo '{not json'", url).is_err());
+    }
+}
@@ -3,6 +3,8 @@ use crate::metadata::fetchers::{fetch_bytes, MetadataFetcher};
 use crate::metadata::types::{FetchOutcome, Metadata};
 use serde_json::Value;
 
+const API_URL: &str = "https://iframe.ly/api/iframely";
+
 pub struct IframelyFetcher;
 
 impl IframelyFetcher {
@@ -79,12 +81,14 @@ impl MetadataFetcher for IframelyFetcher {
             }
         };
 
+        let permit = crate::scrape::limiter::acquire(API_URL, scrape_config);
         let client = reqwest::blocking::Client::new();
         let resp = client
-            .get("https://iframe.ly/api/iframely")
+            .get(API_URL)
             .query(&[("url", url), ("api_key", &api_key)])
             .send()?
             .json::<Value>()?;
+        drop(permit);
 
         if let Some(error) = resp.get("error").and_then(|v| v.as_str()) {
             let status = resp.get("status").and_then(|v| v.as_i64()).unwrap_or(0);
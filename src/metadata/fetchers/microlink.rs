@@ -13,6 +13,8 @@ pub struct MicrolinkResult {
     pub image_url: Option<String>, // og:image or screenshot
 }
 
+const API_URL: &str = "https://api.microlink.io";
+
 pub struct MicrolinkFetcher;
 
 impl MicrolinkFetcher {
@@ -23,7 +25,7 @@ impl MicrolinkFetcher {
     pub fn microlink(url: &str, api_key: Option<&str>) -> Option<MicrolinkResult> {
         let client = reqwest::blocking::Client::new();
 
-        let mut req = client.get("https://api.microlink.io").query(&[
+        let mut req = client.get(API_URL).query(&[
             ("url", url),
             ("screenshot", "true"),
             ("screenshot.device", "desktop"),
@@ -105,7 +107,11 @@ impl MetadataFetcher for MicrolinkFetcher {
             );
         }
 
-        match Self::microlink(url, api_key.as_deref()) {
+        let permit = crate::scrape::limiter::acquire(API_URL, scrape_config);
+        let result = Self::microlink(url, api_key.as_deref());
+        drop(permit);
+
+        match result {
             Some(m) => {
                 // Accept partial results — any useful field is sufficient
                 if m.title.is_some() || m.description.is_some() || m.image_url.is_some() || m.icon_url.is_some() {
@@ -1,17 +1,21 @@
+pub mod command;
 pub mod ddg;
 pub mod iframely;
 pub mod microlink;
 pub mod oembed;
 pub mod peekalink;
 pub mod plain;
+pub mod site;
 pub mod wayback;
 
 use crate::config::ScrapeConfig;
+use crate::metadata::cache::MetadataCache;
 use crate::metadata::image_validation::validate_image;
 use crate::metadata::types::{
     FetchOutcome, FieldDecision, FetcherFields, FetcherReport, FetcherStatus,
     HeadlessFallbackInfo, Metadata, MetaOptions, MetadataReport,
 };
+use crate::scrape::robots::{self, RobotsHints};
 use std::thread;
 use std::time::Instant;
 
@@ -38,24 +42,33 @@ pub trait MetadataFetcher: Send + Sync {
 /// Collection of all available fetchers
 pub struct FetcherRegistry {
     fetchers: Vec<Box<dyn MetadataFetcher>>,
+    /// Headless already runs in parallel, so it isn't a fallback
+    always_headless: bool,
 }
 
 // Guard: switch to semaphore-bounded pool if >6 fetchers
-const MAX_UNBOUNDED_FETCHERS: usize = 8;
+// (Site skips without a request for URLs no extractor knows, plugins mostly
+// wait on their command)
+const MAX_UNBOUNDED_FETCHERS: usize = 9 + crate::config::MAX_PLUGINS;
 
 impl FetcherRegistry {
-    pub fn new(opts: &MetaOptions) -> Self {
-        let mut registry = Self { fetchers: Vec::new() };
+    /// Fetchers for `url`, honouring any `scrape.domains` override for its host.
+    pub fn new(url: &str, opts: &MetaOptions) -> Self {
+        let scrape_config = opts.scrape_config.as_ref();
+        let mut registry = Self {
+            fetchers: Vec::new(),
+            always_headless: scrape_config.is_some_and(|c| c.always_headless_for(url)),
+        };
 
         // Build fetcher order from config, falling back to defaults
-        let order = opts.scrape_config.as_ref()
-            .map(|c| &c.fetcher_order)
+        let order = scrape_config
+            .map(|c| c.fetcher_order_for(url))
             .filter(|o| !o.is_empty())
-            .cloned()
             .unwrap_or_else(crate::config::default_fetcher_order);
 
         for name in &order {
             match name.as_str() {
+                "Site" => registry.fetchers.push(Box::new(site::SiteFetcher::new())),
                 "oEmbed" => registry.fetchers.push(Box::new(oembed::OembedFetcher::new())),
                 "Wayback" => registry.fetchers.push(Box::new(wayback::WaybackFetcher::new())),
                 "Plain" => registry.fetchers.push(Box::new(plain::PlainFetcher::new())),
@@ -63,15 +76,17 @@ impl FetcherRegistry {
                 "Peekalink" => registry.fetchers.push(Box::new(peekalink::PeekalinkFetcher::new())),
                 "Iframely" => registry.fetchers.push(Box::new(iframely::IframelyFetcher::new())),
                 "DDG" => registry.fetchers.push(Box::new(ddg::DdgFetcher::new())),
-                other => log::warn!("Unknown fetcher in config: {other}"),
+                other => match scrape_config.and_then(|c| c.plugins.get(other)) {
+                    Some(plugin) => registry
+                        .fetchers
+                        .push(Box::new(command::CommandFetcher::new(other, plugin.clone()))),
+                    None => log::warn!("Unknown fetcher in config: {other}"),
+                },
             }
         }
 
         // Headless is conditional, not part of the configurable order
-        let always_headless = opts.scrape_config.as_ref()
-            .map(|c| c.always_headless)
-            .unwrap_or(false);
-        if always_headless && !opts.no_headless {
+        if registry.always_headless && !opts.no_headless {
             registry.fetchers.push(Box::new(plain::HeadlessParallelFetcher::new(opts.clone())));
         }
 
@@ -85,39 +100,60 @@ impl FetcherRegistry {
     }
 
     /// Fan out all fetchers in parallel, collect results, merge by priority.
+    /// Fetchers with a fresh result in the metadata cache are not called.
     /// Falls back to headless if no validated image after merge.
     /// Returns both the merged metadata and a diagnostic report.
     pub fn fetch_metadata(&self, url: &str, opts: &MetaOptions) -> anyhow::Result<(Option<Metadata>, MetadataReport)> {
         let pipeline_start = Instant::now();
         let scrape_config = opts.scrape_config.as_ref();
+        let cache = MetadataCache::for_url(url, opts);
+        let mut cached = cache.as_ref().map(MetadataCache::load).unwrap_or_default();
 
         // Fan out parallel fetchers using thread::scope (bounded by fetcher count)
-        let raw_results: Vec<(u8, &str, Result<FetchOutcome, String>, u64)> = thread::scope(|s| {
+        let mut raw_results: Vec<FetchResult> = thread::scope(|s| {
             let handles: Vec<_> = self
                 .fetchers
                 .iter()
                 .enumerate()
                 .map(|(idx, f)| {
                     let sc = scrape_config;
+                    let hit = cached.remove(f.name());
                     s.spawn(move || {
                         let name = f.name();
+                        if let Some(outcome) = hit {
+                            log::info!("fetcher={name} outcome=cached");
+                            return FetchResult {
+                                priority: idx as u8,
+                                name,
+                                result: Ok(outcome),
+                                duration_ms: 0,
+                                cached: true,
+                            };
+                        }
                         let start = Instant::now();
                         let result = f.fetch(url, sc);
-                        let dur_ms = start.elapsed().as_millis() as u64;
-                        match result {
+                        let duration_ms = start.elapsed().as_millis() as u64;
+                        let result = match result {
                             Ok(FetchOutcome::Data(m)) => {
                                 let fields = describe_fields(&m);
                                 log::info!("fetcher={name} outcome=success fields=[{fields}]");
-                                (idx as u8, name, Ok(FetchOutcome::Data(m)), dur_ms)
+                                Ok(FetchOutcome::Data(m))
                             }
                             Ok(FetchOutcome::Skip(reason)) => {
                                 log::info!("fetcher={name} outcome=skip reason={reason}");
-                                (idx as u8, name, Ok(FetchOutcome::Skip(reason)), dur_ms)
+                                Ok(FetchOutcome::Skip(reason))
                             }
                             Err(e) => {
                                 log::warn!("fetcher={name} outcome=error err={e}");
-                                (idx as u8, name, Err(e.to_string()), dur_ms)
+                                Err(e.to_string())
                             }
+                        };
+                        FetchResult {
+                            priority: idx as u8,
+                            name,
+                            result,
+                            duration_ms,
+                            cached: false,
                         }
                     })
                 })
@@ -129,38 +165,52 @@ impl FetcherRegistry {
                 .collect()
         });
 
+        let hints = apply_robots_hints(&mut raw_results, scrape_config);
+
+        if let Some(cache) = &cache {
+            let fresh: Vec<(&str, &FetchOutcome)> = raw_results
+                .iter()
+                .filter(|r| !r.cached)
+                .filter_map(|r| Some((r.name, r.result.as_ref().ok()?)))
+                .collect();
+            cache.store(&fresh);
+        }
+
         // Build fetcher reports and extract successes
         let mut fetcher_reports = Vec::new();
         let mut successes: Vec<(u8, &str, Metadata)> = Vec::new();
 
-        for (priority, name, result, dur_ms) in &raw_results {
-            match result {
+        for raw in &raw_results {
+            match &raw.result {
                 Ok(FetchOutcome::Data(m)) => {
                     fetcher_reports.push(FetcherReport {
-                        name: name.to_string(),
-                        priority: *priority,
+                        name: raw.name.to_string(),
+                        priority: raw.priority,
                         status: FetcherStatus::Success,
-                        duration_ms: *dur_ms,
+                        duration_ms: raw.duration_ms,
                         fields: Some(metadata_to_fetcher_fields(m)),
+                        cached: raw.cached,
                     });
-                    successes.push((*priority, *name, m.clone()));
+                    successes.push((raw.priority, raw.name, m.clone()));
                 }
                 Ok(FetchOutcome::Skip(reason)) => {
                     fetcher_reports.push(FetcherReport {
-                        name: name.to_string(),
-                        priority: *priority,
+                        name: raw.name.to_string(),
+                        priority: raw.priority,
                         status: FetcherStatus::Skip(reason.clone()),
-                        duration_ms: *dur_ms,
+                        duration_ms: raw.duration_ms,
                         fields: None,
+                        cached: raw.cached,
                     });
                 }
                 Err(e) => {
                     fetcher_reports.push(FetcherReport {
-                        name: name.to_string(),
-                        priority: *priority,
+                        name: raw.name.to_string(),
+                        priority: raw.priority,
                         status: FetcherStatus::Error(e.clone()),
-                        duration_ms: *dur_ms,
+                        duration_ms: raw.duration_ms,
                         fields: None,
+                        cached: raw.cached,
                     });
                 }
             }
@@ -170,11 +220,8 @@ impl FetcherRegistry {
         let merged = merge_results(successes, scrape_config, &mut field_decisions);
 
         // Headless fallback
-        let already_ran_headless = opts.scrape_config.as_ref()
-            .map(|c| c.always_headless)
-            .unwrap_or(false);
-        let needs_headless = !already_ran_headless
-            && (!merged.has_valid_image()
+        let needs_headless = !self.always_headless
+            && ((!merged.has_valid_image() && !hints.noimageindex)
                 || merged.description.is_none()
                 || is_generic_title(merged.title.as_deref()));
 
@@ -189,10 +236,17 @@ impl FetcherRegistry {
                 "generic title"
             }.to_string();
 
-            let headless_fetcher = plain::HeadlessFetcher::new(opts.clone());
+            let headless_fetcher = plain::HeadlessFetcher::new(opts.clone()).with_robots(hints);
             let headless_start = Instant::now();
-            match headless_fetcher.fetch_with_headless(url, scrape_config) {
-                Ok(Some(m)) => {
+            let result = if robots::allowed(url, scrape_config) {
+                headless_fetcher
+                    .fetch_with_headless(url, scrape_config)
+                    .map(|m| m.ok_or_else(|| "headless returned no data".to_string()))
+            } else {
+                Ok(Err(robots::DISALLOWED.to_string()))
+            };
+            match result {
+                Ok(Ok(m)) => {
                     let mut overridden = Vec::new();
                     let final_merged = merge_two(merged, m, scrape_config, &mut field_decisions, &mut overridden);
                     headless_fallback = Some(HeadlessFallbackInfo {
@@ -207,6 +261,7 @@ impl FetcherRegistry {
                         status: FetcherStatus::Success,
                         duration_ms: headless_start.elapsed().as_millis() as u64,
                         fields: None,
+                        cached: false,
                     });
                     let report = MetadataReport {
                         fetchers: fetcher_reports,
@@ -216,19 +271,20 @@ impl FetcherRegistry {
                     };
                     return Ok((Some(final_merged), report));
                 }
-                Ok(None) => {
+                Ok(Err(skip)) => {
                     headless_fallback = Some(HeadlessFallbackInfo {
                         triggered: true,
                         reason,
-                        status: FetcherStatus::Skip("headless returned no data".into()),
+                        status: FetcherStatus::Skip(skip.clone()),
                         fields_overridden: vec![],
                     });
                     fetcher_reports.push(FetcherReport {
                         name: "Headless".to_string(),
                         priority: 255,
-                        status: FetcherStatus::Skip("headless returned no data".into()),
+                        status: FetcherStatus::Skip(skip),
                         duration_ms: headless_start.elapsed().as_millis() as u64,
                         fields: None,
+                        cached: false,
                     });
                 }
                 Err(e) => {
@@ -244,6 +300,7 @@ impl FetcherRegistry {
                         status: FetcherStatus::Error(e.to_string()),
                         duration_ms: headless_start.elapsed().as_millis() as u64,
                         fields: None,
+                        cached: false,
                     });
                 }
             }
@@ -266,6 +323,50 @@ impl FetcherRegistry {
     }
 }
 
+/// One fetcher's part in a `fetch_metadata` run.
+struct FetchResult<'a> {
+    /// Index in the fetcher order, lower wins the merge
+    priority: u8,
+    name: &'a str,
+    result: Result<FetchOutcome, String>,
+    duration_ms: u64,
+    /// Taken from the metadata cache instead of fetched
+    cached: bool,
+}
+
+/// With `scrape.respect_robots`, apply the robots hints any fetcher found on
+/// the page to all results: `noimageindex` drops their images and
+/// `noarchive` the Wayback snapshot and what may be a headless screenshot.
+/// Returns the hints applied.
+fn apply_robots_hints(results: &mut [FetchResult], scrape_config: Option<&ScrapeConfig>) -> RobotsHints {
+    let hints = results
+        .iter()
+        .filter_map(|r| match &r.result {
+            Ok(FetchOutcome::Data(m)) => Some(m.robots),
+            _ => None,
+        })
+        .fold(RobotsHints::default(), RobotsHints::union)
+        .honoured(scrape_config);
+
+    for FetchResult { name, result, .. } in results.iter_mut() {
+        let Ok(FetchOutcome::Data(m)) = result else {
+            continue;
+        };
+        if hints.noarchive && *name == "Wayback" {
+            *result = Ok(FetchOutcome::Skip("page is marked noarchive".into()));
+            continue;
+        }
+        if hints.noimageindex {
+            m.image = None;
+            m.image_url = None;
+        } else if hints.noarchive && *name == "Headless" {
+            m.image = None;
+        }
+    }
+
+    hints
+}
+
 /// Merge multiple fetcher results by priority (lower priority number = preferred).
 /// For each field, take the first non-None value from the sorted results.
 /// Validate images during merge and set image_valid flag.
@@ -351,6 +452,7 @@ fn merge_results(mut results: Vec<(u8, &str, Metadata)>, scrape_config: Option<&
         if merged.dump.is_none() {
             merged.dump.clone_from(&m.dump);
         }
+        merge_site_fields(&mut merged, m, name, decisions);
 
         // Image merge: try each source until we find a validated one
         if !merged.image_valid {
@@ -446,6 +548,7 @@ fn merge_two(mut base: Metadata, overlay: Metadata, scrape_config: Option<&Scrap
             value_preview: overlay.description.as_ref().map(|v| v.chars().take(120).collect()),
         });
     }
+    merge_site_fields(&mut base, &overlay, source, decisions);
     if base.keywords.is_none() {
         base.keywords = overlay.keywords;
     }
@@ -524,6 +627,27 @@ fn merge_two(mut base: Metadata, overlay: Metadata, scrape_config: Option<&Scrap
     base
 }
 
+/// Add structured fields the merged metadata doesn't have yet, and any new
+/// tags, from `m`.
+fn merge_site_fields(merged: &mut Metadata, m: &Metadata, name: &str, decisions: &mut Vec<FieldDecision>) {
+    for (key, value) in &m.fields {
+        if !merged.fields.contains_key(key) {
+            merged.fields.insert(key.clone(), value.clone());
+            decisions.push(FieldDecision {
+                field: format!("fields.{key}"),
+                winner: name.to_string(),
+                reason: "first available".into(),
+                value_preview: Some(value.chars().take(120).collect()),
+            });
+        }
+    }
+    for tag in &m.tags {
+        if !merged.tags.contains(tag) {
+            merged.tags.push(tag.clone());
+        }
+    }
+}
+
 fn metadata_to_fetcher_fields(m: &Metadata) -> FetcherFields {
     FetcherFields {
         title: m.title.clone(),
@@ -534,6 +658,8 @@ fn metadata_to_fetcher_fields(m: &Metadata) -> FetcherFields {
         icon_url: m.icon_url.clone(),
         has_image_bytes: m.image.is_some(),
         has_icon_bytes: m.icon.is_some(),
+        fields: m.fields.clone(),
+        tags: m.tags.clone(),
     }
 }
 
@@ -623,6 +749,8 @@ fn describe_fields(m: &Metadata) -> String {
     if m.icon_url.is_some() { fields.push("icon_url"); }
     if m.icon.is_some() { fields.push("icon"); }
     if m.canonical_url.is_some() { fields.push("canonical_url"); }
+    if !m.fields.is_empty() { fields.push("fields"); }
+    if !m.tags.is_empty() { fields.push("tags"); }
     fields.join(",")
 }
 
@@ -638,6 +766,70 @@ mod tests {
         }
     }
 
+    fn fetcher_names(url: &str, opts: &MetaOptions) -> (Vec<&'static str>, bool) {
+        let registry = FetcherRegistry::new(url, opts);
+        let names = registry.fetchers.iter().map(|f| f.name()).collect();
+        (names, registry.always_headless)
+    }
+
+    #[test]
+    fn test_registry_uses_domain_overrides() {
+        let scrape_config: ScrapeConfig = serde_yml::from_str(
+            r#"
+domains:
+  Example.com:
+    fetcher_order: [Plain]
+  "*.news.example.com":
+    fetcher_order: [Plain, DDG]
+    always_headless: true
+"#,
+        )
+        .unwrap();
+        let mut opts = MetaOptions {
+            scrape_config: Some(scrape_config),
+            ..Default::default()
+        };
+
+        assert_eq!(fetcher_names("https://www.example.com/a", &opts), (vec!["Plain"], false));
+        // the longest matching pattern wins
+        assert_eq!(
+            fetcher_names("https://eu.news.example.com/", &opts),
+            (vec!["Plain", "DDG", "Headless"], true)
+        );
+        // `*.` doesn't match the parent itself
+        assert_eq!(fetcher_names("https://news.example.com/", &opts), (vec!["Plain"], false));
+        assert_eq!(fetcher_names("https://example.org/", &opts).0.len(), 8);
+
+        opts.scrape_config.as_mut().unwrap().force_headless();
+        assert_eq!(
+            fetcher_names("https://example.com/", &opts),
+            (vec!["Plain", "Headless"], true)
+        );
+    }
+
+    #[test]
+    fn test_registry_runs_plugins_from_fetcher_order() {
+        let scrape_config: ScrapeConfig = serde_yml::from_str(
+            r#"
+plugins:
+  intranet:
+    command: /usr/local/bin/intranet-meta
+    urls: ['^https://wiki\.corp\.example/']
+domains:
+  corp.example:
+    fetcher_order: [intranet, Plain, unknown]
+"#,
+        )
+        .unwrap();
+        let opts = MetaOptions {
+            scrape_config: Some(scrape_config),
+            ..Default::default()
+        };
+
+        assert_eq!(fetcher_names("https://wiki.corp.example/", &opts).0, ["intranet", "Plain"]);
+        assert!(!fetcher_names("https://example.com/", &opts).0.contains(&"intranet"));
+    }
+
     #[test]
     fn test_generic_title_overridden_in_merge() {
         let results = vec![
@@ -648,6 +840,58 @@ mod tests {
         assert_eq!(merged.title.as_deref(), Some("Actual Post Title"));
     }
 
+    #[test]
+    fn test_robots_hints_applied_to_all_results() {
+        let page = || Metadata {
+            image: Some(vec![0xff, 0xd8]),
+            image_url: Some("https://example.com/cover.jpg".into()),
+            ..meta_with_title("Page")
+        };
+        let fetched = |priority, name, meta| FetchResult {
+            priority,
+            name,
+            result: Ok(FetchOutcome::Data(meta)),
+            duration_ms: 0,
+            cached: false,
+        };
+        let results = || {
+            vec![
+                fetched(
+                    0,
+                    "Plain",
+                    Metadata {
+                        robots: RobotsHints {
+                            noarchive: true,
+                            noimageindex: true,
+                        },
+                        ..page()
+                    },
+                ),
+                fetched(1, "Wayback", page()),
+                fetched(2, "Microlink", page()),
+            ]
+        };
+
+        // ignored unless scrape.respect_robots is on
+        let mut ignored = results();
+        assert!(apply_robots_hints(&mut ignored, Some(&ScrapeConfig::default())).is_empty());
+        assert!(matches!(&ignored[2].result, Ok(FetchOutcome::Data(m)) if m.image.is_some()));
+
+        let config = ScrapeConfig {
+            respect_robots: true,
+            ..Default::default()
+        };
+        let mut applied = results();
+        let hints = apply_robots_hints(&mut applied, Some(&config));
+        assert!(hints.noarchive && hints.noimageindex);
+        assert!(matches!(&applied[1].result, Ok(FetchOutcome::Skip(r)) if r == "page is marked noarchive"));
+        let Ok(FetchOutcome::Data(m)) = &applied[2].result else {
+            panic!("expected Microlink data");
+        };
+        assert_eq!((m.image.as_ref(), m.image_url.as_deref()), (None, None));
+        assert_eq!(m.title.as_deref(), Some("Page"));
+    }
+
     #[test]
     fn test_generic_title_short() {
         assert!(is_generic_title(Some("Hi")));
@@ -13,6 +13,8 @@ pub struct PeekalinkResult {
     pub image_url: Option<String>,
 }
 
+const API_URL: &str = "https://api.peekalink.io/";
+
 pub struct PeekalinkFetcher;
 
 impl PeekalinkFetcher {
@@ -23,7 +25,7 @@ impl PeekalinkFetcher {
     pub fn peekalink(url: &str, api_key: &str) -> Option<PeekalinkResult> {
         let client = reqwest::blocking::Client::new();
         let resp = client
-            .post("https://api.peekalink.io/")
+            .post(API_URL)
             .header("Content-Type", "application/json")
             .header("Authorization", format!("Bearer {}", api_key))
             .json(&json!({ "link": url }))
@@ -172,7 +174,9 @@ impl MetadataFetcher for PeekalinkFetcher {
             }
         };
 
+        let permit = crate::scrape::limiter::acquire(API_URL, scrape_config);
         let peek_result = Self::peekalink(url, &api_key);
+        drop(permit);
 
         if let Some(m) = peek_result {
             // Accept partial results — any useful field is sufficient
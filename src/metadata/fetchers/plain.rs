@@ -1,6 +1,7 @@
 use crate::config::ScrapeConfig;
 use crate::metadata::types::{FetchOutcome, Metadata, MetaOptions};
 use crate::metadata::fetchers::{MetadataFetcher, fetch_bytes};
+use crate::scrape::robots::{self, RobotsHints};
 
 pub struct PlainFetcher;
 
@@ -12,13 +13,18 @@ impl PlainFetcher {
 
 impl MetadataFetcher for PlainFetcher {
     fn fetch(&self, url: &str, scrape_config: Option<&ScrapeConfig>) -> anyhow::Result<FetchOutcome> {
+        if !robots::allowed(url, scrape_config) {
+            return Ok(FetchOutcome::Skip(robots::DISALLOWED.into()));
+        }
+
         // Try basic reqwest fetch first
         if let Some(reqwest_result) = crate::scrape::fetch_page_with_reqwest(url, scrape_config) {
             log::debug!("plain request successful");
             let mut meta = crate::scrape::get_data_from_page(reqwest_result.html.clone(), url);
+            meta.robots = meta.robots.union(reqwest_result.robots);
 
             // Try fetching image if present
-            if meta.image.is_none() {
+            if meta.image.is_none() && !meta.robots.honoured(scrape_config).noimageindex {
                 meta.try_fetch_image(scrape_config);
             }
 
@@ -52,6 +58,9 @@ impl HeadlessParallelFetcher {
 
 impl MetadataFetcher for HeadlessParallelFetcher {
     fn fetch(&self, url: &str, scrape_config: Option<&ScrapeConfig>) -> anyhow::Result<FetchOutcome> {
+        if !robots::allowed(url, scrape_config) {
+            return Ok(FetchOutcome::Skip(robots::DISALLOWED.into()));
+        }
         let inner = HeadlessFetcher::new(self.opts.clone());
         match inner.fetch_with_headless(url, scrape_config)? {
             Some(m) => Ok(FetchOutcome::Data(m)),
@@ -67,11 +76,21 @@ impl MetadataFetcher for HeadlessParallelFetcher {
 /// Enhanced plain fetcher that can use headless browser if needed (fallback mode)
 pub struct HeadlessFetcher {
     opts: MetaOptions,
+    /// What the other fetchers learned about the page
+    robots: RobotsHints,
 }
 
 impl HeadlessFetcher {
     pub fn new(opts: MetaOptions) -> Self {
-        Self { opts }
+        Self {
+            opts,
+            robots: RobotsHints::default(),
+        }
+    }
+
+    pub fn with_robots(mut self, robots: RobotsHints) -> Self {
+        self.robots = robots;
+        self
     }
     
     pub fn fetch_with_headless(&self, url: &str, scrape_config: Option<&crate::config::ScrapeConfig>) -> anyhow::Result<Option<Metadata>> {
@@ -81,15 +100,16 @@ impl HeadlessFetcher {
         
         #[cfg(feature = "headless")]
         {
-            if let Some(chrome_res) = crate::scrape::headless::fetch_page_with_chrome(url) {
+            if let Some(chrome_res) = crate::scrape::headless::fetch_page_with_chrome(url, scrape_config) {
                 let blocked = chrome_res.html.contains("Sorry, you have been blocked")
                     || chrome_res.html.contains("Verify you are human by completing the action below");
                 
                 if !blocked {
                     let mut meta = crate::scrape::get_data_from_page(chrome_res.html.clone(), url);
+                    let hints = self.robots.union(meta.robots).honoured(scrape_config);
                     
                     // Try YouTube thumbnail first if no image
-                    if meta.image.is_none() {
+                    if meta.image.is_none() && !hints.noimageindex {
                         if let Some(y_img) = self.get_youtube_image_url(url) {
                             if let Some(bytes) = fetch_bytes(&y_img, scrape_config) {
                                 meta.image = Some(bytes);
@@ -98,8 +118,9 @@ impl HeadlessFetcher {
                         }
                     }
                     
-                    // If still no image, use screenshot
-                    if meta.image.is_none() {
+                    // If still no image, use screenshot, unless the page
+                    // doesn't want copies kept
+                    if meta.image.is_none() && !hints.noimageindex && !hints.noarchive {
                         meta.image = Some(chrome_res.screenshot);
                     }
                     
@@ -0,0 +1,139 @@
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use super::{attr_content, clean_text, segments, site_host, text_of, SiteExtractor};
+use crate::metadata::types::Metadata;
+
+/// New style (`2301.01234v2`) and old style (`hep-th/9901001`) identifiers.
+static ARXIV_ID: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^(\d{4}\.\d{4,5}|[a-z-]+(\.[A-Z]{2})?/\d{7})(v\d+)?$").unwrap());
+
+/// Category codes in the subjects line, e.g. `(cs.LG)`.
+static CATEGORY: Lazy<Regex> = Lazy::new(|| Regex::new(r"\(([a-z-]+(\.[A-Za-z-]+)?)\)").unwrap());
+
+/// Papers, read from the citation meta tags of their abstract page.
+pub struct ArxivExtractor;
+
+impl SiteExtractor for ArxivExtractor {
+    fn name(&self) -> &'static str {
+        "arXiv"
+    }
+
+    fn source_url(&self, url: &reqwest::Url) -> Option<String> {
+        if site_host(url)? != "arxiv.org" {
+            return None;
+        }
+        let segments = segments(url);
+        let (kind, rest) = segments.split_first()?;
+        if !["abs", "pdf", "html"].contains(kind) {
+            return None;
+        }
+        let id = rest.join("/");
+        let id = id.strip_suffix(".pdf").unwrap_or(&id);
+        ARXIV_ID
+            .is_match(id)
+            .then(|| format!("https://arxiv.org/abs/{id}"))
+    }
+
+    fn extract(&self, url: &reqwest::Url, body: &str) -> anyhow::Result<Metadata> {
+        let doc = scraper::Html::parse_document(body);
+        let title = attr_content(&doc, r#"meta[name="citation_title"]"#)
+            .ok_or_else(|| anyhow::anyhow!("no citation_title on {url}"))?;
+        let abstract_ = attr_content(&doc, r#"meta[name="citation_abstract"]"#);
+
+        let mut meta = Metadata {
+            title: Some(title),
+            description: abstract_.clone(),
+            canonical_url: self.source_url(url),
+            ..Default::default()
+        };
+
+        // "Last, First" -> "First Last"
+        let authors: Vec<String> = scraper::Selector::parse(r#"meta[name="citation_author"]"#)
+            .map(|selector| {
+                doc.select(&selector)
+                    .filter_map(|el| el.value().attr("content"))
+                    .map(|name| match name.split_once(',') {
+                        Some((last, first)) => clean_text(&format!("{first} {last}")),
+                        None => clean_text(name),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        if !authors.is_empty() {
+            meta.fields.insert("authors".into(), authors.join(", "));
+        }
+        if let Some(abstract_) = abstract_ {
+            meta.fields.insert("abstract".into(), abstract_);
+        }
+        if let Some(id) = attr_content(&doc, r#"meta[name="citation_arxiv_id"]"#) {
+            meta.fields.insert("arxiv_id".into(), id);
+        }
+        if let Some(date) = attr_content(&doc, r#"meta[name="citation_date"]"#) {
+            meta.fields.insert("published".into(), date.replace('/', "-"));
+        }
+        if let Some(subjects) = text_of(&doc, "td.subjects") {
+            meta.tags = CATEGORY
+                .captures_iter(&subjects)
+                .map(|caps| caps[1].to_lowercase())
+                .collect();
+            meta.fields.insert("subjects".into(), subjects);
+        }
+
+        Ok(meta)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_paper_urls() {
+        let source = |url: &str| ArxivExtractor.source_url(&reqwest::Url::parse(url).unwrap());
+        assert_eq!(
+            source("https://arxiv.org/pdf/2301.01234v2.pdf").as_deref(),
+            Some("https://arxiv.org/abs/2301.01234v2")
+        );
+        assert_eq!(
+            source("https://arxiv.org/abs/hep-th/9901001").as_deref(),
+            Some("https://arxiv.org/abs/hep-th/9901001")
+        );
+        assert_eq!(source("https://arxiv.org/list/cs.LG/recent"), None);
+        assert_eq!(source("https://arxiv.org/abs/not-an-id"), None);
+    }
+
+    #[test]
+    fn test_extract_paper() {
+        let url = reqwest::Url::parse("https://arxiv.org/abs/1706.03762").unwrap();
+        let meta = ArxivExtractor
+            .extract(&url, include_str!("fixtures/arxiv_abs.html"))
+            .unwrap();
+
+        assert_eq!(meta.title.as_deref(), Some("Attention Is All You Need"));
+        assert_eq!(meta.canonical_url.as_deref(), Some("https://arxiv.org/abs/1706.03762"));
+        assert!(meta
+            .description
+            .as_deref()
+            .unwrap()
+            .starts_with("The dominant sequence transduction models are based on complex"));
+        assert_eq!(meta.fields["abstract"], meta.description.clone().unwrap());
+        assert_eq!(
+            meta.fields["authors"],
+            "Ashish Vaswani, Noam Shazeer, Niki Parmar, Jakob Uszkoreit, Llion Jones, Aidan N. Gomez, Lukasz Kaiser, Illia Polosukhin"
+        );
+        assert_eq!(meta.fields["published"], "2017-06-12");
+        assert_eq!(meta.fields["arxiv_id"], "1706.03762");
+        assert_eq!(
+            meta.fields["subjects"],
+            "Computation and Language (cs.CL); Machine Learning (cs.LG)"
+        );
+        assert_eq!(meta.tags, ["cs.cl", "cs.lg"]);
+    }
+
+    #[test]
+    fn test_page_without_citation_fails() {
+        let url = reqwest::Url::parse("https://arxiv.org/abs/1706.03762").unwrap();
+        assert!(ArxivExtractor.extract(&url, "<html><head></head></html>").is_err());
+    }
+}
@@ -0,0 +1,137 @@
+use serde::Deserialize;
+
+use super::{segments, site_host, to_tag, SiteExtractor};
+use crate::metadata::types::Metadata;
+
+/// First path segments that are GitHub pages rather than users or orgs.
+const RESERVED: [&str; 20] = [
+    "about", "apps", "collections", "customer-stories", "enterprise", "explore", "features",
+    "issues", "login", "marketplace", "new", "notifications", "orgs", "organizations", "pricing",
+    "pulls", "search", "settings", "sponsors", "topics",
+];
+
+#[derive(Debug, Deserialize)]
+struct Repo {
+    full_name: String,
+    description: Option<String>,
+    html_url: String,
+    language: Option<String>,
+    #[serde(default)]
+    stargazers_count: u64,
+    #[serde(default)]
+    forks_count: u64,
+    #[serde(default)]
+    topics: Vec<String>,
+    #[serde(default)]
+    archived: bool,
+    license: Option<License>,
+    owner: Owner,
+}
+
+#[derive(Debug, Deserialize)]
+struct License {
+    spdx_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Owner {
+    avatar_url: Option<String>,
+}
+
+/// Repository pages, read from the REST API.
+pub struct GithubExtractor;
+
+impl SiteExtractor for GithubExtractor {
+    fn name(&self) -> &'static str {
+        "GitHub"
+    }
+
+    fn source_url(&self, url: &reqwest::Url) -> Option<String> {
+        if site_host(url)? != "github.com" {
+            return None;
+        }
+        // only the repo root, deeper pages are issues, files etc.
+        match segments(url)[..] {
+            [owner, repo] if !RESERVED.contains(&owner) => {
+                let repo = repo.strip_suffix(".git").unwrap_or(repo);
+                Some(format!("https://api.github.com/repos/{owner}/{repo}"))
+            }
+            _ => None,
+        }
+    }
+
+    fn extract(&self, _url: &reqwest::Url, body: &str) -> anyhow::Result<Metadata> {
+        let repo: Repo = serde_json::from_str(body)?;
+
+        let mut meta = Metadata {
+            title: Some(match &repo.description {
+                Some(description) => format!("{}: {description}", repo.full_name),
+                None => repo.full_name.clone(),
+            }),
+            description: repo.description.clone(),
+            canonical_url: Some(repo.html_url.clone()),
+            image_url: repo.owner.avatar_url.clone(),
+            ..Default::default()
+        };
+
+        meta.fields.insert("stars".into(), repo.stargazers_count.to_string());
+        meta.fields.insert("forks".into(), repo.forks_count.to_string());
+        if let Some(language) = &repo.language {
+            meta.fields.insert("language".into(), language.clone());
+            meta.tags.push(to_tag(language));
+        }
+        if !repo.topics.is_empty() {
+            meta.fields.insert("topics".into(), repo.topics.join(", "));
+            meta.tags.extend(repo.topics.iter().map(|t| to_tag(t)));
+        }
+        if let Some(license) = repo.license.and_then(|l| l.spdx_id).filter(|id| id != "NOASSERTION") {
+            meta.fields.insert("license".into(), license);
+        }
+        if repo.archived {
+            meta.fields.insert("archived".into(), "true".into());
+        }
+
+        Ok(meta)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_repo_urls_only() {
+        let source = |url: &str| GithubExtractor.source_url(&reqwest::Url::parse(url).unwrap());
+        assert_eq!(
+            source("https://www.github.com/serde-rs/json.git").as_deref(),
+            Some("https://api.github.com/repos/serde-rs/json")
+        );
+        assert_eq!(source("https://github.com/serde-rs/json/issues/1"), None);
+        assert_eq!(source("https://github.com/topics/rust"), None);
+        assert_eq!(source("https://github.com/serde-rs"), None);
+        assert_eq!(source("https://gitlab.com/serde-rs/json"), None);
+    }
+
+    #[test]
+    fn test_extract_repo() {
+        let url = reqwest::Url::parse("https://github.com/BurntSushi/ripgrep").unwrap();
+        let meta = GithubExtractor
+            .extract(&url, include_str!("fixtures/github_repo.json"))
+            .unwrap();
+
+        assert_eq!(
+            meta.title.as_deref(),
+            Some("BurntSushi/ripgrep: ripgrep recursively searches directories for a regex pattern while respecting your gitignore")
+        );
+        assert_eq!(meta.canonical_url.as_deref(), Some("https://github.com/BurntSushi/ripgrep"));
+        assert_eq!(
+            meta.image_url.as_deref(),
+            Some("https://avatars.githubusercontent.com/u/456674?v=4")
+        );
+        assert_eq!(meta.fields["language"], "Rust");
+        assert_eq!(meta.fields["stars"], "48213");
+        assert_eq!(meta.fields["license"], "Unlicense");
+        assert!(!meta.fields.contains_key("archived"));
+        assert_eq!(meta.tags, ["rust", "cli", "grep", "regex", "search"]);
+    }
+}
@@ -0,0 +1,139 @@
+use serde::Deserialize;
+
+use super::{clean_text, site_host, SiteExtractor};
+use crate::metadata::types::Metadata;
+
+#[derive(Debug, Deserialize)]
+struct Item {
+    id: u64,
+    #[serde(rename = "type")]
+    kind: String,
+    by: Option<String>,
+    title: Option<String>,
+    url: Option<String>,
+    text: Option<String>,
+    score: Option<i64>,
+    descendants: Option<u64>,
+    time: Option<i64>,
+}
+
+/// Stories and comments, read from the Firebase API.
+pub struct HackerNewsExtractor;
+
+impl SiteExtractor for HackerNewsExtractor {
+    fn name(&self) -> &'static str {
+        "Hacker News"
+    }
+
+    fn source_url(&self, url: &reqwest::Url) -> Option<String> {
+        if site_host(url)? != "news.ycombinator.com" || url.path() != "/item" {
+            return None;
+        }
+        let id: u64 = url
+            .query_pairs()
+            .find(|(key, _)| key == "id")
+            .and_then(|(_, id)| id.parse().ok())?;
+        Some(format!("https://hacker-news.firebaseio.com/v0/item/{id}.json"))
+    }
+
+    fn extract(&self, _url: &reqwest::Url, body: &str) -> anyhow::Result<Metadata> {
+        // the API answers `null` for ids that don't exist
+        let item: Item = serde_json::from_str::<Option<Item>>(body)?
+            .ok_or_else(|| anyhow::anyhow!("item not found"))?;
+
+        let title = match (&item.title, &item.by) {
+            (Some(title), _) => title.clone(),
+            (None, Some(by)) => format!("Comment by {by}"),
+            (None, None) => format!("Hacker News item {}", item.id),
+        };
+        let mut meta = Metadata {
+            title: Some(title),
+            description: item.text.as_deref().map(strip_html).filter(|t| !t.is_empty()),
+            canonical_url: Some(format!("https://news.ycombinator.com/item?id={}", item.id)),
+            ..Default::default()
+        };
+
+        meta.fields.insert("type".into(), item.kind.clone());
+        if let Some(by) = item.by {
+            meta.fields.insert("author".into(), by);
+        }
+        if let Some(score) = item.score {
+            meta.fields.insert("points".into(), score.to_string());
+        }
+        if let Some(descendants) = item.descendants {
+            meta.fields.insert("comments".into(), descendants.to_string());
+        }
+        if let Some(link) = item.url {
+            meta.fields.insert("link".into(), link);
+        }
+        if let Some(posted) = item.time.and_then(|t| chrono::DateTime::from_timestamp(t, 0)) {
+            meta.fields.insert("posted".into(), posted.format("%Y-%m-%d").to_string());
+        }
+
+        let title = item.title.unwrap_or_default();
+        if title.starts_with("Ask HN:") {
+            meta.tags.push("ask-hn".into());
+        } else if title.starts_with("Show HN:") {
+            meta.tags.push("show-hn".into());
+        }
+
+        Ok(meta)
+    }
+}
+
+/// Plain text of an item's HTML `text`, paragraphs separated by blank lines.
+fn strip_html(html: &str) -> String {
+    let fragment = scraper::Html::parse_fragment(&html.replace("<p>", "\n\n<p>"));
+    fragment
+        .root_element()
+        .text()
+        .collect::<String>()
+        .split("\n\n")
+        .map(clean_text)
+        .filter(|p| !p.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_item_urls() {
+        let source = |url: &str| HackerNewsExtractor.source_url(&reqwest::Url::parse(url).unwrap());
+        assert_eq!(
+            source("https://news.ycombinator.com/item?id=121003&p=2").as_deref(),
+            Some("https://hacker-news.firebaseio.com/v0/item/121003.json")
+        );
+        assert_eq!(source("https://news.ycombinator.com/item?id=abc"), None);
+        assert_eq!(source("https://news.ycombinator.com/news"), None);
+    }
+
+    #[test]
+    fn test_extract_ask_hn() {
+        let url = reqwest::Url::parse("https://news.ycombinator.com/item?id=121003").unwrap();
+        let meta = HackerNewsExtractor
+            .extract(&url, include_str!("fixtures/hn_item.json"))
+            .unwrap();
+
+        assert_eq!(meta.title.as_deref(), Some("Ask HN: The Arc Effect"));
+        assert_eq!(
+            meta.description.as_deref(),
+            Some("This is a test to see if Arc can handle <tags> & entities.\n\nSecond paragraph with a link.")
+        );
+        assert_eq!(meta.canonical_url.as_deref(), Some("https://news.ycombinator.com/item?id=121003"));
+        assert_eq!(meta.fields["author"], "tel");
+        assert_eq!(meta.fields["points"], "25");
+        assert_eq!(meta.fields["comments"], "16");
+        assert_eq!(meta.fields["posted"], "2008-02-22");
+        assert!(!meta.fields.contains_key("link"));
+        assert_eq!(meta.tags, ["ask-hn"]);
+    }
+
+    #[test]
+    fn test_missing_item_fails() {
+        let url = reqwest::Url::parse("https://news.ycombinator.com/item?id=1").unwrap();
+        assert!(HackerNewsExtractor.extract(&url, "null").is_err());
+    }
+}
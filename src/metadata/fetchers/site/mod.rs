@@ -0,0 +1,180 @@
+//! Extractors for sites generic scraping handles poorly. Each one knows
+//! where to get structured data for the URLs it handles (an API, or the page
+//! itself) and how to read it. `SiteFetcher` runs the first that matches.
+
+pub mod arxiv;
+pub mod github;
+pub mod hackernews;
+pub mod stackoverflow;
+pub mod youtube;
+
+use crate::config::ScrapeConfig;
+use crate::metadata::fetchers::MetadataFetcher;
+use crate::metadata::types::{FetchOutcome, Metadata};
+
+pub trait SiteExtractor: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    /// Where to fetch structured data for `url` from, or `None` when this
+    /// extractor doesn't handle it.
+    fn source_url(&self, url: &reqwest::Url) -> Option<String>;
+
+    /// Read the response fetched from `source_url`. Suggested tags go to
+    /// `Metadata::tags`; the fetcher drops them unless `scrape.site_tags` is on.
+    fn extract(&self, url: &reqwest::Url, body: &str) -> anyhow::Result<Metadata>;
+}
+
+pub fn extractors() -> Vec<Box<dyn SiteExtractor>> {
+    vec![
+        Box::new(github::GithubExtractor),
+        Box::new(arxiv::ArxivExtractor),
+        Box::new(hackernews::HackerNewsExtractor),
+        Box::new(youtube::YoutubeExtractor),
+        Box::new(stackoverflow::StackOverflowExtractor),
+    ]
+}
+
+pub struct SiteFetcher {
+    extractors: Vec<Box<dyn SiteExtractor>>,
+}
+
+impl SiteFetcher {
+    pub fn new() -> Self {
+        Self { extractors: extractors() }
+    }
+}
+
+impl MetadataFetcher for SiteFetcher {
+    fn fetch(&self, url: &str, scrape_config: Option<&ScrapeConfig>) -> anyhow::Result<FetchOutcome> {
+        let Ok(parsed) = reqwest::Url::parse(url) else {
+            return Ok(FetchOutcome::Skip("invalid URL".into()));
+        };
+        let Some((extractor, source)) = self
+            .extractors
+            .iter()
+            .find_map(|e| e.source_url(&parsed).map(|source| (e, source)))
+        else {
+            return Ok(FetchOutcome::Skip("no site extractor for URL".into()));
+        };
+
+        let name = extractor.name();
+        log::debug!("site extractor {name} fetching {source}");
+        let bytes = match crate::scrape::reqwest_with_retries(&source, scrape_config) {
+            Some((status, bytes)) if status.is_success() => bytes,
+            Some((status, _)) => anyhow::bail!("{name}: {source} returned status {status}"),
+            None => anyhow::bail!("{name}: request to {source} failed"),
+        };
+
+        let mut meta = extractor.extract(&parsed, &String::from_utf8_lossy(&bytes))?;
+        if !scrape_config.is_some_and(|c| c.site_tags) {
+            meta.tags.clear();
+        }
+        if meta.image.is_none() {
+            meta.try_fetch_image(scrape_config);
+        }
+
+        if meta.has_any_data() || !meta.fields.is_empty() {
+            Ok(FetchOutcome::Data(meta))
+        } else {
+            Ok(FetchOutcome::Skip(format!("{name} found nothing")))
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        "Site"
+    }
+}
+
+/// Host of `url` without a `www.` or `m.` prefix.
+fn site_host(url: &reqwest::Url) -> Option<&str> {
+    let host = url.host_str()?;
+    Some(
+        host.strip_prefix("www.")
+            .or_else(|| host.strip_prefix("m."))
+            .unwrap_or(host),
+    )
+}
+
+/// Non-empty path segments of `url`.
+fn segments(url: &reqwest::Url) -> Vec<&str> {
+    url.path_segments()
+        .map(|s| s.filter(|s| !s.is_empty()).collect())
+        .unwrap_or_default()
+}
+
+/// Trimmed `content` attribute of the first element matching `selector`.
+fn attr_content(doc: &scraper::Html, selector: &str) -> Option<String> {
+    let selector = scraper::Selector::parse(selector).ok()?;
+    doc.select(&selector)
+        .find_map(|el| el.value().attr("content"))
+        .map(clean_text)
+        .filter(|s| !s.is_empty())
+}
+
+/// Text of the first element matching `selector`, whitespace collapsed.
+fn text_of(doc: &scraper::Html, selector: &str) -> Option<String> {
+    let selector = scraper::Selector::parse(selector).ok()?;
+    doc.select(&selector)
+        .next()
+        .map(|el| clean_text(&el.text().collect::<String>()))
+        .filter(|s| !s.is_empty())
+}
+
+fn clean_text(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Tag form of a site value: lowercase, words joined by `-`.
+fn to_tag(value: &str) -> String {
+    value
+        .split_whitespace()
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_first_matching_extractor_wins() {
+        let sources: Vec<_> = [
+            "https://github.com/rust-lang/rust",
+            "https://arxiv.org/pdf/1706.03762v7",
+            "https://news.ycombinator.com/item?id=38471822",
+            "https://youtu.be/dQw4w9WgXcQ",
+            "https://stackoverflow.com/questions/1642028/what-is-the-operator",
+            "https://example.com/",
+        ]
+        .iter()
+        .map(|url| {
+            let url = reqwest::Url::parse(url).unwrap();
+            extractors()
+                .iter()
+                .find_map(|e| e.source_url(&url).map(|source| (e.name(), source)))
+        })
+        .collect();
+
+        assert_eq!(
+            sources,
+            vec![
+                Some(("GitHub", "https://api.github.com/repos/rust-lang/rust".to_string())),
+                Some(("arXiv", "https://arxiv.org/abs/1706.03762v7".to_string())),
+                Some((
+                    "Hacker News",
+                    "https://hacker-news.firebaseio.com/v0/item/38471822.json".to_string()
+                )),
+                Some(("YouTube", "https://www.youtube.com/watch?v=dQw4w9WgXcQ".to_string())),
+                Some(("Stack Overflow", "https://stackoverflow.com/questions/1642028".to_string())),
+                None,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_to_tag() {
+        assert_eq!(to_tag("Jupyter Notebook"), "jupyter-notebook");
+        assert_eq!(to_tag(" Rust "), "rust");
+    }
+}
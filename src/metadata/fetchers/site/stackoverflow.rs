@@ -0,0 +1,131 @@
+use super::{attr_content, clean_text, segments, site_host, text_of, SiteExtractor};
+use crate::metadata::types::Metadata;
+
+/// Stack Exchange sites that live on their own domain.
+const HOSTS: [&str; 5] = [
+    "stackoverflow.com",
+    "superuser.com",
+    "serverfault.com",
+    "askubuntu.com",
+    "mathoverflow.net",
+];
+
+/// Questions on Stack Overflow and the other Stack Exchange sites, read from
+/// the question page (the API only answers gzip-compressed).
+pub struct StackOverflowExtractor;
+
+impl SiteExtractor for StackOverflowExtractor {
+    fn name(&self) -> &'static str {
+        "Stack Overflow"
+    }
+
+    fn source_url(&self, url: &reqwest::Url) -> Option<String> {
+        let host = site_host(url)?;
+        if !HOSTS.contains(&host) && !host.ends_with(".stackexchange.com") {
+            return None;
+        }
+        match segments(url)[..] {
+            ["questions" | "q", id, ..] if id.parse::<u64>().is_ok() => {
+                Some(format!("https://{host}/questions/{id}"))
+            }
+            _ => None,
+        }
+    }
+
+    fn extract(&self, url: &reqwest::Url, body: &str) -> anyhow::Result<Metadata> {
+        let doc = scraper::Html::parse_document(body);
+        let title = text_of(&doc, "#question-header h1")
+            .or_else(|| attr_content(&doc, r#"meta[property="og:title"]"#))
+            .ok_or_else(|| anyhow::anyhow!("no question title on {url}"))?;
+
+        let mut meta = Metadata {
+            title: Some(title),
+            description: attr_content(&doc, r#"meta[property="og:description"]"#),
+            canonical_url: self.source_url(url),
+            image_url: attr_content(&doc, r#"meta[property="og:image"]"#),
+            ..Default::default()
+        };
+
+        let tags: Vec<String> = scraper::Selector::parse("#question .post-taglist .post-tag")
+            .map(|selector| {
+                doc.select(&selector)
+                    .map(|el| clean_text(&el.text().collect::<String>()))
+                    .filter(|tag| !tag.is_empty())
+                    .collect()
+            })
+            .unwrap_or_default();
+        if !tags.is_empty() {
+            meta.fields.insert("tags".into(), tags.join(", "));
+            meta.tags = tags;
+        }
+
+        let question = scraper::Selector::parse("#question").unwrap();
+        let vote_count = scraper::Selector::parse(".js-vote-count[data-value]").unwrap();
+        if let Some(score) = doc
+            .select(&question)
+            .next()
+            .and_then(|q| q.select(&vote_count).next())
+            .and_then(|el| el.value().attr("data-value"))
+        {
+            meta.fields.insert("score".into(), score.to_string());
+        }
+
+        let answers = scraper::Selector::parse("#answers-header [data-answercount]").unwrap();
+        if let Some(count) = doc
+            .select(&answers)
+            .next()
+            .and_then(|el| el.value().attr("data-answercount"))
+        {
+            meta.fields.insert("answers".into(), count.to_string());
+        }
+
+        let accepted = scraper::Selector::parse(".accepted-answer").unwrap();
+        meta.fields.insert(
+            "accepted".into(),
+            doc.select(&accepted).next().is_some().to_string(),
+        );
+
+        Ok(meta)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_question_urls() {
+        let source = |url: &str| StackOverflowExtractor.source_url(&reqwest::Url::parse(url).unwrap());
+        assert_eq!(
+            source("https://stackoverflow.com/questions/1642028/what-is-the-operator-in-c#1642035").as_deref(),
+            Some("https://stackoverflow.com/questions/1642028")
+        );
+        assert_eq!(
+            source("https://unix.stackexchange.com/q/12345/99").as_deref(),
+            Some("https://unix.stackexchange.com/questions/12345")
+        );
+        assert_eq!(source("https://stackoverflow.com/questions/tagged/rust"), None);
+        assert_eq!(source("https://stackoverflow.com/users/1/jeff-atwood"), None);
+    }
+
+    #[test]
+    fn test_extract_question() {
+        let url = reqwest::Url::parse("https://stackoverflow.com/questions/1642028/what-is-the-operator-in-c").unwrap();
+        let meta = StackOverflowExtractor
+            .extract(&url, include_str!("fixtures/stackoverflow_question.html"))
+            .unwrap();
+
+        assert_eq!(meta.title.as_deref(), Some("What is the '-->' operator in C/C++?"));
+        assert!(meta
+            .description
+            .as_deref()
+            .unwrap()
+            .starts_with("After reading Hidden Features and Dark Corners of C++/STL"));
+        assert_eq!(meta.canonical_url.as_deref(), Some("https://stackoverflow.com/questions/1642028"));
+        assert_eq!(meta.fields["tags"], "c++, c, operators, code-formatting, standards-compliance");
+        assert_eq!(meta.fields["score"], "10112");
+        assert_eq!(meta.fields["answers"], "26");
+        assert_eq!(meta.fields["accepted"], "true");
+        assert_eq!(meta.tags, ["c++", "c", "operators", "code-formatting", "standards-compliance"]);
+    }
+}
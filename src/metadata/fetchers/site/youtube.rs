@@ -0,0 +1,138 @@
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use super::{attr_content, segments, site_host, to_tag, SiteExtractor};
+use crate::metadata::types::Metadata;
+
+static VIDEO_ID: Lazy<Regex> = Lazy::new(|| Regex::new(r"^[A-Za-z0-9_-]{11}$").unwrap());
+
+/// ISO 8601 durations as used in `itemprop="duration"`, e.g. `PT1H2M3S`.
+static DURATION: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^PT(?:(\d+)H)?(?:(\d+)M)?(?:(\d+)S)?$").unwrap());
+
+/// Videos, read from the microdata on their watch page.
+pub struct YoutubeExtractor;
+
+impl SiteExtractor for YoutubeExtractor {
+    fn name(&self) -> &'static str {
+        "YouTube"
+    }
+
+    fn source_url(&self, url: &reqwest::Url) -> Option<String> {
+        let id = match (site_host(url)?, &segments(url)[..]) {
+            ("youtu.be", [id]) => id.to_string(),
+            ("youtube.com", ["shorts" | "live" | "embed", id]) => id.to_string(),
+            ("youtube.com", ["watch"]) => url
+                .query_pairs()
+                .find(|(key, _)| key == "v")
+                .map(|(_, id)| id.into_owned())?,
+            _ => return None,
+        };
+        VIDEO_ID
+            .is_match(&id)
+            .then(|| format!("https://www.youtube.com/watch?v={id}"))
+    }
+
+    fn extract(&self, url: &reqwest::Url, body: &str) -> anyhow::Result<Metadata> {
+        let doc = scraper::Html::parse_document(body);
+        let title = attr_content(&doc, r#"meta[name="title"]"#)
+            .or_else(|| attr_content(&doc, r#"meta[property="og:title"]"#))
+            .ok_or_else(|| anyhow::anyhow!("no video title on {url}"))?;
+
+        let mut meta = Metadata {
+            title: Some(title),
+            description: attr_content(&doc, r#"meta[property="og:description"]"#)
+                .or_else(|| attr_content(&doc, r#"meta[name="description"]"#)),
+            canonical_url: self.source_url(url),
+            image_url: attr_content(&doc, r#"meta[property="og:image"]"#),
+            ..Default::default()
+        };
+
+        if let Some(channel) = attr_content(&doc, r#"[itemprop="author"] [itemprop="name"]"#) {
+            meta.fields.insert("channel".into(), channel);
+        }
+        if let Some(duration) =
+            attr_content(&doc, r#"meta[itemprop="duration"]"#).and_then(|d| format_duration(&d))
+        {
+            meta.fields.insert("duration".into(), duration);
+        }
+        if let Some(published) = attr_content(&doc, r#"meta[itemprop="datePublished"]"#)
+            .or_else(|| attr_content(&doc, r#"meta[itemprop="uploadDate"]"#))
+        {
+            let date = published.split('T').next().unwrap_or(&published).to_string();
+            meta.fields.insert("published".into(), date);
+        }
+        if let Some(views) = attr_content(&doc, r#"meta[itemprop="interactionCount"]"#) {
+            meta.fields.insert("views".into(), views);
+        }
+        if let Some(genre) = attr_content(&doc, r#"meta[itemprop="genre"]"#) {
+            meta.tags.push(to_tag(&genre));
+            meta.fields.insert("genre".into(), genre);
+        }
+
+        Ok(meta)
+    }
+}
+
+/// `PT1H2M3S` -> `1:02:03`, `PT4M5S` -> `4:05`.
+fn format_duration(iso: &str) -> Option<String> {
+    let caps = DURATION.captures(iso)?;
+    let part = |i: usize| caps.get(i).map_or(0, |m| m.as_str().parse::<u64>().unwrap_or(0));
+    let seconds = part(1) * 3600 + part(2) * 60 + part(3);
+    Some(match seconds / 3600 {
+        0 => format!("{}:{:02}", seconds / 60, seconds % 60),
+        hours => format!("{hours}:{:02}:{:02}", seconds % 3600 / 60, seconds % 60),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_video_urls() {
+        let source = |url: &str| YoutubeExtractor.source_url(&reqwest::Url::parse(url).unwrap());
+        let watch = Some("https://www.youtube.com/watch?v=dQw4w9WgXcQ");
+        assert_eq!(source("https://m.youtube.com/watch?feature=share&v=dQw4w9WgXcQ").as_deref(), watch);
+        assert_eq!(source("https://youtu.be/dQw4w9WgXcQ?t=42").as_deref(), watch);
+        assert_eq!(source("https://www.youtube.com/shorts/dQw4w9WgXcQ").as_deref(), watch);
+        assert_eq!(source("https://www.youtube.com/@RickAstleyYT"), None);
+        assert_eq!(source("https://www.youtube.com/watch?v=short"), None);
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration("PT3M33S").as_deref(), Some("3:33"));
+        assert_eq!(format_duration("PT1H2M3S").as_deref(), Some("1:02:03"));
+        assert_eq!(format_duration("PT45S").as_deref(), Some("0:45"));
+        assert_eq!(format_duration("P1D"), None);
+    }
+
+    #[test]
+    fn test_extract_video() {
+        let url = reqwest::Url::parse("https://youtu.be/dQw4w9WgXcQ").unwrap();
+        let meta = YoutubeExtractor
+            .extract(&url, include_str!("fixtures/youtube_watch.html"))
+            .unwrap();
+
+        assert_eq!(
+            meta.title.as_deref(),
+            Some("Rick Astley - Never Gonna Give You Up (Official Music Video)")
+        );
+        assert!(meta.description.as_deref().unwrap().starts_with("The official video for"));
+        assert_eq!(
+            meta.canonical_url.as_deref(),
+            Some("https://www.youtube.com/watch?v=dQw4w9WgXcQ")
+        );
+        assert_eq!(
+            meta.image_url.as_deref(),
+            Some("https://i.ytimg.com/vi/dQw4w9WgXcQ/maxresdefault.jpg")
+        );
+        assert_eq!(meta.fields["channel"], "Rick Astley");
+        assert_eq!(meta.fields["duration"], "3:33");
+        assert_eq!(meta.fields["published"], "2009-10-24");
+        assert_eq!(meta.fields["views"], "1577414066");
+        assert_eq!(meta.fields["genre"], "Music");
+        assert_eq!(meta.tags, ["music"]);
+    }
+}
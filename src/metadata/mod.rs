@@ -2,6 +2,8 @@ pub mod types;
 pub mod fetchers;
 pub mod normalize;
 pub mod image_validation;
+pub mod cache;
+pub mod reports;
 
 pub use types::{Metadata, MetaOptions, MetadataReport, FieldDecision};
 pub use fetchers::FetcherRegistry;
@@ -12,7 +14,7 @@ use anyhow::Result;
 pub fn fetch_meta(url: &str, opts: MetaOptions) -> Result<(Metadata, MetadataReport)> {
     let url = &normalize::normalize_url(url);
     let scrape_config = opts.scrape_config.as_ref();
-    let registry = FetcherRegistry::new(&opts);
+    let registry = FetcherRegistry::new(url, &opts);
 
     let (meta_opt, mut report) = registry.fetch_metadata(url, &opts)?;
     let mut metadata = meta_opt.unwrap_or_default();
@@ -0,0 +1,191 @@
+//! The last few metadata reports of each bookmark, kept as one
+//! `metadata_reports/<id>.json` file per bookmark next to `bookmarks.csv` so a
+//! bad title can be traced back to the fetcher that picked it.
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+use std::sync::{Arc, Mutex, RwLock};
+
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+
+use super::MetadataReport;
+use crate::search_query::MetadataSources;
+use crate::storage::{self, StorageManager};
+
+const REPORTS_DIR: &str = "metadata_reports";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StoredReport {
+    /// RFC 3339, UTC
+    pub fetched_at: String,
+    pub url: String,
+    #[serde(flatten)]
+    pub report: MetadataReport,
+}
+
+impl StoredReport {
+    /// Field -> fetcher that supplied it. Later decisions override earlier
+    /// ones, e.g. a generic title replaced by the headless fallback.
+    pub fn sources(&self) -> HashMap<String, String> {
+        self.report
+            .field_decisions
+            .iter()
+            .map(|d| (d.field.clone(), d.winner.clone()))
+            .collect()
+    }
+}
+
+pub struct ReportStore {
+    dir: String,
+    /// Newest first
+    reports: Mutex<BTreeMap<u64, Vec<StoredReport>>>,
+    /// Field sources of each bookmark's latest report, for `source:` terms.
+    /// Swapped for a new map on change so searches keep a consistent snapshot.
+    sources: RwLock<Arc<MetadataSources>>,
+}
+
+impl ReportStore {
+    /// Load the reports under `base_path`. Unreadable files are skipped.
+    pub fn load(base_path: &str) -> Self {
+        let dir = Path::new(base_path).join(REPORTS_DIR).to_string_lossy().into_owned();
+        let reports = match Self::read(&dir) {
+            Ok(reports) => reports,
+            Err(err) => {
+                log::warn!("ignoring {REPORTS_DIR}: {err:#}");
+                BTreeMap::new()
+            }
+        };
+
+        let sources = reports
+            .iter()
+            .filter_map(|(id, list)| Some((*id, latest_sources(list)?)))
+            .collect();
+
+        Self {
+            dir,
+            reports: Mutex::new(reports),
+            sources: RwLock::new(Arc::new(sources)),
+        }
+    }
+
+    fn read(dir: &str) -> anyhow::Result<BTreeMap<u64, Vec<StoredReport>>> {
+        let store = storage::BackendLocal::new(dir)?;
+        let mut reports = BTreeMap::new();
+        for name in store.list() {
+            // Skips in-flight temp files too, they are `<eid>-<id>.json`
+            let Some(id) = name.strip_suffix(".json").and_then(|id| id.parse::<u64>().ok()) else {
+                continue;
+            };
+            let parsed = store
+                .read(&name)
+                .map_err(anyhow::Error::from)
+                .and_then(|data| serde_json::from_slice(&data).context("malformed"));
+            match parsed {
+                Ok(list) => {
+                    reports.insert(id, list);
+                }
+                Err(err) => log::warn!("ignoring {REPORTS_DIR}/{name}: {err:#}"),
+            }
+        }
+        Ok(reports)
+    }
+
+    fn file_name(id: u64) -> String {
+        format!("{id}.json")
+    }
+
+    /// Add the report of a fetch for bookmark `id`, keeping the newest `keep`.
+    /// Only that bookmark's file is rewritten.
+    pub fn record(&self, id: u64, url: &str, report: &MetadataReport, keep: usize) -> anyhow::Result<()> {
+        let store = storage::BackendLocal::new(&self.dir)?;
+        let mut reports = self.reports.lock().unwrap();
+        let list = reports.entry(id).or_default();
+        list.insert(
+            0,
+            StoredReport {
+                fetched_at: chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+                url: url.to_string(),
+                report: report.clone(),
+            },
+        );
+        list.truncate(keep);
+
+        let latest = latest_sources(list);
+        let written = if list.is_empty() {
+            reports.remove(&id);
+            Self::delete_file(&store, id)
+        } else {
+            serde_json::to_vec(list)
+                .map_err(anyhow::Error::from)
+                .and_then(|data| Ok(store.write(&Self::file_name(id), &data)?))
+        };
+        self.update_sources(|sources| {
+            match latest {
+                Some(fields) => sources.insert(id, fields),
+                None => sources.remove(&id),
+            };
+        });
+
+        written.context("failed to write metadata reports")
+    }
+
+    /// Reports of bookmark `id`, newest first.
+    pub fn get(&self, id: u64) -> Vec<StoredReport> {
+        self.reports
+            .lock()
+            .unwrap()
+            .get(&id)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Field sources of every bookmark's latest report.
+    pub fn sources(&self) -> Arc<MetadataSources> {
+        self.sources.read().unwrap().clone()
+    }
+
+    /// Drop the reports of deleted bookmarks, whose ids may be reused.
+    pub fn remove(&self, ids: &[u64]) -> anyhow::Result<()> {
+        let store = storage::BackendLocal::new(&self.dir)?;
+        let mut reports = self.reports.lock().unwrap();
+        let removed: Vec<u64> = ids.iter().copied().filter(|id| reports.remove(id).is_some()).collect();
+        if removed.is_empty() {
+            return Ok(());
+        }
+
+        self.update_sources(|sources| {
+            for id in &removed {
+                sources.remove(id);
+            }
+        });
+        for id in removed {
+            Self::delete_file(&store, id).context("failed to delete metadata reports")?;
+        }
+        Ok(())
+    }
+
+    fn delete_file(store: &storage::BackendLocal, id: u64) -> anyhow::Result<()> {
+        let name = Self::file_name(id);
+        if store.exists(&name) {
+            store.delete(&name)?;
+        }
+        Ok(())
+    }
+
+    fn update_sources(&self, update: impl FnOnce(&mut MetadataSources)) {
+        let mut sources = self.sources.write().unwrap();
+        update(Arc::make_mut(&mut sources));
+    }
+}
+
+/// Lowercased field -> lowercased fetcher of the newest report in `list`.
+fn latest_sources(list: &[StoredReport]) -> Option<HashMap<String, String>> {
+    list.first().map(|report| {
+        report
+            .sources()
+            .into_iter()
+            .map(|(field, fetcher)| (field.to_lowercase(), fetcher.to_lowercase()))
+            .collect()
+    })
+}
@@ -1,6 +1,7 @@
 use crate::config::ScrapeConfig;
+use crate::scrape::robots::RobotsHints;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::time::Duration;
 
 /// Error types for metadata fetching, distinguishing retryable from terminal failures
@@ -36,6 +37,17 @@ pub struct Metadata {
     #[serde(skip_serializing, skip_deserializing)]
     pub icon: Option<Vec<u8>>,
     pub dump: Option<String>,
+    /// Site-specific structured fields, e.g. `language` and `stars` of a
+    /// GitHub repo or `authors` of an arXiv paper
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub fields: BTreeMap<String, String>,
+    /// Tags derived from `fields`, only set when `scrape.site_tags` is on
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// `noarchive`/`noimageindex` from the page's robots meta tag and
+    /// `X-Robots-Tag` header
+    #[serde(default, skip_serializing_if = "RobotsHints::is_empty")]
+    pub robots: RobotsHints,
     /// Whether the image bytes have been validated (magic bytes, dimensions, decode)
     #[serde(skip_serializing, skip_deserializing)]
     pub image_valid: bool,
@@ -55,9 +67,13 @@ pub struct MetaOptions {
     /// Per-fetcher timeout budget
     #[serde(skip)]
     pub fetcher_timeout: Option<Duration>,
+    /// Call every fetcher even when the metadata cache has a fresh result
+    #[serde(default)]
+    pub no_cache: bool,
 }
 
 /// Outcome of a single fetcher attempt
+#[derive(Debug)]
 pub enum FetchOutcome {
     Data(Metadata),
     Skip(String),
@@ -114,6 +130,9 @@ pub struct FetcherReport {
     pub status: FetcherStatus,
     pub duration_ms: u64,
     pub fields: Option<FetcherFields>,
+    /// Result came from the metadata cache instead of a request
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub cached: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -134,6 +153,10 @@ pub struct FetcherFields {
     pub icon_url: Option<String>,
     pub has_image_bytes: bool,
     pub has_icon_bytes: bool,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub fields: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -0,0 +1,603 @@
+mod suggest;
+
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
+
+use crate::search_query::SavedSearches;
+
+pub use suggest::{suggest, SuggestOpts};
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Rule {
+    /// Stable id for the CLI and API. Assigned when rules are loaded or
+    /// saved; 0 until then.
+    #[serde(default, skip_serializing_if = "is_zero_id")]
+    pub id: u64,
+
+    /// Disabled rules stay in the file but never run.
+    #[serde(default = "default_enabled", skip_serializing_if = "is_enabled")]
+    pub enabled: bool,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub query: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+
+    /// When the rule runs; `on_create` and `after_metadata` when empty.
+    /// Accepts a single trigger or a list.
+    #[serde(default, deserialize_with = "one_or_many", skip_serializing_if = "Vec::is_empty")]
+    pub when: Vec<Trigger>,
+
+    /// Don't evaluate any further rules once this one has matched.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub stop: bool,
+
+    /// Higher priorities run first; equal priorities keep file order.
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub priority: i32,
+
+    pub action: Action,
+
+    /// `url`/`title`/`description` patterns, compiled on first use or by
+    /// `Rule::compile`, and the action's regex.
+    #[serde(skip)]
+    pub compiled: CompiledPatterns,
+}
+
+/// A `url`/`title`/`description` condition: a case-insensitive substring, or
+/// a regex written `r/pattern/` with optional `i`, `m`, `s` and `x` flags
+/// after the closing slash.
+#[derive(Clone, Debug)]
+pub enum Pattern {
+    Contains(String),
+    Regex(Regex),
+}
+
+impl Pattern {
+    pub fn parse(pattern: &str) -> Result<Self, String> {
+        let Some(rest) = pattern.strip_prefix("r/") else {
+            return Ok(Pattern::Contains(pattern.to_lowercase()));
+        };
+        let Some((body, flags)) = rest.rsplit_once('/') else {
+            return Err(format!("invalid regex '{pattern}': missing closing '/'"));
+        };
+
+        let mut builder = RegexBuilder::new(body);
+        for flag in flags.chars() {
+            match flag {
+                'i' => builder.case_insensitive(true),
+                'm' => builder.multi_line(true),
+                's' => builder.dot_matches_new_line(true),
+                'x' => builder.ignore_whitespace(true),
+                _ => {
+                    return Err(format!(
+                        "invalid regex '{pattern}': unsupported flag '{flag}', expected i, m, s or x"
+                    ))
+                }
+            };
+        }
+
+        builder
+            .build()
+            .map(Pattern::Regex)
+            .map_err(|e| format!("invalid regex '{pattern}': {e}"))
+    }
+
+    pub fn is_match(&self, input: &str) -> bool {
+        match self {
+            Pattern::Contains(needle) => input.to_lowercase().contains(needle),
+            Pattern::Regex(regex) => regex.is_match(input),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Patterns {
+    url: Option<Pattern>,
+    title: Option<Pattern>,
+    description: Option<Pattern>,
+}
+
+/// Compiled once per rule. Rules are replaced rather than edited in place,
+/// so the cache never goes stale.
+#[derive(Clone, Debug, Default)]
+pub struct CompiledPatterns {
+    conditions: OnceLock<Result<Patterns, String>>,
+    /// The `RewriteUrl`/`ReplaceDescription` pattern
+    action: OnceLock<Result<Option<Regex>, String>>,
+}
+
+/// Event that runs rules.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Trigger {
+    /// The bookmark was added.
+    OnCreate,
+    /// The bookmark was edited.
+    OnUpdate,
+    /// Metadata was fetched, on add or on refresh.
+    AfterMetadata,
+    /// Only when rules are applied by hand. Applying by hand runs every rule.
+    Manual,
+}
+
+/// Triggers for rules without a `when:`, matching how rules ran before
+/// triggers existed.
+const DEFAULT_TRIGGERS: [Trigger; 2] = [Trigger::OnCreate, Trigger::AfterMetadata];
+
+fn is_zero(n: &i32) -> bool {
+    *n == 0
+}
+
+fn is_zero_id(id: &u64) -> bool {
+    *id == 0
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+fn is_enabled(enabled: &bool) -> bool {
+    *enabled
+}
+
+fn one_or_many<'de, D>(deserializer: D) -> Result<Vec<Trigger>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany {
+        One(Trigger),
+        Many(Vec<Trigger>),
+    }
+
+    Ok(match OneOrMany::deserialize(deserializer)? {
+        OneOrMany::One(trigger) => vec![trigger],
+        OneOrMany::Many(triggers) => triggers,
+    })
+}
+
+/// The rules that run for any of `triggers` with their index in `rules`, in
+/// evaluation order: by descending priority, then file order.
+pub fn in_order<'a>(rules: &'a [Rule], triggers: &[Trigger]) -> Vec<(usize, &'a Rule)> {
+    let mut ordered: Vec<(usize, &Rule)> = rules
+        .iter()
+        .enumerate()
+        .filter(|(_, rule)| triggers.iter().any(|t| rule.runs_on(*t)))
+        .collect();
+    ordered.sort_by_key(|(_, rule)| std::cmp::Reverse(rule.priority));
+    ordered
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Record {
+    pub url: String,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub tags: Option<Vec<String>>,
+}
+
+impl Record {
+    /// The bookmark's fields as rules see them; empty fields are unset.
+    pub fn from_bookmark(bm: &crate::bookmarks::Bookmark) -> Self {
+        Self {
+            url: bm.url.clone(),
+            title: (!bm.title.is_empty()).then(|| bm.title.clone()),
+            description: (!bm.description.is_empty()).then(|| bm.description.clone()),
+            tags: (!bm.tags.is_empty()).then(|| bm.tags.clone()),
+        }
+    }
+}
+
+/// Fields `{{name}}` templates can use.
+const TEMPLATE_FIELDS: [&str; 5] = ["url", "domain", "title", "description", "tags"];
+
+/// What an action's templates can refer to.
+#[derive(Debug, Clone, Default)]
+pub struct TemplateContext {
+    /// `$0` is the whole match of the first regex condition; `$1` onwards
+    /// are the groups of the url, title and description regexes, in order.
+    groups: Vec<String>,
+    named: std::collections::HashMap<String, String>,
+    record: Record,
+}
+
+impl TemplateContext {
+    pub fn from_record(record: &Record) -> Self {
+        Self {
+            record: record.clone(),
+            ..Default::default()
+        }
+    }
+
+    fn field(&self, name: &str) -> Option<String> {
+        let record = &self.record;
+        match name {
+            "url" => Some(record.url.clone()),
+            "domain" => Some(crate::search_query::url_host(&record.url).unwrap_or_default()),
+            "title" => Some(record.title.clone().unwrap_or_default()),
+            "description" => Some(record.description.clone().unwrap_or_default()),
+            "tags" => Some(record.tags.clone().unwrap_or_default().join(",")),
+            _ => None,
+        }
+    }
+
+    fn group(&self, name: &str) -> &str {
+        let value = match name.parse::<usize>() {
+            Ok(idx) => self.groups.get(idx),
+            Err(_) => self.named.get(name),
+        };
+        value.map(String::as_str).unwrap_or_default()
+    }
+
+    /// Fill in `template`: `$1` or `${1}` is a capture group, `${name}` a
+    /// named group, `{{domain}}` a bookmark field (`url`, `domain`, `title`,
+    /// `description`, `tags`) and `$$` a literal `$`. Missing groups are
+    /// empty; anything else is copied as is.
+    pub fn render(&self, template: &str) -> String {
+        let mut out = String::with_capacity(template.len());
+        let mut rest = template;
+
+        while let Some(pos) = rest.find(['$', '{']) {
+            out.push_str(&rest[..pos]);
+            let tail = &rest[pos..];
+
+            if let Some(after) = tail.strip_prefix("$$") {
+                out.push('$');
+                rest = after;
+                continue;
+            }
+            if let Some((name, after)) = tail.strip_prefix("${").and_then(|t| t.split_once('}')) {
+                out.push_str(self.group(name));
+                rest = after;
+                continue;
+            }
+            if let Some(after) = tail.strip_prefix('$') {
+                let digits = after.len() - after.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+                if digits > 0 {
+                    out.push_str(self.group(&after[..digits]));
+                    rest = &after[digits..];
+                    continue;
+                }
+            }
+            if let Some((name, after)) = tail.strip_prefix("{{").and_then(|t| t.split_once("}}")) {
+                if let Some(value) = self.field(name.trim()) {
+                    out.push_str(&value);
+                    rest = after;
+                    continue;
+                }
+            }
+
+            // a lone `$` or `{`, both one byte
+            out.push_str(&tail[..1]);
+            rest = &tail[1..];
+        }
+
+        out.push_str(rest);
+        out
+    }
+}
+
+/// Reject `{{name}}` references to fields templates don't know.
+fn check_template(template: &str) -> Result<(), String> {
+    let mut rest = template;
+    while let Some((_, after)) = rest.split_once("{{") {
+        let Some((name, after)) = after.split_once("}}") else {
+            break;
+        };
+        if !TEMPLATE_FIELDS.contains(&name.trim()) {
+            return Err(format!(
+                "unknown template field '{{{{{}}}}}', expected one of {}",
+                name.trim(),
+                TEMPLATE_FIELDS.join(", ")
+            ));
+        }
+        rest = after;
+    }
+    Ok(())
+}
+
+impl Rule {
+    pub fn runs_on(&self, trigger: Trigger) -> bool {
+        if !self.enabled {
+            return false;
+        }
+        trigger == Trigger::Manual || self.triggers().contains(&trigger)
+    }
+
+    /// The `when:` triggers, or the defaults when none are given.
+    pub fn triggers(&self) -> &[Trigger] {
+        if self.when.is_empty() {
+            &DEFAULT_TRIGGERS
+        } else {
+            &self.when
+        }
+    }
+
+    /// `SkipMetadata` and `ForceHeadless` are read before the fetch from the
+    /// `after_metadata` rules only, so any other trigger would be a no-op.
+    pub fn validate_triggers(&self) -> Result<(), String> {
+        match self.action {
+            Action::SkipMetadata | Action::ForceHeadless
+                if !self.triggers().contains(&Trigger::AfterMetadata) =>
+            {
+                Err(format!("{:?} only takes effect on after_metadata", self.action))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Match a single pattern, compiling it on every call. An invalid
+    /// regex matches nothing.
+    #[cfg(test)]
+    pub fn is_string_matches(match_query: &str, input: &str) -> bool {
+        Pattern::parse(match_query).is_ok_and(|pattern| pattern.is_match(input))
+    }
+
+    /// Compile the rule's patterns, reporting the first invalid one.
+    pub fn compile(&self) -> Result<(), String> {
+        self.patterns().map(|_| ())
+    }
+
+    /// Capture groups of the rule's regex conditions on `record`, plus its
+    /// fields, for rendering the action. Call once the rule has matched.
+    pub fn template_context(&self, record: &Record) -> TemplateContext {
+        let mut ctx = TemplateContext::from_record(record);
+        let Ok(patterns) = self.patterns() else {
+            return ctx;
+        };
+
+        let conditions = [
+            (&patterns.url, Some(&record.url)),
+            (&patterns.title, record.title.as_ref()),
+            (&patterns.description, record.description.as_ref()),
+        ];
+        for (pattern, input) in conditions {
+            let (Some(Pattern::Regex(regex)), Some(input)) = (pattern, input) else {
+                continue;
+            };
+            let Some(caps) = regex.captures(input) else {
+                continue;
+            };
+
+            if ctx.groups.is_empty() {
+                ctx.groups.push(caps[0].to_string());
+            }
+            ctx.groups.extend(
+                caps.iter()
+                    .skip(1)
+                    .map(|group| group.map(|m| m.as_str().to_string()).unwrap_or_default()),
+            );
+            for name in regex.capture_names().flatten() {
+                if let Some(m) = caps.name(name) {
+                    ctx.named.entry(name.to_string()).or_insert_with(|| m.as_str().to_string());
+                }
+            }
+        }
+
+        ctx
+    }
+
+    /// The regex of a `RewriteUrl` or `ReplaceDescription` action, if it
+    /// has one.
+    pub fn action_regex(&self) -> Result<Option<&Regex>, String> {
+        self.compiled
+            .action
+            .get_or_init(|| {
+                let pattern = match &self.action {
+                    Action::RewriteUrl { pattern, .. } => pattern,
+                    Action::ReplaceDescription {
+                        pattern: Some(pattern),
+                        ..
+                    } => pattern,
+                    _ => return Ok(None),
+                };
+                Regex::new(pattern)
+                    .map(Some)
+                    .map_err(|e| format!("invalid action pattern '{pattern}': {e}"))
+            })
+            .as_ref()
+            .map(Option::as_ref)
+            .map_err(|e| e.clone())
+    }
+
+    fn patterns(&self) -> Result<&Patterns, String> {
+        self.compiled
+            .conditions
+            .get_or_init(|| {
+                let parse = |field: &Option<String>, name: &str| {
+                    field
+                        .as_deref()
+                        .map(Pattern::parse)
+                        .transpose()
+                        .map_err(|e| format!("{name}: {e}"))
+                };
+                Ok(Patterns {
+                    url: parse(&self.url, "url")?,
+                    title: parse(&self.title, "title")?,
+                    description: parse(&self.description, "description")?,
+                })
+            })
+            .as_ref()
+            .map_err(|e| e.clone())
+    }
+
+    /// Whether `record` meets every condition; `saved` resolves `@name`
+    /// references in the query.
+    pub fn is_match(&self, record: &Record, saved: &SavedSearches) -> bool {
+        // validation rejects these on load, but rules can still arrive unchecked
+        let patterns = match self.patterns() {
+            Ok(patterns) => patterns,
+            Err(err) => {
+                log::warn!("skipping rule {} with invalid pattern: {err}", self.id);
+                return false;
+            }
+        };
+
+        let mut has_any_condition = false;
+
+        if let Some(match_url) = &patterns.url {
+            has_any_condition = true;
+            if !match_url.is_match(&record.url) {
+                return false;
+            }
+        }
+
+        if let (Some(match_title), Some(record_title)) = (&patterns.title, &record.title) {
+            has_any_condition = true;
+            if !match_title.is_match(record_title) {
+                return false;
+            }
+        }
+
+        if let (Some(match_description), Some(record_description)) = (&patterns.description, &record.description) {
+            has_any_condition = true;
+            if !match_description.is_match(record_description) {
+                return false;
+            }
+        }
+
+        if let Some(match_tags) = &self.tags {
+            has_any_condition = true;
+            let record_tags = record.tags.clone().unwrap_or_default();
+
+            if match_tags.is_empty() {
+                if !record_tags.is_empty() {
+                    return false;
+                }
+            } else {
+                let record_tags_lower: Vec<String> =
+                    record_tags.iter().map(|t| t.to_lowercase()).collect();
+
+                for tag in match_tags {
+                    if !record_tags_lower.iter().any(|t| *t == tag.to_lowercase()) {
+                        return false;
+                    }
+                }
+            }
+        }
+
+        if let Some(query_str) = &self.query {
+            has_any_condition = true;
+            let temp_bookmark = crate::bookmarks::Bookmark {
+                id: 0,
+                url: record.url.clone(),
+                title: record.title.clone().unwrap_or_default(),
+                description: record.description.clone().unwrap_or_default(),
+                tags: record.tags.clone().unwrap_or_default(),
+                image_id: None,
+                icon_id: None,
+            };
+            match crate::search_query::parse_with(query_str, saved) {
+                // A record has no id to look up metadata sources by
+                Ok(filter) if crate::search_query::eval(&filter, &temp_bookmark, &Default::default()) => {}
+                _ => return false,
+            }
+        }
+
+        has_any_condition
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum Action {
+    /// Set the title or description and add tags. Values are templates, see
+    /// `TemplateContext::render`.
+    UpdateBookmark {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        title: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        description: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        tags: Option<Vec<String>>,
+    },
+    /// Drop these tags, compared case-insensitively.
+    RemoveTags { tags: Vec<String> },
+    /// Replace every match of the `pattern` regex in the URL, e.g. to turn
+    /// `old.reddit.com` into `reddit.com` or strip query parameters.
+    RewriteUrl { pattern: String, replacement: String },
+    /// Replace matches of `pattern` in the description, or the whole
+    /// description when there's no pattern.
+    ReplaceDescription {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pattern: Option<String>,
+        replacement: String,
+    },
+    /// Delete the bookmark. Later rules don't run.
+    Delete,
+    /// Don't fetch metadata. Checked before the fetch, against the bookmark
+    /// as added.
+    SkipMetadata,
+    /// Fetch metadata with headless Chrome, as if `scrape.always_headless`
+    /// were set. Checked before the fetch, like `SkipMetadata`.
+    ForceHeadless,
+}
+
+impl Action {
+    pub fn validate(&self) -> Result<(), String> {
+        match self {
+            Action::UpdateBookmark {
+                title,
+                description,
+                tags,
+            } => title
+                .iter()
+                .chain(description)
+                .chain(tags.iter().flatten())
+                .try_for_each(|template| check_template(template)),
+            Action::RemoveTags { tags } if tags.is_empty() => {
+                Err("RemoveTags needs at least one tag".to_string())
+            }
+            Action::RewriteUrl { pattern, .. } => Regex::new(pattern)
+                .map(|_| ())
+                .map_err(|e| format!("RewriteUrl pattern is not a valid regex: {e}")),
+            Action::ReplaceDescription {
+                pattern: Some(pattern),
+                ..
+            } => Regex::new(pattern)
+                .map(|_| ())
+                .map_err(|e| format!("ReplaceDescription pattern is not a valid regex: {e}")),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// How the rules matching a bookmark change its metadata fetch.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FetchDirectives {
+    pub skip_metadata: bool,
+    pub force_headless: bool,
+}
+
+impl FetchDirectives {
+    /// Directives from the `after_metadata` rules matching `record`, honoring
+    /// priority and `stop`.
+    pub fn from_rules(rules: &[Rule], record: &Record, saved: &SavedSearches) -> Self {
+        let mut directives = Self::default();
+        for (_, rule) in in_order(rules, &[Trigger::AfterMetadata]) {
+            if !rule.is_match(record, saved) {
+                continue;
+            }
+            match rule.action {
+                Action::SkipMetadata => directives.skip_metadata = true,
+                Action::ForceHeadless => directives.force_headless = true,
+                _ => {}
+            }
+            if rule.stop {
+                break;
+            }
+        }
+        directives
+    }
+}
@@ -0,0 +1,221 @@
+use std::collections::{BTreeSet, HashMap};
+
+use serde::Serialize;
+
+use super::{Action, Record, Rule};
+use crate::bookmarks::Bookmark;
+
+/// Title words too common to say anything about a tag.
+const STOPWORDS: [&str; 24] = [
+    "about", "also", "from", "have", "here", "home", "into", "just", "more", "only", "page",
+    "than", "that", "their", "them", "there", "they", "this", "using", "welcome", "what", "when",
+    "will", "with",
+];
+
+const MIN_KEYWORD_LEN: usize = 4;
+
+#[derive(Debug, Clone)]
+pub struct SuggestOpts {
+    /// Fewest bookmarks that must have both the condition and the tag.
+    pub min_support: usize,
+    /// Least share of the bookmarks with the condition that have the tag.
+    pub min_confidence: f32,
+    pub limit: usize,
+}
+
+impl Default for SuggestOpts {
+    fn default() -> Self {
+        Self {
+            min_support: 5,
+            min_confidence: 0.8,
+            limit: 20,
+        }
+    }
+}
+
+/// A candidate rule and the evidence for it.
+#[derive(Debug, Clone, Serialize)]
+pub struct Suggestion {
+    pub rule: Rule,
+    /// Bookmarks with both the condition and the tag.
+    pub support: usize,
+    /// `support` over the bookmarks with the condition.
+    pub confidence: f32,
+}
+
+/// Something a bookmark has that may predict one of its tags.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+enum Antecedent {
+    Domain(String),
+    /// Host and first path segment, e.g. `github.com/rust-lang`.
+    PathPrefix(String),
+    TitleWord(String),
+}
+
+impl Antecedent {
+    fn of(bmark: &Bookmark) -> BTreeSet<Antecedent> {
+        let mut found = BTreeSet::new();
+
+        if let Some(host) = crate::search_query::url_host(&bmark.url) {
+            let segment = bmark
+                .url
+                .split_once("://")
+                .map_or(bmark.url.as_str(), |(_, rest)| rest)
+                .split(['?', '#'])
+                .next()
+                .and_then(|rest| rest.split('/').nth(1))
+                .filter(|segment| !segment.is_empty());
+            if let Some(segment) = segment {
+                found.insert(Antecedent::PathPrefix(format!("{host}/{}", segment.to_lowercase())));
+            }
+            found.insert(Antecedent::Domain(host));
+        }
+
+        for word in bmark.title.split(|c: char| !c.is_alphanumeric()) {
+            let word = word.to_lowercase();
+            if word.chars().count() >= MIN_KEYWORD_LEN
+                && !word.chars().all(|c| c.is_ascii_digit())
+                && !STOPWORDS.contains(&word.as_str())
+            {
+                found.insert(Antecedent::TitleWord(word));
+            }
+        }
+
+        found
+    }
+
+    fn to_rule(&self, tag: &str, support: usize, confidence: f32) -> Rule {
+        let (url, title, query) = match self {
+            Antecedent::Domain(host) => (None, None, Some(format!("domain:{host}"))),
+            Antecedent::PathPrefix(prefix) => {
+                // anchored, so `github.com/rust-lang` leaves out `github.com/rust-lang-nursery`
+                let (host, segment) = prefix.split_once('/').unwrap_or((prefix, ""));
+                let url = format!(
+                    r"r/^[a-z]+://(www\.)?{}/{}([/?#]|$)/i",
+                    regex::escape(host),
+                    regex::escape(segment)
+                );
+                (Some(url), None, None)
+            }
+            Antecedent::TitleWord(word) => (None, Some(format!("r/\\b{}\\b/i", regex::escape(word))), None),
+        };
+
+        Rule {
+            id: 0,
+            enabled: true,
+            url,
+            title,
+            description: None,
+            tags: None,
+            query,
+            comment: Some(format!(
+                "suggested: {support} bookmarks, {:.0}% confidence",
+                confidence * 100.0
+            )),
+            when: vec![],
+            stop: false,
+            priority: 0,
+            action: Action::UpdateBookmark {
+                title: None,
+                description: None,
+                tags: Some(vec![tag.to_string()]),
+            },
+            compiled: Default::default(),
+        }
+    }
+}
+
+/// Mine `bookmarks` for domains, path prefixes and title words that predict
+/// a tag, strongest first. Associations `existing` rules already produce,
+/// and path prefixes whose domain predicts the same tag, are left out.
+pub fn suggest(bookmarks: &[Bookmark], existing: &[Rule], opts: &SuggestOpts) -> Vec<Suggestion> {
+    let mut antecedent_counts: HashMap<Antecedent, Vec<usize>> = HashMap::new();
+    for (idx, bmark) in bookmarks.iter().enumerate() {
+        for antecedent in Antecedent::of(bmark) {
+            antecedent_counts.entry(antecedent).or_default().push(idx);
+        }
+    }
+
+    let mut candidates = Vec::new();
+    for (antecedent, members) in &antecedent_counts {
+        if members.len() < opts.min_support {
+            continue;
+        }
+
+        let mut tag_counts: HashMap<&str, usize> = HashMap::new();
+        for idx in members {
+            let tags: BTreeSet<&str> = bookmarks[*idx].tags.iter().map(String::as_str).collect();
+            for tag in tags {
+                *tag_counts.entry(tag).or_default() += 1;
+            }
+        }
+
+        for (tag, support) in tag_counts {
+            let confidence = support as f32 / members.len() as f32;
+            if support >= opts.min_support && confidence >= opts.min_confidence {
+                candidates.push((antecedent, tag, support, confidence));
+            }
+        }
+    }
+
+    candidates.sort_by(|a, b| {
+        b.3.total_cmp(&a.3)
+            .then(b.2.cmp(&a.2))
+            .then(a.0.cmp(b.0))
+            .then(a.1.cmp(b.1))
+    });
+
+    let domain_tags: BTreeSet<(&str, &str)> = candidates
+        .iter()
+        .filter_map(|(antecedent, tag, ..)| match antecedent {
+            Antecedent::Domain(host) => Some((host.as_str(), *tag)),
+            _ => None,
+        })
+        .collect();
+
+    candidates
+        .into_iter()
+        .filter(|(antecedent, tag, ..)| match antecedent {
+            Antecedent::PathPrefix(prefix) => {
+                let host = prefix.split('/').next().unwrap_or_default();
+                !domain_tags.contains(&(host, *tag))
+            }
+            _ => true,
+        })
+        .filter(|(antecedent, tag, ..)| {
+            !covered(&antecedent_counts[*antecedent], tag, bookmarks, existing)
+        })
+        .take(opts.limit)
+        .map(|(antecedent, tag, support, confidence)| Suggestion {
+            rule: antecedent.to_rule(tag, support, confidence),
+            support,
+            confidence,
+        })
+        .collect()
+}
+
+/// Whether existing rules already add `tag` to every bookmark in `members`
+/// that has it.
+fn covered(members: &[usize], tag: &str, bookmarks: &[Bookmark], existing: &[Rule]) -> bool {
+    let adding: Vec<&Rule> = existing
+        .iter()
+        .filter(|rule| rule.enabled)
+        .filter(|rule| match &rule.action {
+            Action::UpdateBookmark { tags: Some(tags), .. } => tags.iter().any(|t| t == tag),
+            _ => false,
+        })
+        .collect();
+    if adding.is_empty() {
+        return false;
+    }
+
+    members
+        .iter()
+        .map(|idx| &bookmarks[*idx])
+        .filter(|bmark| bmark.tags.iter().any(|t| t == tag))
+        .all(|bmark| {
+            let record = Record::from_bookmark(bmark);
+            // suggested rules only match on the URL
+            adding.iter().any(|rule| rule.is_match(&record, &Default::default()))
+        })
+}
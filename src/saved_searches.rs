@@ -0,0 +1,336 @@
+use crate::{config::RulesConfig, search_query, search_query::SavedSearches, storage, storage::StorageManager, workspaces::WorkspaceStore};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+const SAVED_SEARCHES_FILE: &str = "saved_searches.yaml";
+
+/// A named query that other queries can reference as `@name`.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct SavedSearch {
+    pub name: String,
+    pub query: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum SavedSearchError {
+    #[error("saved search name must be 1-64 letters, digits, '-' or '_'")]
+    InvalidName,
+    #[error("duplicate saved search name: {0}")]
+    DuplicateName(String),
+    #[error("invalid query: {0}")]
+    InvalidQuery(String),
+    #[error("saved search not found: {0}")]
+    NotFound(String),
+    #[error("saved search is still used by {0}")]
+    InUse(String),
+    #[error("storage error: {0}")]
+    Storage(String),
+}
+
+/// A query kept outside the store that may reference saved searches.
+pub struct QueryUse {
+    /// Where the query lives, e.g. `rule 3`
+    pub owner: String,
+    pub query: String,
+}
+
+/// The rule and workspace queries that can reference saved searches.
+pub fn query_uses(rules: &RulesConfig, workspaces: &WorkspaceStore) -> Vec<QueryUse> {
+    let rules = rules.rules().iter().filter_map(|rule| {
+        Some(QueryUse {
+            owner: format!("rule {}", rule.id),
+            query: rule.query.clone()?,
+        })
+    });
+    let workspaces = workspaces.list().iter().filter_map(|workspace| {
+        Some(QueryUse {
+            owner: format!("workspace '{}'", workspace.name),
+            query: workspace.filters.query.clone()?,
+        })
+    });
+    rules.chain(workspaces).collect()
+}
+
+pub struct SavedSearchStore {
+    searches: Vec<SavedSearch>,
+    /// `searches` as `@name` references resolve them
+    map: Arc<SavedSearches>,
+    base_path: String,
+}
+
+impl SavedSearchStore {
+    pub fn load(base_path: &str) -> Result<Self, SavedSearchError> {
+        let store = storage::BackendLocal::new(base_path)
+            .map_err(|e| SavedSearchError::Storage(e.to_string()))?;
+
+        let searches: Vec<SavedSearch> = if store.exists(SAVED_SEARCHES_FILE) {
+            let data = store.read(SAVED_SEARCHES_FILE)
+                .map_err(|e| SavedSearchError::Storage(e.to_string()))?;
+            let yaml_str = String::from_utf8(data)
+                .map_err(|e| SavedSearchError::Storage(e.to_string()))?;
+            serde_yml::from_str(&yaml_str)
+                .map_err(|e| SavedSearchError::Storage(e.to_string()))?
+        } else {
+            vec![]
+        };
+
+        Ok(Self {
+            map: Arc::new(as_map(&searches)),
+            searches,
+            base_path: base_path.to_string(),
+        })
+    }
+
+    fn save(&self) -> Result<(), SavedSearchError> {
+        let store = storage::BackendLocal::new(&self.base_path)
+            .map_err(|e| SavedSearchError::Storage(e.to_string()))?;
+        let yaml = serde_yml::to_string(&self.searches)
+            .map_err(|e| SavedSearchError::Storage(e.to_string()))?;
+        store.write(SAVED_SEARCHES_FILE, yaml.as_bytes())
+            .map_err(|e| SavedSearchError::Storage(e.to_string()))?;
+        Ok(())
+    }
+
+    pub fn list(&self) -> &[SavedSearch] {
+        &self.searches
+    }
+
+    /// What `search_query::parse_with` expands `@name` references to.
+    pub fn map(&self) -> Arc<SavedSearches> {
+        self.map.clone()
+    }
+
+    pub fn get(&self, name: &str) -> Option<&SavedSearch> {
+        let name = name.trim_start_matches('@').to_lowercase();
+        self.searches.iter().find(|s| s.name == name)
+    }
+
+    pub fn create(
+        &mut self,
+        name: String,
+        query: String,
+        description: Option<String>,
+    ) -> Result<SavedSearch, SavedSearchError> {
+        let name = validate_name(&name)?;
+        if self.get(&name).is_some() {
+            return Err(SavedSearchError::DuplicateName(name));
+        }
+
+        let search = SavedSearch {
+            name,
+            query,
+            description,
+        };
+
+        let mut candidate = self.searches.clone();
+        candidate.push(search.clone());
+        self.commit(candidate, &[])?;
+        Ok(search)
+    }
+
+    /// Fails when a rename or new query would break one of `uses`.
+    pub fn update(
+        &mut self,
+        name: &str,
+        new_name: Option<String>,
+        query: Option<String>,
+        description: Option<String>,
+        uses: &[QueryUse],
+    ) -> Result<SavedSearch, SavedSearchError> {
+        let idx = self.position(name)?;
+        let mut candidate = self.searches.clone();
+
+        if let Some(ref new_name) = new_name {
+            let new_name = validate_name(new_name)?;
+            if new_name != candidate[idx].name && self.get(&new_name).is_some() {
+                return Err(SavedSearchError::DuplicateName(new_name));
+            }
+            candidate[idx].name = new_name;
+        }
+
+        if let Some(query) = query {
+            candidate[idx].query = query;
+        }
+
+        if let Some(description) = description {
+            candidate[idx].description = Some(description).filter(|d| !d.trim().is_empty());
+        }
+
+        let search = candidate[idx].clone();
+        self.commit(candidate, uses)?;
+        Ok(search)
+    }
+
+    /// Fails when another saved search or one of `uses` still references
+    /// `name`.
+    pub fn delete(&mut self, name: &str, uses: &[QueryUse]) -> Result<(), SavedSearchError> {
+        let idx = self.position(name)?;
+        let mut candidate = self.searches.clone();
+        candidate.remove(idx);
+        self.commit(candidate, uses)
+    }
+
+    fn position(&self, name: &str) -> Result<usize, SavedSearchError> {
+        let key = name.trim_start_matches('@').to_lowercase();
+        self.searches
+            .iter()
+            .position(|s| s.name == key)
+            .ok_or_else(|| SavedSearchError::NotFound(name.to_string()))
+    }
+
+    /// Validate the full set and check it leaves `uses` resolving as they do
+    /// now, then persist it.
+    fn commit(&mut self, candidate: Vec<SavedSearch>, uses: &[QueryUse]) -> Result<(), SavedSearchError> {
+        validate_all(&candidate)?;
+        let map = as_map(&candidate);
+
+        // queries that are broken already aren't held against the change
+        let resolves = |query: &str, map: &SavedSearches| search_query::parse_tolerant_with(query, map).is_ok();
+        let broken: Vec<&str> = uses
+            .iter()
+            .filter(|u| resolves(&u.query, &self.map) && !resolves(&u.query, &map))
+            .map(|u| u.owner.as_str())
+            .collect();
+        if !broken.is_empty() {
+            return Err(SavedSearchError::InUse(broken.join(", ")));
+        }
+
+        self.searches = candidate;
+        self.save()?;
+        self.map = Arc::new(map);
+        Ok(())
+    }
+}
+
+fn as_map(searches: &[SavedSearch]) -> SavedSearches {
+    searches
+        .iter()
+        .map(|s| (s.name.clone(), s.query.clone()))
+        .collect()
+}
+
+fn validate_name(name: &str) -> Result<String, SavedSearchError> {
+    let name = name.trim().trim_start_matches('@').to_lowercase();
+    if name.is_empty() || name.len() > 64 || !name.chars().all(search_query::is_name_char) {
+        return Err(SavedSearchError::InvalidName);
+    }
+    Ok(name)
+}
+
+/// Every query must parse with references resolved against the set itself,
+/// which also rejects unknown names and cycles.
+fn validate_all(searches: &[SavedSearch]) -> Result<(), SavedSearchError> {
+    let map = as_map(searches);
+    for search in searches {
+        search_query::parse_with(&search.query, &map)
+            .map_err(|e| SavedSearchError::InvalidQuery(format!("@{}: {}", search.name, e)))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+    fn tmp_dir() -> String {
+        let c = COUNTER.fetch_add(1, Ordering::SeqCst);
+        let p = std::env::temp_dir().join(format!("bb-saved-test-{}-{}", std::process::id(), c));
+        std::fs::create_dir_all(&p).unwrap();
+        p.to_str().unwrap().to_string()
+    }
+
+    #[test]
+    fn create_persists_and_reloads() {
+        let dir = tmp_dir();
+        let mut store = SavedSearchStore::load(&dir).unwrap();
+        assert!(store.list().is_empty());
+
+        let s = store
+            .create("Reading-List".into(), "#toread not #done".into(), None)
+            .unwrap();
+        assert_eq!(s.name, "reading-list");
+
+        let store = SavedSearchStore::load(&dir).unwrap();
+        assert_eq!(store.list().len(), 1);
+        assert_eq!(store.get("@reading-list").unwrap().query, "#toread not #done");
+    }
+
+    #[test]
+    fn create_rejects_bad_names_and_duplicates() {
+        let dir = tmp_dir();
+        let mut store = SavedSearchStore::load(&dir).unwrap();
+        assert!(matches!(
+            store.create("has space".into(), "rust".into(), None),
+            Err(SavedSearchError::InvalidName)
+        ));
+        store.create("rust".into(), "#rust".into(), None).unwrap();
+        assert!(matches!(
+            store.create("RUST".into(), "#rust".into(), None),
+            Err(SavedSearchError::DuplicateName(_))
+        ));
+    }
+
+    #[test]
+    fn references_must_resolve() {
+        let dir = tmp_dir();
+        let mut store = SavedSearchStore::load(&dir).unwrap();
+        assert!(matches!(
+            store.create("a".into(), "@missing".into(), None),
+            Err(SavedSearchError::InvalidQuery(_))
+        ));
+
+        store.create("base".into(), "#rust".into(), None).unwrap();
+        store.create("derived".into(), "@base .async".into(), None).unwrap();
+
+        // deleting a referenced search would leave `@derived` dangling
+        assert!(matches!(store.delete("base", &[]), Err(SavedSearchError::InvalidQuery(_))));
+        store.delete("derived", &[]).unwrap();
+        store.delete("base", &[]).unwrap();
+    }
+
+    #[test]
+    fn rules_and_workspaces_keep_their_references() {
+        let dir = tmp_dir();
+        let mut store = SavedSearchStore::load(&dir).unwrap();
+        store.create("base".into(), "#rust".into(), None).unwrap();
+        let uses = [
+            QueryUse {
+                owner: "rule 3".into(),
+                query: "@base and #async".into(),
+            },
+            QueryUse {
+                owner: "workspace 'broken'".into(),
+                query: "@gone".into(),
+            },
+        ];
+
+        let err = store.delete("base", &uses).unwrap_err();
+        assert_eq!(err.to_string(), "saved search is still used by rule 3");
+        let err = store.update("base", Some("core".into()), None, None, &uses).unwrap_err();
+        assert!(matches!(err, SavedSearchError::InUse(_)), "{err}");
+        assert_eq!(store.list().len(), 1);
+        assert_eq!(store.map()["base"], "#rust");
+
+        // a new query keeps the reference resolving
+        store.update("base", None, Some("#rust or #go".into()), None, &uses).unwrap();
+        store.delete("base", &uses[1..]).unwrap();
+        assert!(store.map().is_empty());
+    }
+
+    #[test]
+    fn update_rejects_cycles() {
+        let dir = tmp_dir();
+        let mut store = SavedSearchStore::load(&dir).unwrap();
+        store.create("a".into(), "#rust".into(), None).unwrap();
+        store.create("b".into(), "@a".into(), None).unwrap();
+
+        let err = store.update("a", None, Some("@b".into()), None, &[]).unwrap_err();
+        assert!(err.to_string().contains("cycle"), "{err}");
+        assert_eq!(store.get("a").unwrap().query, "#rust");
+    }
+}
@@ -0,0 +1,173 @@
+//! Netscape `cookies.txt` jars for fetching pages behind a login. Jars live
+//! in `<data dir>/cookies/`, one per domain and named after it, e.g.
+//! `cookies/github.com.txt`. They hold live sessions: never log values.
+
+use std::path::Path;
+
+pub const COOKIES_DIR: &str = "cookies";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cookie {
+    pub domain: String,
+    pub include_subdomains: bool,
+    pub path: String,
+    pub secure: bool,
+    pub http_only: bool,
+    /// Unix time, 0 for session cookies
+    pub expires: i64,
+    pub name: String,
+    pub value: String,
+}
+
+impl Cookie {
+    fn matches(&self, url: &reqwest::Url, now: i64) -> bool {
+        let Some(host) = url.host_str() else {
+            return false;
+        };
+        let host = host.to_lowercase();
+        let domain = self.domain.trim_start_matches('.').to_lowercase();
+
+        let domain_ok = host == domain
+            || (self.include_subdomains && host.ends_with(&format!(".{domain}")));
+        let path = url.path();
+        let path_ok = path == self.path
+            || (path.starts_with(&self.path)
+                && (self.path.ends_with('/') || path[self.path.len()..].starts_with('/')));
+        let secure_ok = !self.secure || url.scheme() == "https";
+        let fresh = self.expires == 0 || self.expires > now;
+
+        domain_ok && path_ok && secure_ok && fresh
+    }
+}
+
+/// Parse a Netscape cookie file: one cookie per line with domain,
+/// include-subdomains flag, path, secure flag, expiry, name and value
+/// separated by tabs. Lines starting with `#HttpOnly_` are http-only
+/// cookies, other `#` lines are comments. Malformed lines are skipped.
+pub fn parse(text: &str) -> Vec<Cookie> {
+    text.lines()
+        .filter_map(|line| {
+            let line = line.trim_end_matches('\r');
+            let (line, http_only) = match line.strip_prefix("#HttpOnly_") {
+                Some(rest) => (rest, true),
+                None if line.starts_with('#') || line.trim().is_empty() => return None,
+                None => (line, false),
+            };
+
+            let fields: Vec<&str> = line.split('\t').collect();
+            let [domain, include_subdomains, path, secure, expires, name, value] = fields[..] else {
+                return None;
+            };
+            Some(Cookie {
+                domain: domain.to_string(),
+                include_subdomains: include_subdomains.eq_ignore_ascii_case("TRUE"),
+                path: path.to_string(),
+                secure: secure.eq_ignore_ascii_case("TRUE"),
+                http_only,
+                expires: expires.parse().ok()?,
+                name: name.to_string(),
+                value: value.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Cookies that apply to `url` from the jars in `dir`: the one named after
+/// its host and those of its parent domains, e.g. `docs.github.com.txt` and
+/// `github.com.txt` for `https://docs.github.com/`.
+pub fn for_url(dir: &Path, url: &reqwest::Url) -> Vec<Cookie> {
+    let Some(host) = url.host_str() else {
+        return vec![];
+    };
+    let host = host.to_lowercase();
+    let now = chrono::Utc::now().timestamp();
+
+    let mut cookies = Vec::new();
+    let mut domain = host.as_str();
+    while domain.contains('.') {
+        let path = dir.join(format!("{domain}.txt"));
+        if path.is_file() {
+            match std::fs::read_to_string(&path) {
+                Ok(text) => cookies.extend(parse(&text).into_iter().filter(|c| c.matches(url, now))),
+                Err(err) => log::warn!("failed to read cookie jar {}: {err}", path.display()),
+            }
+        }
+        domain = domain.split_once('.').map_or("", |(_, parent)| parent);
+    }
+
+    cookies
+}
+
+/// Value of the `Cookie` header for `cookies`, if there are any.
+pub fn header_value(cookies: &[Cookie]) -> Option<String> {
+    (!cookies.is_empty()).then(|| {
+        cookies
+            .iter()
+            .map(|c| format!("{}={}", c.name, c.value))
+            .collect::<Vec<_>>()
+            .join("; ")
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const JAR: &str = "# Netscape HTTP Cookie File\n\
+        # This is a generated file! Do not edit.\n\
+        \n\
+        .github.com\tTRUE\t/\tTRUE\t0\t_octo\tGH1.1\n\
+        #HttpOnly_github.com\tFALSE\t/\tTRUE\t4102444800\tuser_session\tabc123\n\
+        github.com\tFALSE\t/settings\tTRUE\t0\tsettings_only\tx\n\
+        github.com\tFALSE\t/\tFALSE\t1\texpired\tx\n\
+        not a cookie line\n";
+
+    #[test]
+    fn test_parse() {
+        let cookies = parse(JAR);
+        assert_eq!(cookies.len(), 4);
+        assert_eq!(
+            cookies[1],
+            Cookie {
+                domain: "github.com".into(),
+                include_subdomains: false,
+                path: "/".into(),
+                secure: true,
+                http_only: true,
+                expires: 4102444800,
+                name: "user_session".into(),
+                value: "abc123".into(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_for_url_reads_host_and_parent_jars() {
+        let dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(dir.path().join("github.com.txt"), JAR).unwrap();
+        std::fs::write(
+            dir.path().join("gist.github.com.txt"),
+            "gist.github.com\tFALSE\t/\tFALSE\t0\tgist\t1\n",
+        )
+        .unwrap();
+
+        let names = |url: &str| -> Vec<String> {
+            for_url(dir.path(), &reqwest::Url::parse(url).unwrap())
+                .into_iter()
+                .map(|c| c.name)
+                .collect()
+        };
+
+        assert_eq!(names("https://github.com/settings/keys"), ["_octo", "user_session", "settings_only"]);
+        assert_eq!(names("https://github.com/settingsx"), ["_octo", "user_session"]);
+        // only `.github.com` includes subdomains
+        assert_eq!(names("https://gist.github.com/"), ["gist", "_octo"]);
+        // secure cookies need https
+        assert!(names("http://github.com/").is_empty());
+        assert!(names("https://gitlab.com/").is_empty());
+
+        let cookies = for_url(dir.path(), &reqwest::Url::parse("https://github.com/").unwrap());
+        assert_eq!(header_value(&cookies).as_deref(), Some("_octo=GH1.1; user_session=abc123"));
+        assert_eq!(header_value(&[]), None);
+    }
+}
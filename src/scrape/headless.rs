@@ -1,9 +1,12 @@
 use headless_chrome::{
-    protocol::cdp::{Page, Target::CreateTarget},
+    protocol::cdp::{Network, Page, Target::CreateTarget},
     LaunchOptionsBuilder, Tab,
 };
 use std::{path::PathBuf, str::FromStr, sync::Arc, thread::sleep, time::Duration};
 
+use crate::config::ScrapeConfig;
+use crate::scrape::cookies::{self, Cookie};
+
 pub struct ChromeResult {
     pub html: String,
     pub screenshot: Vec<u8>,
@@ -92,12 +95,44 @@ pub fn test_launch() {
     tab.close(false).unwrap();
 }
 
-pub fn fetch_page_with_chrome(url: &str) -> Option<ChromeResult> {
+/// Jar cookies as CDP parameters. Cookies without the include-subdomains
+/// flag are set by URL so they stay host-only.
+fn cookie_params(cookies: &[Cookie]) -> Vec<Network::CookieParam> {
+    cookies
+        .iter()
+        .filter_map(|c| {
+            let domain = c.domain.trim_start_matches('.');
+            let mut param = serde_json::json!({
+                "name": c.name,
+                "value": c.value,
+                "path": c.path,
+                "secure": c.secure,
+                "httpOnly": c.http_only,
+            });
+            if c.include_subdomains {
+                param["domain"] = format!(".{domain}").into();
+            } else {
+                let scheme = if c.secure { "https" } else { "http" };
+                param["url"] = format!("{scheme}://{domain}{}", c.path).into();
+            }
+            if c.expires > 0 {
+                param["expires"] = (c.expires as f64).into();
+            }
+            serde_json::from_value(param).ok()
+        })
+        .collect()
+}
+
+pub fn fetch_page_with_chrome(url: &str, scrape_config: Option<&ScrapeConfig>) -> Option<ChromeResult> {
     let opt_proxy = std::env::var("OPT_PROXY").unwrap_or_default();
     // TODO: reuse HTTP client / browser instance across retries to reduce overhead
 
     let url_parsed = reqwest::Url::parse(url).unwrap();
     let host = url_parsed.host_str().unwrap_or_default();
+    let jar = scrape_config
+        .and_then(|c| c.cookies_dir.as_deref())
+        .map(|dir| cookie_params(&cookies::for_url(dir, &url_parsed)))
+        .unwrap_or_default();
 
     let mut r = 0;
     let mut force_proxy = false;
@@ -137,6 +172,8 @@ pub fn fetch_page_with_chrome(url: &str) -> Option<ChromeResult> {
             }
         };
 
+        // held while the page loads
+        let permit = crate::scrape::limiter::acquire(url, scrape_config);
         let tab = browser
             .new_tab_with_options(CreateTarget {
                 for_tab: None,
@@ -155,6 +192,13 @@ pub fn fetch_page_with_chrome(url: &str) -> Option<ChromeResult> {
 
         tab.set_default_timeout(Duration::from_secs(15));
 
+        if !jar.is_empty() {
+            log::debug!("{host}: setting {} cookies from jar", jar.len());
+            if let Err(err) = tab.set_cookies(jar.clone()) {
+                log::warn!("{host}: failed to set cookies: {err}");
+            }
+        }
+
         if let Err(err) = tab.navigate_to(url) {
             log::error!("{host}: {err}");
             force_proxy = true;
@@ -169,6 +213,7 @@ pub fn fetch_page_with_chrome(url: &str) -> Option<ChromeResult> {
 
         log::debug!("{host}: sleeping for 2 seconds...");
         sleep(Duration::from_secs(5));
+        drop(permit);
 
         // in case the page hasn't been fully loaded yet;
         log::debug!("{host}: sleeping some more...");
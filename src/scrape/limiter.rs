@@ -0,0 +1,227 @@
+//! Per-host request limits shared by every fetch in the process: at most
+//! `scrape.host_concurrency` requests in flight to a host, starting at least
+//! `scrape.host_interval_ms` apart, and none before a `Retry-After` a host
+//! sent has passed. A bulk import then queues per host instead of hitting one
+//! site with every task thread and fetcher at once.
+
+use std::collections::HashMap;
+use std::sync::{Condvar, Mutex};
+use std::time::{Duration, Instant};
+
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+
+use crate::config::ScrapeConfig;
+
+/// Waiters re-check at least this often, e.g. after a config change.
+const MAX_WAIT: Duration = Duration::from_secs(1);
+
+struct HostState {
+    active: usize,
+    waiting: usize,
+    /// No request starts before this
+    next_start: Instant,
+}
+
+impl HostState {
+    fn is_idle(&self, now: Instant) -> bool {
+        self.active == 0 && self.waiting == 0 && self.next_start <= now
+    }
+}
+
+#[derive(Default)]
+struct Limiter {
+    hosts: Mutex<HashMap<String, HostState>>,
+    released: Condvar,
+}
+
+static LIMITER: Lazy<Limiter> = Lazy::new(Default::default);
+
+/// A slot for one request to a host, given back on drop.
+pub struct Permit {
+    host: String,
+}
+
+impl Drop for Permit {
+    fn drop(&mut self) {
+        let mut hosts = LIMITER.hosts.lock().unwrap();
+        if let Some(state) = hosts.get_mut(&self.host) {
+            state.active = state.active.saturating_sub(1);
+        }
+        LIMITER.released.notify_all();
+    }
+}
+
+/// What the limiter is doing to a host, for the task queue status.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct HostStatus {
+    pub host: String,
+    pub active: usize,
+    pub waiting: usize,
+    /// Until the next request may start, e.g. after a `Retry-After`
+    pub wait_ms: u64,
+}
+
+fn host_of(url: &str) -> Option<String> {
+    let url = reqwest::Url::parse(url).ok()?;
+    Some(url.host_str()?.to_lowercase())
+}
+
+/// Wait until a request to the host of `url` may start. Without a scrape
+/// config, or for URLs without a host, nothing is limited.
+pub fn acquire(url: &str, scrape_config: Option<&ScrapeConfig>) -> Option<Permit> {
+    let config = scrape_config?;
+    let host = host_of(url)?;
+    let concurrency = config.host_concurrency_for(url);
+    let interval = config.host_interval_for(url);
+
+    let mut hosts = LIMITER.hosts.lock().unwrap();
+    let now = Instant::now();
+    hosts.retain(|_, state| !state.is_idle(now));
+
+    let mut waiting = false;
+    loop {
+        let now = Instant::now();
+        let state = hosts.entry(host.clone()).or_insert_with(|| HostState {
+            active: 0,
+            waiting: 0,
+            next_start: now,
+        });
+        let slot_free = concurrency == 0 || state.active < concurrency;
+
+        if slot_free && state.next_start <= now {
+            if waiting {
+                state.waiting -= 1;
+            }
+            state.active += 1;
+            state.next_start = now + interval;
+            return Some(Permit { host });
+        }
+
+        if !waiting {
+            waiting = true;
+            state.waiting += 1;
+            log::debug!("{host}: waiting for the host rate limit");
+        }
+        let wait = if slot_free { state.next_start - now } else { MAX_WAIT };
+        hosts = LIMITER.released.wait_timeout(hosts, wait.min(MAX_WAIT)).unwrap().0;
+    }
+}
+
+/// Hold off every request to the host of `url` for `delay`, capped at
+/// `scrape.max_retry_after_secs`.
+pub fn back_off(url: &str, delay: Duration, config: &ScrapeConfig) {
+    let Some(host) = host_of(url) else {
+        return;
+    };
+    let delay = delay.min(Duration::from_secs(config.max_retry_after_secs));
+    log::info!("{host}: backing off for {}s", delay.as_secs());
+
+    let until = Instant::now() + delay;
+    let mut hosts = LIMITER.hosts.lock().unwrap();
+    let state = hosts.entry(host).or_insert_with(|| HostState {
+        active: 0,
+        waiting: 0,
+        next_start: until,
+    });
+    state.next_start = state.next_start.max(until);
+}
+
+/// A `Retry-After` value: delay seconds or an HTTP date.
+pub fn parse_retry_after(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(Duration::from_secs(secs));
+    }
+    let at = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+    let secs = (at.timestamp() - chrono::Utc::now().timestamp()).max(0);
+    Some(Duration::from_secs(secs as u64))
+}
+
+/// Hosts with requests in flight, queued or held off, busiest first.
+pub fn status() -> Vec<HostStatus> {
+    let now = Instant::now();
+    let hosts = LIMITER.hosts.lock().unwrap();
+    let mut status: Vec<HostStatus> = hosts
+        .iter()
+        .filter(|(_, state)| !state.is_idle(now))
+        .map(|(host, state)| HostStatus {
+            host: host.clone(),
+            active: state.active,
+            waiting: state.waiting,
+            wait_ms: state.next_start.saturating_duration_since(now).as_millis() as u64,
+        })
+        .collect();
+    status.sort_by(|a, b| (b.active + b.waiting).cmp(&(a.active + a.waiting)).then_with(|| a.host.cmp(&b.host)));
+    status
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The limiter is process-wide, so each test uses its own host.
+
+    fn host_status(host: &str) -> Option<HostStatus> {
+        status().into_iter().find(|s| s.host == host)
+    }
+
+    #[test]
+    fn test_parse_retry_after() {
+        assert_eq!(parse_retry_after("120"), Some(Duration::from_secs(120)));
+        assert_eq!(parse_retry_after("Wed, 21 Oct 2015 07:28:00 GMT"), Some(Duration::ZERO));
+        let later = (chrono::Utc::now() + chrono::Duration::seconds(90)).to_rfc2822();
+        let delay = parse_retry_after(&later).unwrap();
+        assert!(delay > Duration::from_secs(80) && delay <= Duration::from_secs(90));
+        assert_eq!(parse_retry_after("soon"), None);
+    }
+
+    #[test]
+    fn test_host_concurrency() {
+        let config = ScrapeConfig {
+            host_concurrency: 1,
+            host_interval_ms: 0,
+            ..Default::default()
+        };
+        let url = "https://concurrency.limiter.test/a";
+
+        let first = acquire(url, Some(&config)).unwrap();
+        let status = host_status("concurrency.limiter.test").unwrap();
+        assert_eq!((status.active, status.waiting), (1, 0));
+
+        let waiter = std::thread::spawn(move || {
+            let start = Instant::now();
+            let _second = acquire("https://Concurrency.limiter.test/b", Some(&config)).unwrap();
+            start.elapsed()
+        });
+        std::thread::sleep(Duration::from_millis(200));
+        assert_eq!(host_status("concurrency.limiter.test").unwrap().waiting, 1);
+        drop(first);
+
+        assert!(waiter.join().unwrap() >= Duration::from_millis(100));
+        // another host isn't held up
+        assert!(acquire("https://other.limiter.test/", Some(&ScrapeConfig::default())).is_some());
+        assert!(acquire(url, None).is_none());
+    }
+
+    #[test]
+    fn test_interval_and_back_off() {
+        let config = ScrapeConfig {
+            host_concurrency: 0,
+            host_interval_ms: 300,
+            max_retry_after_secs: 1,
+            ..Default::default()
+        };
+        let url = "https://interval.limiter.test/";
+
+        let start = Instant::now();
+        drop(acquire(url, Some(&config)));
+        drop(acquire(url, Some(&config)));
+        assert!(start.elapsed() >= Duration::from_millis(300));
+
+        // capped at max_retry_after_secs
+        back_off(url, Duration::from_secs(3600), &config);
+        let wait_ms = host_status("interval.limiter.test").unwrap().wait_ms;
+        assert!(wait_ms > 500 && wait_ms <= 1000, "{wait_ms}");
+    }
+}
@@ -1,5 +1,8 @@
+pub mod cookies;
 #[cfg(feature = "headless")]
 pub mod headless;
+pub mod limiter;
+pub mod robots;
 
 use reqwest::StatusCode;
 use std::{cmp::Ordering, error::Error, net::IpAddr, thread::sleep, time::Duration};
@@ -75,6 +78,14 @@ fn get_error(error: &reqwest::Error) -> String {
 }
 
 pub fn reqwest_with_retries(url: &str, scrape_config: Option<&ScrapeConfig>) -> Option<(StatusCode, Vec<u8>)> {
+    request_with_retries(url, scrape_config).map(|(status, _headers, bytes)| (status, bytes))
+}
+
+/// Like `reqwest_with_retries`, also returning the response headers.
+fn request_with_retries(
+    url: &str,
+    scrape_config: Option<&ScrapeConfig>,
+) -> Option<(StatusCode, reqwest::header::HeaderMap, Vec<u8>)> {
     let opt_proxy = std::env::var("OPT_PROXY").unwrap_or_default().to_string();
 
     let mut r = 0;
@@ -106,6 +117,32 @@ pub fn reqwest_with_retries(url: &str, scrape_config: Option<&ScrapeConfig>) ->
     let path = url_parsed.path();
     let iden = format!("{host}{path}");
 
+    // per-host overrides from scrape.domains
+    let domain = scrape_config.and_then(|c| c.domain(&url));
+    let user_agent = domain
+        .and_then(|d| d.user_agent.as_deref())
+        .unwrap_or(USER_AGENT_DEFAULT);
+    let timeout = Duration::from_secs(domain.and_then(|d| d.timeout_secs).unwrap_or(10));
+    let domain_proxy = domain.and_then(|d| d.proxy.as_deref());
+    let cookie_header = scrape_config
+        .and_then(|c| c.cookies_dir.as_deref())
+        .and_then(|dir| cookies::header_value(&cookies::for_url(dir, &url_parsed)));
+    if cookie_header.is_some() {
+        log::debug!("{iden}: sending cookies from jar");
+    }
+    let mut headers = reqwest::header::HeaderMap::new();
+    for (name, value) in domain.map(|d| &d.headers).into_iter().flatten() {
+        match (
+            reqwest::header::HeaderName::from_bytes(name.as_bytes()),
+            reqwest::header::HeaderValue::from_str(value),
+        ) {
+            (Ok(name), Ok(value)) => {
+                headers.insert(name, value);
+            }
+            _ => log::warn!("{iden}: skipping invalid header '{name}'"),
+        }
+    }
+
     let mut force_proxy = false;
     loop {
         if r >= 5 {
@@ -119,22 +156,37 @@ pub fn reqwest_with_retries(url: &str, scrape_config: Option<&ScrapeConfig>) ->
         r += 1;
 
         let mut client = reqwest::blocking::Client::builder()
-            .user_agent(USER_AGENT_DEFAULT)
+            .user_agent(user_agent)
+            .default_headers(headers.clone())
             .danger_accept_invalid_certs(accept_invalid_certs)
             .danger_accept_invalid_hostnames(accept_invalid_certs)
-            .timeout(Duration::from_secs(10))
+            .timeout(timeout)
             .pool_idle_timeout(Duration::from_secs(10));
 
-        if force_proxy && !opt_proxy.is_empty() {
+        if let Some(proxy) = domain_proxy {
+            match reqwest::Proxy::all(proxy) {
+                Ok(proxy) => client = client.proxy(proxy),
+                Err(err) => {
+                    log::warn!("{iden}: invalid proxy {proxy:?}: {err}");
+                    return None;
+                }
+            }
+        } else if force_proxy && !opt_proxy.is_empty() {
             log::debug!("{iden}: using proxy {opt_proxy:#?}");
             client = client.proxy(reqwest::Proxy::all(&opt_proxy).unwrap());
         }
 
         let client = client.build().unwrap();
 
+        let _permit = limiter::acquire(&url, scrape_config);
         log::debug!("{iden}: requesting");
 
-        let resp = match client.get(&url).send() {
+        let mut request = client.get(&url);
+        if let Some(cookie) = &cookie_header {
+            request = request.header(reqwest::header::COOKIE, cookie);
+        }
+
+        let resp = match request.send() {
             Ok(r) => r,
             Err(err) => {
                 force_proxy = true;
@@ -150,6 +202,7 @@ pub fn reqwest_with_retries(url: &str, scrape_config: Option<&ScrapeConfig>) ->
         }
 
         if status == StatusCode::OK {
+            let headers = resp.headers().clone();
             // we might get OK, but no text response.
             // resp.text().unwrap();
             let bytes = match resp.bytes() {
@@ -161,11 +214,24 @@ pub fn reqwest_with_retries(url: &str, scrape_config: Option<&ScrapeConfig>) ->
                 }
             };
 
-            return Some((status, bytes.into()));
+            return Some((status, headers, bytes.into()));
         }
 
-        if status == StatusCode::TOO_MANY_REQUESTS {
-            sleep(Duration::from_secs(r * 4));
+        if status == StatusCode::TOO_MANY_REQUESTS || status == StatusCode::SERVICE_UNAVAILABLE {
+            let retry_after = resp
+                .headers()
+                .get(reqwest::header::RETRY_AFTER)
+                .and_then(|v| v.to_str().ok())
+                .and_then(limiter::parse_retry_after);
+            match (scrape_config, retry_after) {
+                // the next request to this host, from here or elsewhere, waits
+                (Some(config), Some(delay)) => limiter::back_off(&url, delay, config),
+                (Some(config), None) if status == StatusCode::TOO_MANY_REQUESTS => {
+                    limiter::back_off(&url, Duration::from_secs(r * 4), config)
+                }
+                (None, _) if status == StatusCode::TOO_MANY_REQUESTS => sleep(Duration::from_secs(r * 4)),
+                _ => {}
+            }
         }
 
         if status.is_client_error() {
@@ -181,10 +247,17 @@ pub fn reqwest_with_retries(url: &str, scrape_config: Option<&ScrapeConfig>) ->
 
 pub struct ReqwestResult {
     pub html: String,
+    /// From the `X-Robots-Tag` headers
+    pub robots: robots::RobotsHints,
 }
 pub fn fetch_page_with_reqwest(url: &str, scrape_config: Option<&ScrapeConfig>) -> Option<ReqwestResult> {
-    reqwest_with_retries(url, scrape_config).map(|(_status, bytes)| ReqwestResult {
+    request_with_retries(url, scrape_config).map(|(_status, headers, bytes)| ReqwestResult {
         html: String::from_utf8_lossy(&bytes).to_string(),
+        robots: headers
+            .get_all("x-robots-tag")
+            .iter()
+            .filter_map(|v| v.to_str().ok())
+            .fold(robots::RobotsHints::default(), |hints, v| hints.union(robots::RobotsHints::parse(v))),
     })
 }
 
@@ -265,6 +338,7 @@ pub fn get_data_from_page(resp_text: String, url: &str) -> Metadata {
     #[allow(unused_assignments)]
     let mut icon_url = None;
     let mut canonical_url = None;
+    let mut robots_hints = robots::RobotsHints::default();
 
     let head = match document.select(&head_selector).next() {
         Some(h) => h,
@@ -310,6 +384,10 @@ pub fn get_data_from_page(resp_text: String, url: &str) -> Metadata {
             image_url = Some(meta_value.to_string());
         }
 
+        if robots::is_robots_meta(meta_key) {
+            robots_hints = robots_hints.union(robots::RobotsHints::parse(meta_value));
+        }
+
         // parse canonical url
         if canonical_url.is_none() && meta_key == "og:url" {
             canonical_url = Some(meta_value.to_string());
@@ -476,6 +554,7 @@ pub fn get_data_from_page(resp_text: String, url: &str) -> Metadata {
         canonical_url,
         image_url,
         icon_url,
+        robots: robots_hints,
         ..Default::default()
     }
 }
@@ -590,6 +669,19 @@ mod tests {
         assert_eq!(m.description.as_deref(), Some("A twitter description"));
     }
 
+    #[test]
+    fn test_parse_robots_meta() {
+        let html = html_with_meta(
+            r#"<meta name="robots" content="index, noarchive"><meta name="googlebot" content="noimageindex"><meta name="bb" content="noimageindex">"#,
+            "Title",
+        );
+        let m = get_data_from_page(html, "https://example.com");
+        assert!(m.robots.noarchive && m.robots.noimageindex);
+
+        let m = get_data_from_page(html_with_meta(r#"<meta name="googlebot" content="noarchive">"#, "Title"), "https://example.com");
+        assert!(m.robots.is_empty());
+    }
+
     #[test]
     fn test_title_tag_fallback() {
         let html = html_with_meta("", "Fallback Title");
@@ -0,0 +1,297 @@
+//! `scrape.respect_robots`: robots.txt rules, checked before the page itself
+//! is fetched, and the `noarchive`/`noimageindex` hints pages give in
+//! `X-Robots-Tag` headers and `<meta name="robots">` tags.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+
+use crate::config::ScrapeConfig;
+
+/// The product token robots.txt groups and robots hints can name us by.
+const ROBOT_NAME: &str = "bb";
+
+/// Skip reason of fetchers robots.txt keeps off a page.
+pub const DISALLOWED: &str = "disallowed by robots.txt";
+
+/// How long a host's robots.txt is used before fetching it again.
+const ROBOTS_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// A host's robots.txt and when it was fetched; `None` when the host has none
+/// or it couldn't be fetched.
+type CachedRobots = (Instant, Option<Arc<Robots>>);
+
+/// robots.txt per origin (`https://example.com`).
+static ROBOTS_CACHE: Lazy<Mutex<HashMap<String, CachedRobots>>> = Lazy::new(Default::default);
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RobotsHints {
+    /// Don't keep a copy of the page: no screenshot, no archived snapshot
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub noarchive: bool,
+    /// Don't keep the page's images
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub noimageindex: bool,
+}
+
+impl RobotsHints {
+    /// Hints from a robots meta tag or `X-Robots-Tag` value, e.g.
+    /// `noarchive, noimageindex`. Directives for another crawler, like
+    /// `googlebot: noarchive`, are ignored.
+    pub fn parse(value: &str) -> Self {
+        let value = value.trim().to_lowercase();
+        let directives = match value.split_once(':') {
+            Some((agent, rest)) if !agent.contains([',', ' ']) => {
+                if agent.trim() != ROBOT_NAME {
+                    return Self::default();
+                }
+                rest
+            }
+            _ => value.as_str(),
+        };
+
+        let mut hints = Self::default();
+        for directive in directives.split(',').map(str::trim) {
+            match directive {
+                "noarchive" => hints.noarchive = true,
+                "noimageindex" => hints.noimageindex = true,
+                _ => {}
+            }
+        }
+        hints
+    }
+
+    pub fn union(self, other: Self) -> Self {
+        Self {
+            noarchive: self.noarchive || other.noarchive,
+            noimageindex: self.noimageindex || other.noimageindex,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// These hints if `scrape.respect_robots` is on, none otherwise.
+    pub fn honoured(self, scrape_config: Option<&ScrapeConfig>) -> Self {
+        if scrape_config.is_some_and(|c| c.respect_robots) {
+            self
+        } else {
+            Self::default()
+        }
+    }
+}
+
+/// Whether `name` (a robots meta tag name) speaks to us.
+pub fn is_robots_meta(name: &str) -> bool {
+    name.eq_ignore_ascii_case("robots") || name.eq_ignore_ascii_case(ROBOT_NAME)
+}
+
+#[derive(Debug, Default, PartialEq)]
+struct Rule {
+    allow: bool,
+    pattern: String,
+}
+
+/// The rules of the robots.txt group that applies to us.
+#[derive(Debug, Default)]
+pub struct Robots {
+    rules: Vec<Rule>,
+}
+
+impl Robots {
+    /// Parse robots.txt, keeping the groups for `bb` or, if there are none,
+    /// those for `*`.
+    pub fn parse(text: &str) -> Self {
+        let mut ours = Vec::new();
+        let mut anyone = Vec::new();
+        let mut agents: Vec<String> = Vec::new();
+        let mut in_rules = false;
+        let mut has_own_group = false;
+
+        for line in text.lines() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            let Some((key, value)) = line.split_once(':') else {
+                continue;
+            };
+            let value = value.trim();
+
+            match key.trim().to_lowercase().as_str() {
+                "user-agent" => {
+                    // a user-agent after rules starts a new group
+                    if in_rules {
+                        agents.clear();
+                        in_rules = false;
+                    }
+                    has_own_group |= value.eq_ignore_ascii_case(ROBOT_NAME);
+                    agents.push(value.to_lowercase());
+                }
+                field @ ("allow" | "disallow") => {
+                    in_rules = true;
+                    // an empty Disallow allows everything
+                    if value.is_empty() {
+                        continue;
+                    }
+                    let rule = || Rule {
+                        allow: field == "allow",
+                        pattern: value.to_string(),
+                    };
+                    if agents.iter().any(|a| a == ROBOT_NAME) {
+                        ours.push(rule());
+                    } else if agents.iter().any(|a| a == "*") {
+                        anyone.push(rule());
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Self {
+            rules: if has_own_group { ours } else { anyone },
+        }
+    }
+
+    /// Whether `path` (with its query) may be fetched: the longest matching
+    /// rule decides, `Allow` winning a tie.
+    pub fn allows(&self, path: &str) -> bool {
+        self.rules
+            .iter()
+            .filter(|rule| pattern_matches(&rule.pattern, path))
+            .max_by_key(|rule| (rule.pattern.len(), rule.allow))
+            .is_none_or(|rule| rule.allow)
+    }
+}
+
+/// robots.txt path patterns: a prefix with `*` for any characters and a
+/// trailing `$` anchoring the end.
+fn pattern_matches(pattern: &str, path: &str) -> bool {
+    let (pattern, anchored) = match pattern.strip_suffix('$') {
+        Some(pattern) => (pattern, true),
+        None => (pattern, false),
+    };
+    let mut parts = pattern.split('*');
+    let Some(mut rest) = path.strip_prefix(parts.next().unwrap_or_default()) else {
+        return false;
+    };
+    let parts: Vec<&str> = parts.collect();
+    let Some((last, middle)) = parts.split_last() else {
+        return !anchored || rest.is_empty();
+    };
+
+    for part in middle {
+        match rest.find(part) {
+            Some(pos) => rest = &rest[pos + part.len()..],
+            None => return false,
+        }
+    }
+    if anchored {
+        rest.ends_with(last)
+    } else {
+        rest.contains(last)
+    }
+}
+
+fn robots_for(origin: &str, scrape_config: &ScrapeConfig) -> Option<Arc<Robots>> {
+    if let Some((fetched, robots)) = ROBOTS_CACHE.lock().unwrap().get(origin) {
+        if fetched.elapsed() < ROBOTS_TTL {
+            return robots.clone();
+        }
+    }
+
+    // fetched without holding the cache, so one slow host doesn't stall others
+    let robots_url = format!("{origin}/robots.txt");
+    let robots = super::reqwest_with_retries(&robots_url, Some(scrape_config))
+        .map(|(_status, bytes)| Arc::new(Robots::parse(&String::from_utf8_lossy(&bytes))));
+    if robots.is_none() {
+        log::debug!("{robots_url}: none, allowing everything");
+    }
+
+    ROBOTS_CACHE
+        .lock()
+        .unwrap()
+        .insert(origin.to_string(), (Instant::now(), robots.clone()));
+    robots
+}
+
+/// Whether robots.txt lets us fetch `url`. Always true unless
+/// `scrape.respect_robots` is on; a missing or unreachable robots.txt allows
+/// everything.
+pub fn allowed(url: &str, scrape_config: Option<&ScrapeConfig>) -> bool {
+    let Some(config) = scrape_config.filter(|c| c.respect_robots) else {
+        return true;
+    };
+    let Ok(url) = reqwest::Url::parse(url) else {
+        return true;
+    };
+    let origin = url.origin().ascii_serialization();
+    let Some(robots) = robots_for(&origin, config) else {
+        return true;
+    };
+
+    let path = match url.query() {
+        Some(query) => format!("{}?{query}", url.path()),
+        None => url.path().to_string(),
+    };
+    let allowed = robots.allows(&path);
+    if !allowed {
+        log::info!("{url}: disallowed by robots.txt");
+    }
+    allowed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ROBOTS: &str = "# example\n\
+        User-agent: Googlebot\n\
+        Disallow: /\n\
+        \n\
+        User-agent: *\n\
+        Disallow: /private/\n\
+        Allow: /private/press\n\
+        Disallow: /*.pdf$\n\
+        Disallow: /search?*q=\n\
+        Disallow:\n";
+
+    #[test]
+    fn test_robots_rules() {
+        let robots = Robots::parse(ROBOTS);
+        assert!(robots.allows("/"));
+        assert!(robots.allows("/blog/post"));
+        assert!(!robots.allows("/private/notes"));
+        assert!(robots.allows("/private/press/2024"));
+        assert!(!robots.allows("/papers/paper.pdf"));
+        assert!(robots.allows("/papers/paper.pdf.html"));
+        assert!(!robots.allows("/search?lang=en&q=rust"));
+        assert!(robots.allows("/search?lang=en"));
+    }
+
+    #[test]
+    fn test_robots_own_group_wins() {
+        let robots = Robots::parse("User-agent: *\nDisallow: /\n\nUser-agent: other\nUser-agent: bb\nDisallow: /admin\n");
+        assert!(robots.allows("/blog"));
+        assert!(!robots.allows("/admin/users"));
+
+        assert!(!Robots::parse("User-agent: *\nDisallow: /\n").allows("/blog"));
+        assert!(Robots::parse("").allows("/anything"));
+    }
+
+    #[test]
+    fn test_robots_hints() {
+        assert_eq!(
+            RobotsHints::parse("noarchive, NoImageIndex"),
+            RobotsHints {
+                noarchive: true,
+                noimageindex: true
+            }
+        );
+        assert!(RobotsHints::parse("index, follow").is_empty());
+        assert!(RobotsHints::parse("googlebot: noarchive").is_empty());
+        assert!(RobotsHints::parse("bb: noarchive").noarchive);
+        assert!(allowed("https://example.com/private/", Some(&ScrapeConfig::default())));
+    }
+}
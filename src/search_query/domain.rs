@@ -0,0 +1,89 @@
+use std::collections::HashMap;
+use std::net::IpAddr;
+
+use once_cell::sync::Lazy;
+use publicsuffix::{List, Psl};
+use url::Url;
+
+use crate::bookmarks::Bookmark;
+
+/// Mozilla's public suffix list, ICANN and private sections, from
+/// https://publicsuffix.org/list/public_suffix_list.dat
+static PUBLIC_SUFFIXES: Lazy<List> = Lazy::new(|| {
+    include_str!("public_suffix_list.dat")
+        .parse()
+        .expect("bundled public suffix list parses")
+});
+
+fn parse_url(url: &str) -> Option<Url> {
+    let url = url.trim();
+    match Url::parse(url) {
+        Ok(parsed) if parsed.host_str().is_some() => Some(parsed),
+        // Scheme-less input such as `example.com/page`
+        _ => Url::parse(&format!("https://{}", url)).ok(),
+    }
+}
+
+/// Lowercased host of `url` with a leading `www.` removed.
+pub fn host(url: &str) -> Option<String> {
+    let parsed = parse_url(url)?;
+    let host = parsed.host_str()?.trim_end_matches('.').to_lowercase();
+    Some(host.strip_prefix("www.").map(String::from).unwrap_or(host))
+}
+
+/// The registrable domain of `host`: its public suffix plus one label, so
+/// `news.bbc.co.uk` gives `bbc.co.uk` and `user.github.io` stays as is. IP
+/// addresses, single-label hosts and bare suffixes are returned unchanged.
+pub fn registrable_domain(host: &str) -> String {
+    if host.trim_matches(['[', ']']).parse::<IpAddr>().is_ok() {
+        return host.to_string();
+    }
+    PUBLIC_SUFFIXES
+        .domain(host.as_bytes())
+        .and_then(|domain| std::str::from_utf8(domain.as_bytes()).ok())
+        .unwrap_or(host)
+        .to_string()
+}
+
+/// `domain:`/`site:` — the URL host is `pattern` or one of its subdomains.
+/// A public suffix pattern (`domain:co.uk`) matches every registrable domain
+/// under it. The pattern may be written as a URL (`https://www.github.com/`).
+pub fn domain_matches(pattern: &str, url: &str) -> bool {
+    let Some(pattern) = host(pattern) else {
+        return false;
+    };
+    let Some(host) = host(url) else {
+        return false;
+    };
+
+    host == pattern || host.ends_with(&format!(".{}", pattern))
+}
+
+/// `path:` — the URL path starts with `prefix`. The leading `/` is optional.
+pub fn path_matches(prefix: &str, url: &str) -> bool {
+    let Some(parsed) = parse_url(url) else {
+        return false;
+    };
+    let prefix = prefix.trim().to_lowercase();
+    let prefix = if prefix.starts_with('/') {
+        prefix
+    } else {
+        format!("/{}", prefix)
+    };
+
+    parsed.path().to_lowercase().starts_with(&prefix)
+}
+
+/// Bookmark count per registrable domain, most common first.
+pub fn domain_counts(bookmarks: &[Bookmark]) -> Vec<(String, usize)> {
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for bookmark in bookmarks {
+        if let Some(host) = host(&bookmark.url) {
+            *counts.entry(registrable_domain(&host)).or_default() += 1;
+        }
+    }
+
+    let mut counts: Vec<(String, usize)> = counts.into_iter().collect();
+    counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    counts
+}
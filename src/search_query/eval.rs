@@ -1,4 +1,8 @@
+use super::domain::{domain_matches, path_matches};
+use super::fuzzy;
 use super::parser::{FieldTarget, SearchFilter};
+use super::sources::{source_matches, MetadataSources};
+use super::tags::{tag_count_matches, TagPattern};
 use crate::bookmarks::Bookmark;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -8,12 +12,15 @@ pub enum RequiredId {
     Unsatisfiable,
 }
 
-pub fn eval(filter: &SearchFilter, bookmark: &Bookmark) -> bool {
+/// Whether `bookmark` satisfies `filter`; `source:` terms look the bookmark
+/// up in `sources`.
+pub fn eval(filter: &SearchFilter, bookmark: &Bookmark, sources: &MetadataSources) -> bool {
     match filter {
-        SearchFilter::Term(field, term) => eval_term(field, term, bookmark),
-        SearchFilter::And(a, b) => eval(a, bookmark) && eval(b, bookmark),
-        SearchFilter::Or(a, b) => eval(a, bookmark) || eval(b, bookmark),
-        SearchFilter::Not(inner) => !eval(inner, bookmark),
+        SearchFilter::Term(field, term) => eval_term(field, term, bookmark, sources),
+        SearchFilter::Fuzzy(field, term) => fuzzy::quality(field, term, bookmark).is_some(),
+        SearchFilter::And(a, b) => eval(a, bookmark, sources) && eval(b, bookmark, sources),
+        SearchFilter::Or(a, b) => eval(a, bookmark, sources) || eval(b, bookmark, sources),
+        SearchFilter::Not(inner) => !eval(inner, bookmark, sources),
     }
 }
 
@@ -23,7 +30,7 @@ pub fn required_id_constraint(filter: &SearchFilter) -> RequiredId {
             Ok(id) => RequiredId::Exact(id),
             Err(_) => RequiredId::Unsatisfiable,
         },
-        SearchFilter::Term(_, _) => RequiredId::None,
+        SearchFilter::Term(_, _) | SearchFilter::Fuzzy(_, _) => RequiredId::None,
         SearchFilter::And(a, b) => and_required_id(
             required_id_constraint(a),
             required_id_constraint(b),
@@ -36,6 +43,52 @@ pub fn required_id_constraint(filter: &SearchFilter) -> RequiredId {
     }
 }
 
+/// Whether the filter contains any `~` fuzzy terms.
+pub fn has_fuzzy(filter: &SearchFilter) -> bool {
+    match filter {
+        SearchFilter::Fuzzy(_, _) => true,
+        SearchFilter::Term(_, _) => false,
+        SearchFilter::And(a, b) | SearchFilter::Or(a, b) => has_fuzzy(a) || has_fuzzy(b),
+        SearchFilter::Not(inner) => has_fuzzy(inner),
+    }
+}
+
+/// Rewrite text terms as fuzzy terms. Used for the zero-results fallback;
+/// `#tag`, `=id` and `tags:` terms stay exact, as does anything under `not`.
+pub fn fuzzify(filter: &SearchFilter) -> SearchFilter {
+    match filter {
+        SearchFilter::Term(
+            field @ (FieldTarget::All
+            | FieldTarget::Title
+            | FieldTarget::Description
+            | FieldTarget::Url),
+            term,
+        ) => SearchFilter::Fuzzy(field.clone(), term.clone()),
+        SearchFilter::Term(_, _) | SearchFilter::Fuzzy(_, _) => filter.clone(),
+        SearchFilter::And(a, b) => SearchFilter::And(Box::new(fuzzify(a)), Box::new(fuzzify(b))),
+        SearchFilter::Or(a, b) => SearchFilter::Or(Box::new(fuzzify(a)), Box::new(fuzzify(b))),
+        SearchFilter::Not(inner) => SearchFilter::Not(inner.clone()),
+    }
+}
+
+/// Match quality in `[0.0, 1.0]` for a bookmark that satisfies `filter`.
+/// Exact terms count as 1.0; fuzzy terms score by edit distance. Used to order
+/// results when the query contains fuzzy terms.
+pub fn match_quality(filter: &SearchFilter, bookmark: &Bookmark, sources: &MetadataSources) -> f32 {
+    match filter {
+        SearchFilter::Term(_, _) | SearchFilter::Not(_) => 1.0,
+        SearchFilter::Fuzzy(field, term) => fuzzy::quality(field, term, bookmark).unwrap_or(0.0),
+        SearchFilter::And(a, b) => {
+            match_quality(a, bookmark, sources).min(match_quality(b, bookmark, sources))
+        }
+        SearchFilter::Or(a, b) => [a, b]
+            .into_iter()
+            .filter(|branch| eval(branch, bookmark, sources))
+            .map(|branch| match_quality(branch, bookmark, sources))
+            .fold(0.0, f32::max),
+    }
+}
+
 fn and_required_id(left: RequiredId, right: RequiredId) -> RequiredId {
     match (left, right) {
         (RequiredId::Unsatisfiable, _) | (_, RequiredId::Unsatisfiable) => RequiredId::Unsatisfiable,
@@ -61,19 +114,21 @@ fn or_required_id(left: RequiredId, right: RequiredId) -> RequiredId {
     }
 }
 
-fn eval_term(field: &FieldTarget, term: &str, bm: &Bookmark) -> bool {
+fn eval_term(field: &FieldTarget, term: &str, bm: &Bookmark, sources: &MetadataSources) -> bool {
     let term_lower = term.to_lowercase();
     match field {
         FieldTarget::Tag => {
-            // Exact match or hierarchical: tag == bm_tag || bm_tag starts with "tag/"
-            let tag_prefix = format!("{}/", term_lower);
-            bm.tags
-                .iter()
-                .any(|t| {
-                    let t_lower = t.to_lowercase();
-                    t_lower == term_lower || t_lower.starts_with(&tag_prefix)
-                })
+            // Hierarchical by default (`dev` matches `dev/rust`); `=dev` is exact,
+            // `*`/`**` are segment globs
+            let pattern = TagPattern::parse(term);
+            bm.tags.iter().any(|t| pattern.matches(t))
         }
+        FieldTarget::TagCount => tag_count_matches(term, bm.tags.len()).unwrap_or(false),
+        FieldTarget::Has => field_present(term, bm).unwrap_or(false),
+        FieldTarget::Missing => field_present(term, bm).is_some_and(|present| !present),
+        FieldTarget::Domain => domain_matches(term, &bm.url),
+        FieldTarget::Path => path_matches(term, &bm.url),
+        FieldTarget::Source => source_matches(term, bm.id, sources),
         FieldTarget::Title => bm.title.to_lowercase().contains(&term_lower),
         FieldTarget::Description => bm.description.to_lowercase().contains(&term_lower),
         FieldTarget::Url => bm.url.to_lowercase().contains(&term_lower),
@@ -87,3 +142,21 @@ fn eval_term(field: &FieldTarget, term: &str, bm: &Bookmark) -> bool {
         }
     }
 }
+
+/// Whether the named field carries a value, for `has:`/`missing:` terms.
+/// A title that is blank or just repeats the URL counts as absent, since that
+/// is what a failed metadata fetch leaves behind. `None` for unknown fields.
+pub(super) fn field_present(field: &str, bm: &Bookmark) -> Option<bool> {
+    let present = match field.to_lowercase().as_str() {
+        "image" => bm.image_id.is_some(),
+        "icon" => bm.icon_id.is_some(),
+        "description" => !bm.description.trim().is_empty(),
+        "title" => {
+            let title = bm.title.trim();
+            !title.is_empty() && !title.eq_ignore_ascii_case(bm.url.trim())
+        }
+        "tags" => !bm.tags.is_empty(),
+        _ => return None,
+    };
+    Some(present)
+}
@@ -0,0 +1,438 @@
+use serde::{Deserialize, Serialize};
+
+use super::domain;
+use super::eval::eval;
+use super::fuzzy;
+use super::parser::{FieldTarget, SearchFilter};
+use super::saved::SavedSearches;
+use super::sources::MetadataSources;
+use super::tags::TagPattern;
+use crate::bookmarks::Bookmark;
+
+/// What a query parses to and, optionally, how it applies to one bookmark.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QueryExplanation {
+    pub query: String,
+    /// The normalized AST written back as a query; empty when it matches everything.
+    pub normalized: String,
+    /// Tolerant parse of the query, as workspaces see it. `None` matches everything.
+    pub ast: Option<QueryNode>,
+    /// Why the strict parser (search, rules) rejects the query, if it does.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub strict_error: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bookmark: Option<BookmarkExplanation>,
+}
+
+/// Serializable form of a `SearchFilter`. `and`/`or` chains are flattened.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum QueryNode {
+    Term {
+        field: String,
+        value: String,
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        fuzzy: bool,
+    },
+    And { children: Vec<QueryNode> },
+    Or { children: Vec<QueryNode> },
+    Not { child: Box<QueryNode> },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BookmarkExplanation {
+    pub id: u64,
+    pub matched: bool,
+    /// Every term of the query, in query order.
+    pub terms: Vec<TermMatch>,
+    pub highlights: Vec<Highlight>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TermMatch {
+    pub field: String,
+    pub value: String,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub fuzzy: bool,
+    /// The term sits under `not`, so matching it works against the bookmark.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub negated: bool,
+    pub matched: bool,
+    /// Bookmark fields the term matched on.
+    pub fields: Vec<String>,
+    /// Match quality of a fuzzy term, see `match_quality`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quality: Option<f32>,
+}
+
+/// A matched range of a bookmark field, in characters (not bytes).
+/// For `tags`, `tag` is the index of the tag the range belongs to.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Highlight {
+    pub field: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tag: Option<usize>,
+    pub start: usize,
+    pub end: usize,
+}
+
+/// Explain `input`: the AST after tolerant normalization, whether the strict
+/// parser accepts it, and — given a bookmark — which terms matched where.
+pub fn explain(
+    input: &str,
+    saved: &SavedSearches,
+    sources: &MetadataSources,
+    bookmark: Option<&Bookmark>,
+) -> anyhow::Result<QueryExplanation> {
+    let filter = super::parse_tolerant_with(input, saved)
+        .map_err(|e| anyhow::anyhow!("invalid search query: {}", e))?;
+    let strict_error = super::parse_with(input, saved).err().map(|e| e.to_string());
+
+    Ok(QueryExplanation {
+        query: input.to_string(),
+        normalized: filter.as_ref().map(render).unwrap_or_default(),
+        ast: filter.as_ref().map(to_node),
+        strict_error,
+        bookmark: bookmark.map(|bm| explain_bookmark(filter.as_ref(), bm, sources)),
+    })
+}
+
+fn field_name(field: &FieldTarget) -> &'static str {
+    match field {
+        FieldTarget::All => "all",
+        FieldTarget::Tag => "tag",
+        FieldTarget::Title => "title",
+        FieldTarget::Description => "description",
+        FieldTarget::Url => "url",
+        FieldTarget::Id => "id",
+        FieldTarget::TagCount => "tag_count",
+        FieldTarget::Has => "has",
+        FieldTarget::Missing => "missing",
+        FieldTarget::Domain => "domain",
+        FieldTarget::Path => "path",
+        FieldTarget::Source => "source",
+    }
+}
+
+fn to_node(filter: &SearchFilter) -> QueryNode {
+    match filter {
+        SearchFilter::Term(field, value) | SearchFilter::Fuzzy(field, value) => QueryNode::Term {
+            field: field_name(field).to_string(),
+            value: value.clone(),
+            fuzzy: matches!(filter, SearchFilter::Fuzzy(_, _)),
+        },
+        SearchFilter::And(_, _) => QueryNode::And {
+            children: flatten(filter).into_iter().map(to_node).collect(),
+        },
+        SearchFilter::Or(_, _) => QueryNode::Or {
+            children: flatten(filter).into_iter().map(to_node).collect(),
+        },
+        SearchFilter::Not(inner) => QueryNode::Not {
+            child: Box::new(to_node(inner)),
+        },
+    }
+}
+
+/// Operands of a chain of the same binary operator, left to right.
+fn flatten(filter: &SearchFilter) -> Vec<&SearchFilter> {
+    fn collect<'a>(root: &SearchFilter, filter: &'a SearchFilter, out: &mut Vec<&'a SearchFilter>) {
+        match (root, filter) {
+            (SearchFilter::And(_, _), SearchFilter::And(a, b))
+            | (SearchFilter::Or(_, _), SearchFilter::Or(a, b)) => {
+                collect(root, a, out);
+                collect(root, b, out);
+            }
+            _ => out.push(filter),
+        }
+    }
+
+    let mut out = Vec::new();
+    collect(filter, filter, &mut out);
+    out
+}
+
+fn prefix(field: &FieldTarget) -> &'static str {
+    match field {
+        FieldTarget::All => "",
+        FieldTarget::Tag => "#",
+        FieldTarget::Title => ".",
+        FieldTarget::Description => ">",
+        FieldTarget::Url => ":",
+        FieldTarget::Id => "=",
+        FieldTarget::TagCount => "tags:",
+        FieldTarget::Has => "has:",
+        FieldTarget::Missing => "missing:",
+        FieldTarget::Domain => "domain:",
+        FieldTarget::Path => "path:",
+        FieldTarget::Source => "source:",
+    }
+}
+
+/// Quote `value` when the lexer would otherwise split or reinterpret it.
+/// Bare words additionally must not look like operators or prefixed terms.
+fn quote(value: &str, bare: bool) -> String {
+    let splits = value.is_empty()
+        || value.starts_with('~')
+        || value.chars().any(|c| c.is_whitespace() || matches!(c, '(' | ')' | '"'));
+    let reinterpreted = bare
+        && (matches!(value, "and" | "or" | "not")
+            || value.starts_with(['#', '.', '>', ':', '=', '@', '\\'])
+            || value.contains(':'));
+
+    if splits || reinterpreted {
+        format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Write the AST back as query text. `and` binds tighter than `or`, so only
+/// `or` groups need parentheses under `and`; under `not` both do.
+fn render(filter: &SearchFilter) -> String {
+    fn grouped(filter: &SearchFilter, group_and: bool) -> String {
+        match filter {
+            SearchFilter::Or(_, _) => format!("({})", render(filter)),
+            SearchFilter::And(_, _) if group_and => format!("({})", render(filter)),
+            _ => render(filter),
+        }
+    }
+
+    match filter {
+        SearchFilter::Term(field, value) => {
+            format!("{}{}", prefix(field), quote(value, *field == FieldTarget::All))
+        }
+        SearchFilter::Fuzzy(field, value) => format!("{}~{}", prefix(field), quote(value, false)),
+        SearchFilter::And(_, _) => flatten(filter)
+            .into_iter()
+            .map(|f| grouped(f, false))
+            .collect::<Vec<_>>()
+            .join(" and "),
+        SearchFilter::Or(_, _) => flatten(filter)
+            .into_iter()
+            .map(render)
+            .collect::<Vec<_>>()
+            .join(" or "),
+        SearchFilter::Not(inner) => format!("not {}", grouped(inner, true)),
+    }
+}
+
+fn explain_bookmark(
+    filter: Option<&SearchFilter>,
+    bm: &Bookmark,
+    sources: &MetadataSources,
+) -> BookmarkExplanation {
+    let mut terms = Vec::new();
+    let mut highlights = Vec::new();
+    if let Some(filter) = filter {
+        walk(filter, false, bm, sources, &mut terms, &mut highlights);
+    }
+
+    BookmarkExplanation {
+        id: bm.id,
+        matched: filter.is_none_or(|f| eval(f, bm, sources)),
+        terms,
+        highlights: merge(highlights),
+    }
+}
+
+fn walk(
+    filter: &SearchFilter,
+    negated: bool,
+    bm: &Bookmark,
+    sources: &MetadataSources,
+    terms: &mut Vec<TermMatch>,
+    highlights: &mut Vec<Highlight>,
+) {
+    match filter {
+        SearchFilter::And(a, b) | SearchFilter::Or(a, b) => {
+            walk(a, negated, bm, sources, terms, highlights);
+            walk(b, negated, bm, sources, terms, highlights);
+        }
+        SearchFilter::Not(inner) => walk(inner, !negated, bm, sources, terms, highlights),
+        SearchFilter::Term(field, value) | SearchFilter::Fuzzy(field, value) => {
+            let fuzzy = matches!(filter, SearchFilter::Fuzzy(_, _));
+            let matched = eval(filter, bm, sources);
+
+            // Highlighting a negated match would point at the reason the
+            // bookmark is excluded, not at why it is shown.
+            if matched && !negated {
+                highlights.extend(term_highlights(filter, bm));
+            }
+
+            terms.push(TermMatch {
+                field: field_name(field).to_string(),
+                value: value.clone(),
+                fuzzy,
+                negated,
+                matched,
+                fields: if matched { matched_fields(filter, bm) } else { vec![] },
+                quality: if fuzzy { fuzzy::quality(field, value, bm) } else { None },
+            });
+        }
+    }
+}
+
+/// Searchable text of the fields a term targets: (field, tag index, text).
+fn texts<'a>(field: &FieldTarget, bm: &'a Bookmark) -> Vec<(&'static str, Option<usize>, &'a str)> {
+    let tags = || bm.tags.iter().enumerate().map(|(i, t)| ("tags", Some(i), t.as_str()));
+    match field {
+        FieldTarget::Title => vec![("title", None, bm.title.as_str())],
+        FieldTarget::Description => vec![("description", None, bm.description.as_str())],
+        FieldTarget::Url => vec![("url", None, bm.url.as_str())],
+        FieldTarget::Tag => tags().collect(),
+        FieldTarget::All => [
+            ("title", None, bm.title.as_str()),
+            ("description", None, bm.description.as_str()),
+            ("url", None, bm.url.as_str()),
+        ]
+        .into_iter()
+        .chain(tags())
+        .collect(),
+        FieldTarget::Id
+        | FieldTarget::TagCount
+        | FieldTarget::Has
+        | FieldTarget::Missing
+        | FieldTarget::Domain
+        | FieldTarget::Path
+        | FieldTarget::Source => vec![],
+    }
+}
+
+/// Fields a matching term matched on.
+fn matched_fields(filter: &SearchFilter, bm: &Bookmark) -> Vec<String> {
+    let (field, value, fuzzy) = match filter {
+        SearchFilter::Term(field, value) => (field, value, false),
+        SearchFilter::Fuzzy(field, value) => (field, value, true),
+        _ => return vec![],
+    };
+
+    let mut fields: Vec<String> = match field {
+        FieldTarget::Id => vec!["id".into()],
+        FieldTarget::TagCount => vec!["tags".into()],
+        FieldTarget::Has | FieldTarget::Missing => vec![value.to_lowercase()],
+        FieldTarget::Domain | FieldTarget::Path => vec!["url".into()],
+        FieldTarget::Source => vec!["source".into()],
+        FieldTarget::Tag if !fuzzy => vec!["tags".into()],
+        _ => {
+            let value_lower = value.to_lowercase();
+            texts(field, bm)
+                .into_iter()
+                .filter(|(_, _, text)| {
+                    if fuzzy {
+                        fuzzy::text_quality(value, text).is_some()
+                    } else {
+                        text.to_lowercase().contains(&value_lower)
+                    }
+                })
+                .map(|(name, _, _)| name.to_string())
+                .collect()
+        }
+    };
+    fields.dedup();
+    fields
+}
+
+fn term_highlights(filter: &SearchFilter, bm: &Bookmark) -> Vec<Highlight> {
+    let highlight = |field: &str, tag: Option<usize>, (start, end): (usize, usize)| Highlight {
+        field: field.to_string(),
+        tag,
+        start,
+        end,
+    };
+
+    match filter {
+        SearchFilter::Term(FieldTarget::Tag, value) => {
+            let pattern = TagPattern::parse(value);
+            bm.tags
+                .iter()
+                .enumerate()
+                .filter(|(_, t)| pattern.matches(t))
+                .map(|(i, t)| highlight("tags", Some(i), (0, t.chars().count())))
+                .collect()
+        }
+        SearchFilter::Term(FieldTarget::Domain, _) => host_span(&bm.url)
+            .map(|span| highlight("url", None, span))
+            .into_iter()
+            .collect(),
+        SearchFilter::Term(FieldTarget::Path, value) => {
+            let prefix = value.trim();
+            let prefix = if prefix.starts_with('/') {
+                prefix.to_string()
+            } else {
+                format!("/{}", prefix)
+            };
+            let after_host = host_span(&bm.url).map_or(0, |(_, end)| end);
+            find_spans(&bm.url, &prefix)
+                .into_iter()
+                .find(|(start, _)| *start >= after_host)
+                .map(|span| highlight("url", None, span))
+                .into_iter()
+                .collect()
+        }
+        SearchFilter::Term(field, value) => texts(field, bm)
+            .into_iter()
+            .flat_map(|(name, tag, text)| {
+                find_spans(text, value)
+                    .into_iter()
+                    .map(move |span| highlight(name, tag, span))
+            })
+            .collect(),
+        SearchFilter::Fuzzy(field, value) => texts(field, bm)
+            .into_iter()
+            .filter(|(_, _, text)| fuzzy::text_quality(value, text).is_some())
+            .flat_map(|(name, tag, text)| {
+                fuzzy::word_spans(value, text)
+                    .into_iter()
+                    .map(move |span| highlight(name, tag, span))
+            })
+            .collect(),
+        _ => vec![],
+    }
+}
+
+fn host_span(url: &str) -> Option<(usize, usize)> {
+    let host = domain::host(url)?;
+    find_spans(url, &host).into_iter().next()
+}
+
+/// Case-insensitive, non-overlapping occurrences of `needle` in `text`, as
+/// char offsets.
+fn find_spans(text: &str, needle: &str) -> Vec<(usize, usize)> {
+    let lower = |c: char| c.to_lowercase().next().unwrap_or(c);
+    let hay: Vec<char> = text.chars().map(lower).collect();
+    let needle: Vec<char> = needle.chars().map(lower).collect();
+
+    let mut spans = Vec::new();
+    if needle.is_empty() {
+        return spans;
+    }
+
+    let mut i = 0;
+    while i + needle.len() <= hay.len() {
+        if hay[i..i + needle.len()] == needle[..] {
+            spans.push((i, i + needle.len()));
+            i += needle.len();
+        } else {
+            i += 1;
+        }
+    }
+    spans
+}
+
+/// Sort highlights and join the ones that overlap or touch.
+fn merge(mut highlights: Vec<Highlight>) -> Vec<Highlight> {
+    highlights.sort_by(|a, b| {
+        (&a.field, a.tag, a.start, a.end).cmp(&(&b.field, b.tag, b.start, b.end))
+    });
+
+    let mut merged: Vec<Highlight> = Vec::with_capacity(highlights.len());
+    for h in highlights {
+        match merged.last_mut() {
+            Some(last) if last.field == h.field && last.tag == h.tag && h.start <= last.end => {
+                last.end = last.end.max(h.end);
+            }
+            _ => merged.push(h),
+        }
+    }
+    merged
+}
@@ -0,0 +1,88 @@
+use std::collections::{BTreeSet, HashMap};
+
+use serde::{Deserialize, Serialize};
+
+use super::domain;
+use super::eval::field_present;
+use crate::bookmarks::Bookmark;
+
+/// Fields counted as flags; each name works with `has:`/`missing:`.
+const FLAGS: [&str; 5] = ["title", "description", "tags", "image", "icon"];
+
+/// Counts over a set of matched bookmarks, for sidebars and filters.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Facets {
+    /// Every tag path and its ancestors, most common first. A bookmark tagged
+    /// `dev/rust` counts towards both `dev` and `dev/rust`.
+    pub tags: Vec<FacetCount>,
+    /// Registrable domains (public suffix list), most common first.
+    pub domains: Vec<FacetCount>,
+    /// How many bookmarks have each flag field, in `FLAGS` order.
+    pub flags: Vec<FacetCount>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FacetCount {
+    pub value: String,
+    pub count: usize,
+}
+
+impl FacetCount {
+    fn new(value: &str, count: usize) -> Self {
+        Self {
+            value: value.to_string(),
+            count,
+        }
+    }
+}
+
+/// Tag, domain and flag counts over `bookmarks`.
+pub fn facets(bookmarks: &[Bookmark]) -> Facets {
+    let mut tags: HashMap<String, usize> = HashMap::new();
+    for bookmark in bookmarks {
+        // Each path counts once per bookmark, even when several tags share it
+        let mut paths = BTreeSet::new();
+        for tag in &bookmark.tags {
+            let tag = tag.trim_matches('/');
+            for (i, c) in tag.char_indices() {
+                if c == '/' {
+                    paths.insert(&tag[..i]);
+                }
+            }
+            if !tag.is_empty() {
+                paths.insert(tag);
+            }
+        }
+        for path in paths {
+            *tags.entry(path.to_string()).or_default() += 1;
+        }
+    }
+
+    let mut tags: Vec<FacetCount> = tags
+        .into_iter()
+        .map(|(value, count)| FacetCount { value, count })
+        .collect();
+    tags.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.value.cmp(&b.value)));
+
+    let domains = domain::domain_counts(bookmarks)
+        .into_iter()
+        .map(|(value, count)| FacetCount { value, count })
+        .collect();
+
+    let flags = FLAGS
+        .iter()
+        .map(|flag| {
+            let count = bookmarks
+                .iter()
+                .filter(|bm| field_present(flag, bm) == Some(true))
+                .count();
+            FacetCount::new(flag, count)
+        })
+        .collect();
+
+    Facets {
+        tags,
+        domains,
+        flags,
+    }
+}
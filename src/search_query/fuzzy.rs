@@ -0,0 +1,166 @@
+use super::parser::FieldTarget;
+use crate::bookmarks::Bookmark;
+
+/// Lowercase and strip common Latin diacritics (`é` → `e`, `ß` → `ss`).
+pub fn fold(input: &str) -> String {
+    let mut out = String::with_capacity(input.len());
+    for c in input.chars().flat_map(char::to_lowercase) {
+        match c {
+            'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' | 'ā' | 'ă' | 'ą' => out.push('a'),
+            'æ' => out.push_str("ae"),
+            'ç' | 'ć' | 'ĉ' | 'ċ' | 'č' => out.push('c'),
+            'ď' | 'đ' | 'ð' => out.push('d'),
+            'è' | 'é' | 'ê' | 'ë' | 'ē' | 'ĕ' | 'ė' | 'ę' | 'ě' => out.push('e'),
+            'ĝ' | 'ğ' | 'ġ' | 'ģ' => out.push('g'),
+            'ĥ' | 'ħ' => out.push('h'),
+            'ì' | 'í' | 'î' | 'ï' | 'ĩ' | 'ī' | 'ĭ' | 'į' | 'ı' => out.push('i'),
+            'ĵ' => out.push('j'),
+            'ķ' => out.push('k'),
+            'ĺ' | 'ļ' | 'ľ' | 'ŀ' | 'ł' => out.push('l'),
+            'ñ' | 'ń' | 'ņ' | 'ň' => out.push('n'),
+            'ò' | 'ó' | 'ô' | 'õ' | 'ö' | 'ø' | 'ō' | 'ŏ' | 'ő' => out.push('o'),
+            'œ' => out.push_str("oe"),
+            'ŕ' | 'ŗ' | 'ř' => out.push('r'),
+            'ś' | 'ŝ' | 'ş' | 'š' => out.push('s'),
+            'ß' => out.push_str("ss"),
+            'ţ' | 'ť' | 'ŧ' => out.push('t'),
+            'ù' | 'ú' | 'û' | 'ü' | 'ũ' | 'ū' | 'ŭ' | 'ů' | 'ű' | 'ų' => out.push('u'),
+            'ŵ' => out.push('w'),
+            'ý' | 'ÿ' | 'ŷ' => out.push('y'),
+            'ź' | 'ż' | 'ž' => out.push('z'),
+            'þ' => out.push_str("th"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// Edit budget for a query word: short words must match exactly,
+/// longer words tolerate one or two typos.
+fn max_edits(len: usize) -> usize {
+    match len {
+        0..=3 => 0,
+        4..=6 => 1,
+        _ => 2,
+    }
+}
+
+/// Levenshtein distance over chars, giving up once `limit` is exceeded.
+fn bounded_distance(a: &[char], b: &[char], limit: usize) -> Option<usize> {
+    if a.len().abs_diff(b.len()) > limit {
+        return None;
+    }
+
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    let mut curr = vec![0; b.len() + 1];
+
+    for (i, ca) in a.iter().enumerate() {
+        curr[0] = i + 1;
+        let mut row_min = curr[0];
+        for (j, cb) in b.iter().enumerate() {
+            let cost = if ca == cb { 0 } else { 1 };
+            curr[j + 1] = (prev[j] + cost).min(prev[j + 1] + 1).min(curr[j] + 1);
+            row_min = row_min.min(curr[j + 1]);
+        }
+        if row_min > limit {
+            return None;
+        }
+        std::mem::swap(&mut prev, &mut curr);
+    }
+
+    let distance = prev[b.len()];
+    (distance <= limit).then_some(distance)
+}
+
+fn words(text: &str) -> impl Iterator<Item = &str> {
+    text.split(|c: char| !c.is_alphanumeric()).filter(|w| !w.is_empty())
+}
+
+/// Match quality of a fuzzy term against a piece of text, in `(0.0, 1.0]`.
+/// Every word of the term has to match some word of the text within its edit
+/// budget; a plain substring hit scores 1.0.
+pub fn text_quality(term: &str, text: &str) -> Option<f32> {
+    let term = fold(term);
+    let text = fold(text);
+
+    if term.trim().is_empty() {
+        return None;
+    }
+    if text.contains(term.as_str()) {
+        return Some(1.0);
+    }
+
+    let text_words: Vec<Vec<char>> = words(&text).map(|w| w.chars().collect()).collect();
+    let mut total = 0.0;
+    let mut count = 0;
+
+    for term_word in words(&term) {
+        let term_chars: Vec<char> = term_word.chars().collect();
+        let limit = max_edits(term_chars.len());
+        let best = text_words
+            .iter()
+            .filter_map(|w| bounded_distance(&term_chars, w, limit))
+            .min()?;
+        total += 1.0 - best as f32 / (term_chars.len() + 1) as f32;
+        count += 1;
+    }
+
+    (count > 0).then(|| total / count as f32)
+}
+
+/// Char ranges of the words in `text` that some word of a fuzzy `term`
+/// matches, for highlighting.
+pub fn word_spans(term: &str, text: &str) -> Vec<(usize, usize)> {
+    let term = fold(term);
+    let term_words: Vec<Vec<char>> = words(&term).map(|w| w.chars().collect()).collect();
+    let chars: Vec<char> = text.chars().collect();
+
+    let mut spans = Vec::new();
+    let mut start = None;
+    for i in 0..=chars.len() {
+        if chars.get(i).is_some_and(|c| c.is_alphanumeric()) {
+            start.get_or_insert(i);
+            continue;
+        }
+        let Some(s) = start.take() else {
+            continue;
+        };
+
+        let word: Vec<char> = fold(&chars[s..i].iter().collect::<String>()).chars().collect();
+        let hit = term_words.iter().any(|tw| {
+            word.windows(tw.len()).any(|w| w == tw.as_slice())
+                || bounded_distance(tw, &word, max_edits(tw.len())).is_some()
+        });
+        if hit {
+            spans.push((s, i));
+        }
+    }
+    spans
+}
+
+fn best_quality<'a>(term: &str, texts: impl Iterator<Item = &'a str>) -> Option<f32> {
+    texts.filter_map(|text| text_quality(term, text)).reduce(f32::max)
+}
+
+/// Best match quality of a fuzzy term against the targeted bookmark field(s).
+pub fn quality(field: &FieldTarget, term: &str, bm: &Bookmark) -> Option<f32> {
+    match field {
+        FieldTarget::Title => text_quality(term, &bm.title),
+        FieldTarget::Description => text_quality(term, &bm.description),
+        FieldTarget::Url => text_quality(term, &bm.url),
+        FieldTarget::Tag => best_quality(term, bm.tags.iter().map(String::as_str)),
+        FieldTarget::All => best_quality(
+            term,
+            [bm.title.as_str(), bm.description.as_str(), bm.url.as_str()]
+                .into_iter()
+                .chain(bm.tags.iter().map(String::as_str)),
+        ),
+        FieldTarget::Id
+        | FieldTarget::TagCount
+        | FieldTarget::Has
+        | FieldTarget::Missing
+        | FieldTarget::Domain
+        | FieldTarget::Path
+        | FieldTarget::Source => None,
+    }
+}
@@ -0,0 +1,196 @@
+use std::collections::HashMap;
+
+use super::parser::{FieldTarget, SearchFilter};
+use super::tags::TagPattern;
+use crate::bookmarks::Bookmark;
+
+/// Bookmark ids per key, kept sorted.
+type Postings<K> = HashMap<K, Vec<u64>>;
+
+/// In-memory index used to prune candidates before `eval`.
+///
+/// Text fields are indexed by lowercased character trigrams, so a substring
+/// term can only match bookmarks that contain all of its trigrams. Tags are
+/// additionally indexed by path segment for `#` terms. Lookups return a
+/// superset of the matches; `eval` still decides.
+#[derive(Debug, Default)]
+pub struct SearchIndex {
+    title: Postings<u64>,
+    description: Postings<u64>,
+    url: Postings<u64>,
+    tags: Postings<u64>,
+    tag_segments: Postings<String>,
+}
+
+impl SearchIndex {
+    pub fn build(bookmarks: &[Bookmark]) -> Self {
+        let mut index = Self::default();
+        for bookmark in bookmarks {
+            index.insert(bookmark);
+        }
+        index
+    }
+
+    pub fn insert(&mut self, bm: &Bookmark) {
+        for gram in trigrams(&bm.title) {
+            add(&mut self.title, gram, bm.id);
+        }
+        for gram in trigrams(&bm.description) {
+            add(&mut self.description, gram, bm.id);
+        }
+        for gram in trigrams(&bm.url) {
+            add(&mut self.url, gram, bm.id);
+        }
+        for tag in &bm.tags {
+            for gram in trigrams(tag) {
+                add(&mut self.tags, gram, bm.id);
+            }
+            for segment in tag.to_lowercase().split('/') {
+                add(&mut self.tag_segments, segment.to_string(), bm.id);
+            }
+        }
+    }
+
+    /// Drop `bm` from the index. It must be the bookmark as it was inserted.
+    pub fn remove(&mut self, bm: &Bookmark) {
+        for gram in trigrams(&bm.title) {
+            drop_id(&mut self.title, &gram, bm.id);
+        }
+        for gram in trigrams(&bm.description) {
+            drop_id(&mut self.description, &gram, bm.id);
+        }
+        for gram in trigrams(&bm.url) {
+            drop_id(&mut self.url, &gram, bm.id);
+        }
+        for tag in &bm.tags {
+            for gram in trigrams(tag) {
+                drop_id(&mut self.tags, &gram, bm.id);
+            }
+            for segment in tag.to_lowercase().split('/') {
+                drop_id(&mut self.tag_segments, segment, bm.id);
+            }
+        }
+    }
+
+    /// Sorted ids of the bookmarks that may match `filter`, or `None` when
+    /// the index can't narrow it down and every bookmark has to be checked.
+    pub fn candidates(&self, filter: &SearchFilter) -> Option<Vec<u64>> {
+        match filter {
+            SearchFilter::Term(FieldTarget::Title, term) => lookup(&self.title, term),
+            SearchFilter::Term(FieldTarget::Description, term) => lookup(&self.description, term),
+            SearchFilter::Term(FieldTarget::Url, term) => lookup(&self.url, term),
+            SearchFilter::Term(FieldTarget::All, term) => {
+                let mut ids = lookup(&self.title, term)?;
+                for postings in [&self.description, &self.url, &self.tags] {
+                    ids = union(&ids, &lookup(postings, term)?);
+                }
+                Some(ids)
+            }
+            SearchFilter::Term(FieldTarget::Tag, term) => {
+                // Every literal segment of the pattern has to be a segment of
+                // one of the bookmark's tags
+                let pattern = TagPattern::parse(term);
+                pattern
+                    .literal_segments()
+                    .map(|segment| self.tag_segments.get(segment).cloned().unwrap_or_default())
+                    .reduce(|a, b| intersect(&a, &b))
+            }
+            SearchFilter::And(a, b) => match (self.candidates(a), self.candidates(b)) {
+                (Some(a), Some(b)) => Some(intersect(&a, &b)),
+                (Some(ids), None) | (None, Some(ids)) => Some(ids),
+                (None, None) => None,
+            },
+            SearchFilter::Or(a, b) => Some(union(&self.candidates(a)?, &self.candidates(b)?)),
+            SearchFilter::Term(_, _) | SearchFilter::Fuzzy(_, _) | SearchFilter::Not(_) => None,
+        }
+    }
+}
+
+/// Distinct trigrams of the lowercased text, each packed into a `u64`.
+fn trigrams(text: &str) -> Vec<u64> {
+    let chars: Vec<char> = text.to_lowercase().chars().collect();
+    let mut grams: Vec<u64> = chars
+        .windows(3)
+        .map(|w| ((w[0] as u64) << 42) | ((w[1] as u64) << 21) | w[2] as u64)
+        .collect();
+    grams.sort_unstable();
+    grams.dedup();
+    grams
+}
+
+/// Ids whose field contains every trigram of `term`; `None` for terms too
+/// short to have any.
+fn lookup(postings: &Postings<u64>, term: &str) -> Option<Vec<u64>> {
+    let mut lists: Vec<&[u64]> = trigrams(term)
+        .iter()
+        .map(|gram| postings.get(gram).map_or(&[][..], Vec::as_slice))
+        .collect();
+    lists.sort_by_key(|ids| ids.len());
+
+    let (first, rest) = lists.split_first()?;
+    Some(rest.iter().fold(first.to_vec(), |acc, ids| intersect(&acc, ids)))
+}
+
+fn add<K: std::hash::Hash + Eq>(postings: &mut Postings<K>, key: K, id: u64) {
+    let ids = postings.entry(key).or_default();
+    if let Err(pos) = ids.binary_search(&id) {
+        ids.insert(pos, id);
+    }
+}
+
+fn drop_id<K, Q>(postings: &mut Postings<K>, key: &Q, id: u64)
+where
+    K: std::borrow::Borrow<Q> + std::hash::Hash + Eq,
+    Q: std::hash::Hash + Eq + ?Sized,
+{
+    if let Some(ids) = postings.get_mut(key) {
+        if let Ok(pos) = ids.binary_search(&id) {
+            ids.remove(pos);
+        }
+        if ids.is_empty() {
+            postings.remove(key);
+        }
+    }
+}
+
+fn intersect(a: &[u64], b: &[u64]) -> Vec<u64> {
+    let (mut i, mut j) = (0, 0);
+    let mut out = Vec::with_capacity(a.len().min(b.len()));
+    while i < a.len() && j < b.len() {
+        match a[i].cmp(&b[j]) {
+            std::cmp::Ordering::Less => i += 1,
+            std::cmp::Ordering::Greater => j += 1,
+            std::cmp::Ordering::Equal => {
+                out.push(a[i]);
+                i += 1;
+                j += 1;
+            }
+        }
+    }
+    out
+}
+
+fn union(a: &[u64], b: &[u64]) -> Vec<u64> {
+    let (mut i, mut j) = (0, 0);
+    let mut out = Vec::with_capacity(a.len() + b.len());
+    while i < a.len() && j < b.len() {
+        match a[i].cmp(&b[j]) {
+            std::cmp::Ordering::Less => {
+                out.push(a[i]);
+                i += 1;
+            }
+            std::cmp::Ordering::Greater => {
+                out.push(b[j]);
+                j += 1;
+            }
+            std::cmp::Ordering::Equal => {
+                out.push(a[i]);
+                i += 1;
+                j += 1;
+            }
+        }
+    }
+    out.extend_from_slice(&a[i..]);
+    out.extend_from_slice(&b[j..]);
+    out
+}
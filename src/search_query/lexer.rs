@@ -5,6 +5,38 @@ pub enum Prefix {
     Description, // >
     Url,         // :
     Id,          // =
+    TagCount,    // tags:
+    Has,         // has:
+    Missing,     // missing:
+    Domain,      // domain: / site:
+    Path,        // path:
+    Source,      // source:
+}
+
+/// Single-character prefixes written before the term (e.g. `#rust`).
+fn symbol_prefix(symbol: char) -> Option<Prefix> {
+    match symbol {
+        '#' => Some(Prefix::Tag),
+        '.' => Some(Prefix::Title),
+        '>' => Some(Prefix::Description),
+        ':' => Some(Prefix::Url),
+        '=' => Some(Prefix::Id),
+        _ => None,
+    }
+}
+
+/// Keyword operators written as `key:value` (e.g. `tags:>3`).
+/// Unknown keys are left as plain words so URLs and `a:b` text still search literally.
+fn keyword_prefix(key: &str) -> Option<Prefix> {
+    match key.to_ascii_lowercase().as_str() {
+        "tags" => Some(Prefix::TagCount),
+        "has" => Some(Prefix::Has),
+        "missing" => Some(Prefix::Missing),
+        "domain" | "site" => Some(Prefix::Domain),
+        "path" => Some(Prefix::Path),
+        "source" => Some(Prefix::Source),
+        _ => None,
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -13,6 +45,10 @@ pub enum Token {
     QuotedString(String),
     PrefixedWord(Prefix, String),
     PrefixedQuoted(Prefix, String),
+    /// `~term`, `~"two words"` or `.~term` — typo-tolerant match
+    Fuzzy(Option<Prefix>, String),
+    /// `@name` — reference to a saved search, expanded before parsing
+    Macro(String),
     And,
     Or,
     Not,
@@ -27,6 +63,8 @@ impl std::fmt::Display for Token {
             Token::QuotedString(s) => write!(f, "\"{}\"", s),
             Token::PrefixedWord(_, w) => write!(f, "'{}'", w),
             Token::PrefixedQuoted(_, s) => write!(f, "\"{}\"", s),
+            Token::Fuzzy(_, s) => write!(f, "'~{}'", s),
+            Token::Macro(name) => write!(f, "'@{}'", name),
             Token::And => write!(f, "'and'"),
             Token::Or => write!(f, "'or'"),
             Token::Not => write!(f, "'not'"),
@@ -44,6 +82,30 @@ pub fn tokenize(input: &str) -> Vec<Token> {
     let mut i = 0;
 
     while i < len {
+        if let Some(prefix) = symbol_prefix(chars[i]) {
+            let symbol = chars[i];
+            i += 1;
+            if i < len && chars[i] == '~' && prefix != Prefix::Id {
+                i += 1;
+                match read_fuzzy(&chars, &mut i) {
+                    Some(s) => tokens.push(Token::Fuzzy(Some(prefix), s)),
+                    None => tokens.push(Token::PrefixedWord(prefix, "~".to_string())),
+                }
+            } else if i < len && chars[i] == '"' {
+                let s = read_quoted(&chars, &mut i);
+                tokens.push(Token::PrefixedQuoted(prefix, s));
+            } else {
+                let word = read_word(&chars, &mut i);
+                if word.is_empty() {
+                    // Bare prefix with no following word — emit as literal
+                    tokens.push(Token::Word(symbol.to_string()));
+                } else {
+                    tokens.push(Token::PrefixedWord(prefix, word));
+                }
+            }
+            continue;
+        }
+
         match chars[i] {
             ' ' | '\t' | '\n' | '\r' => {
                 i += 1;
@@ -60,34 +122,20 @@ pub fn tokenize(input: &str) -> Vec<Token> {
                 let s = read_quoted(&chars, &mut i);
                 tokens.push(Token::QuotedString(s));
             }
-            '#' | '.' | '>' | ':' | '=' => {
-                let prefix = match chars[i] {
-                    '#' => Prefix::Tag,
-                    '.' => Prefix::Title,
-                    '>' => Prefix::Description,
-                    ':' => Prefix::Url,
-                    '=' => Prefix::Id,
-                    _ => unreachable!(),
-                };
+            '@' => {
                 i += 1;
-                if i < len && chars[i] == '"' {
-                    let s = read_quoted(&chars, &mut i);
-                    tokens.push(Token::PrefixedQuoted(prefix, s));
+                let word = read_word(&chars, &mut i);
+                if !word.is_empty() && word.chars().all(super::saved::is_name_char) {
+                    tokens.push(Token::Macro(word));
                 } else {
-                    let word = read_word(&chars, &mut i);
-                    if word.is_empty() {
-                        // Bare prefix with no following word — emit as literal
-                        let literal = match prefix {
-                            Prefix::Tag => "#",
-                            Prefix::Title => ".",
-                            Prefix::Description => ">",
-                            Prefix::Url => ":",
-                            Prefix::Id => "=",
-                        };
-                        tokens.push(Token::Word(literal.to_string()));
-                    } else {
-                        tokens.push(Token::PrefixedWord(prefix, word));
-                    }
+                    tokens.push(Token::Word(format!("@{}", word)));
+                }
+            }
+            '~' => {
+                i += 1;
+                match read_fuzzy(&chars, &mut i) {
+                    Some(s) => tokens.push(Token::Fuzzy(None, s)),
+                    None => tokens.push(Token::Word("~".to_string())),
                 }
             }
             '\\' => {
@@ -109,7 +157,7 @@ pub fn tokenize(input: &str) -> Vec<Token> {
                     "and" => tokens.push(Token::And),
                     "or" => tokens.push(Token::Or),
                     "not" => tokens.push(Token::Not),
-                    _ => tokens.push(Token::Word(word)),
+                    _ => tokens.push(keyword_token(word, &chars, &mut i)),
                 }
             }
         }
@@ -118,6 +166,35 @@ pub fn tokenize(input: &str) -> Vec<Token> {
     tokens
 }
 
+/// Turn `key:value` / `key:"value"` into a prefixed token when `key` is a known
+/// keyword operator. Anything else stays a literal word.
+fn keyword_token(word: String, chars: &[char], i: &mut usize) -> Token {
+    let Some((key, value)) = word.split_once(':') else {
+        return Token::Word(word);
+    };
+    let Some(prefix) = keyword_prefix(key) else {
+        return Token::Word(word);
+    };
+
+    if !value.is_empty() {
+        return Token::PrefixedWord(prefix, value.to_string());
+    }
+    if *i < chars.len() && chars[*i] == '"' {
+        let s = read_quoted(chars, i);
+        return Token::PrefixedQuoted(prefix, s);
+    }
+    Token::Word(word)
+}
+
+/// Read the term following `~`, quoted or bare. `None` when nothing follows.
+fn read_fuzzy(chars: &[char], i: &mut usize) -> Option<String> {
+    if *i < chars.len() && chars[*i] == '"' {
+        return Some(read_quoted(chars, i));
+    }
+    let word = read_word(chars, i);
+    (!word.is_empty()).then_some(word)
+}
+
 /// Read a quoted string. Tolerant: unterminated quote treats rest-of-input as the string.
 fn read_quoted(chars: &[char], i: &mut usize) -> String {
     *i += 1; // skip opening quote
@@ -1,18 +1,41 @@
+mod domain;
 mod eval;
+mod explain;
+mod facets;
+mod fuzzy;
+mod index;
 mod lexer;
 mod normalize;
 mod parser;
+mod rank;
+mod saved;
+mod sources;
+mod tags;
 
+#[cfg(test)]
 use crate::bookmarks::Bookmark;
 
+pub use domain::domain_counts;
+pub use domain::host as url_host;
 pub use eval::eval;
+pub use eval::fuzzify;
+pub use eval::has_fuzzy;
+pub use eval::match_quality;
 pub use eval::required_id_constraint;
 pub use eval::RequiredId;
+pub use explain::{explain, QueryExplanation};
+pub use facets::{facets, Facets};
+pub use index::SearchIndex;
 pub use parser::SearchFilter;
+pub use rank::rank_by_relevance;
+pub use saved::{is_name_char, SavedSearches};
+pub use sources::MetadataSources;
+pub use tags::TagPattern;
 
 use lexer::Token;
 
-/// Strict parse: returns a SearchFilter or an error.
+/// Strict parse against the saved searches `@name` references expand to:
+/// returns a SearchFilter or an error.
 ///
 /// Behavior:
 /// - Empty/whitespace → error (no query)
@@ -21,8 +44,9 @@ use lexer::Token;
 /// - All-operator input (`and`, `or not`) → re-interpreted as literal words
 /// - Dangling operators with real terms (`#dev and`) → error
 /// - Unmatched parens with terms (`(foo`) → error
-pub fn parse(input: &str) -> anyhow::Result<SearchFilter> {
-    let tokens = lexer::tokenize(input);
+/// - `@name` → expanded to the saved search; unknown names and cycles → error
+pub fn parse_with(input: &str, saved: &SavedSearches) -> anyhow::Result<SearchFilter> {
+    let tokens = saved::expand(lexer::tokenize(input), saved)?;
 
     // Remove empty paren groups (only normalization applied in strict mode)
     let tokens = normalize::remove_empty_parens(tokens);
@@ -39,6 +63,7 @@ pub fn parse(input: &str) -> anyhow::Result<SearchFilter> {
                 | Token::QuotedString(_)
                 | Token::PrefixedWord(_, _)
                 | Token::PrefixedQuoted(_, _)
+                | Token::Fuzzy(_, _)
         )
     });
 
@@ -61,20 +86,36 @@ pub fn parse(input: &str) -> anyhow::Result<SearchFilter> {
     parser::parse_strict(tokens)
 }
 
+/// `parse_with` without saved searches.
+#[cfg(test)]
+pub fn parse(input: &str) -> anyhow::Result<SearchFilter> {
+    parse_with(input, &SavedSearches::new())
+}
+
 /// Tolerant parse: normalizes away all malformed constructs.
 /// Returns `Ok(None)` for empty/whitespace-only/operator-only input (match all).
 /// Used by workspace query validation/merge where tolerance is required.
-pub fn parse_tolerant(input: &str) -> anyhow::Result<Option<SearchFilter>> {
-    let tokens = lexer::tokenize(input);
+pub fn parse_tolerant_with(
+    input: &str,
+    saved: &SavedSearches,
+) -> anyhow::Result<Option<SearchFilter>> {
+    let tokens = saved::expand(lexer::tokenize(input), saved)?;
     let tokens = normalize::normalize(tokens);
     parser::parse(tokens)
 }
 
+/// `parse_tolerant_with` without saved searches.
+#[cfg(test)]
+pub fn parse_tolerant(input: &str) -> anyhow::Result<Option<SearchFilter>> {
+    parse_tolerant_with(input, &SavedSearches::new())
+}
+
 /// Convenience: strict parse + evaluate in one call.
 /// Returns error if query is invalid.
+#[cfg(test)]
 pub fn matches(query: &str, bookmark: &Bookmark) -> anyhow::Result<bool> {
     let filter = parse(query)?;
-    Ok(eval(&filter, bookmark))
+    Ok(eval(&filter, bookmark, &MetadataSources::new()))
 }
 
 #[cfg(test)]
@@ -10,11 +10,26 @@ pub enum FieldTarget {
     Description,
     Url,
     Id,
+    /// Number of tags on the bookmark, e.g. `tags:0`, `tags:>3`
+    TagCount,
+    /// Field is set: `has:image`, `has:description`
+    Has,
+    /// Field is empty or unset: `missing:icon`, `missing:tags`
+    Missing,
+    /// URL host or any subdomain of it: `domain:github.com`, `site:github.com`
+    Domain,
+    /// URL path prefix: `path:/docs`
+    Path,
+    /// Fetcher that supplied a field in the latest metadata report:
+    /// `source:ddg`, `source:title=ddg`
+    Source,
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum SearchFilter {
     Term(FieldTarget, String),
+    /// Typo-tolerant term (`~kubernets`, `.~postgress`)
+    Fuzzy(FieldTarget, String),
     And(Box<SearchFilter>, Box<SearchFilter>),
     Or(Box<SearchFilter>, Box<SearchFilter>),
     Not(Box<SearchFilter>),
@@ -112,7 +127,8 @@ impl Parser {
                 Token::Word(_)
                 | Token::QuotedString(_)
                 | Token::PrefixedWord(_, _)
-                | Token::PrefixedQuoted(_, _),
+                | Token::PrefixedQuoted(_, _)
+                | Token::Fuzzy(_, _),
             ) => {
                 let tok = self.advance().unwrap();
                 Ok(token_to_term(tok))
@@ -130,6 +146,12 @@ fn prefix_to_field(p: Prefix) -> FieldTarget {
         Prefix::Description => FieldTarget::Description,
         Prefix::Url => FieldTarget::Url,
         Prefix::Id => FieldTarget::Id,
+        Prefix::TagCount => FieldTarget::TagCount,
+        Prefix::Has => FieldTarget::Has,
+        Prefix::Missing => FieldTarget::Missing,
+        Prefix::Domain => FieldTarget::Domain,
+        Prefix::Path => FieldTarget::Path,
+        Prefix::Source => FieldTarget::Source,
     }
 }
 
@@ -139,6 +161,9 @@ fn token_to_term(tok: Token) -> SearchFilter {
         Token::QuotedString(s) => SearchFilter::Term(FieldTarget::All, s),
         Token::PrefixedWord(p, w) => SearchFilter::Term(prefix_to_field(p), w),
         Token::PrefixedQuoted(p, s) => SearchFilter::Term(prefix_to_field(p), s),
+        Token::Fuzzy(p, s) => {
+            SearchFilter::Fuzzy(p.map(prefix_to_field).unwrap_or(FieldTarget::All), s)
+        }
         _ => unreachable!(),
     }
 }
@@ -0,0 +1,51 @@
+use super::parser::{FieldTarget, SearchFilter};
+use crate::bookmarks::Bookmark;
+use crate::semantic::lexical::{score_bm25, LexicalDoc, TermScope};
+
+/// Order `bookmarks` by BM25F relevance to the text terms of `filter`, best
+/// first. Ties, and queries without text terms, keep the current order.
+pub fn rank_by_relevance(filter: &SearchFilter, bookmarks: &mut Vec<Bookmark>) {
+    let mut terms = Vec::new();
+    scoring_terms(filter, &mut terms);
+    if terms.is_empty() {
+        return;
+    }
+
+    let docs: Vec<LexicalDoc> = bookmarks
+        .iter()
+        .map(|b| LexicalDoc {
+            title: &b.title,
+            description: &b.description,
+            url: &b.url,
+            tags: &b.tags,
+        })
+        .collect();
+    let scores = score_bm25(&terms, &docs);
+
+    let mut ranked: Vec<(f32, Bookmark)> = scores.into_iter().zip(bookmarks.drain(..)).collect();
+    ranked.sort_by(|a, b| b.0.total_cmp(&a.0));
+    bookmarks.extend(ranked.into_iter().map(|(_, b)| b));
+}
+
+/// Text terms that count towards relevance. Terms under `not` and
+/// structural terms (`=id`, `has:`, `domain:`, ...) don't.
+fn scoring_terms(filter: &SearchFilter, out: &mut Vec<(TermScope, String)>) {
+    match filter {
+        SearchFilter::Term(field, value) | SearchFilter::Fuzzy(field, value) => {
+            let scope = match field {
+                FieldTarget::All => TermScope::All,
+                FieldTarget::Title => TermScope::Title,
+                FieldTarget::Tag => TermScope::Tags,
+                FieldTarget::Description => TermScope::Description,
+                FieldTarget::Url => TermScope::Url,
+                _ => return,
+            };
+            out.push((scope, value.clone()));
+        }
+        SearchFilter::And(a, b) | SearchFilter::Or(a, b) => {
+            scoring_terms(a, out);
+            scoring_terms(b, out);
+        }
+        SearchFilter::Not(_) => {}
+    }
+}
@@ -0,0 +1,62 @@
+use std::collections::HashMap;
+
+use anyhow::bail;
+
+use super::lexer::{self, Token};
+
+/// The queries `@name` references expand to, keyed by lowercased name.
+/// `SavedSearchStore` keeps the persisted set.
+pub type SavedSearches = HashMap<String, String>;
+
+/// Characters allowed in a saved search name (`@reading-list`, `@todo_2`).
+pub fn is_name_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '-' || c == '_'
+}
+
+/// Replace every `@name` token with the tokens of the saved query, wrapped in
+/// parentheses so it binds as a single term.
+pub(super) fn expand(
+    tokens: Vec<Token>,
+    saved: &SavedSearches,
+) -> anyhow::Result<Vec<Token>> {
+    expand_inner(tokens, saved, &mut Vec::new())
+}
+
+fn expand_inner(
+    tokens: Vec<Token>,
+    saved: &SavedSearches,
+    stack: &mut Vec<String>,
+) -> anyhow::Result<Vec<Token>> {
+    let mut out = Vec::with_capacity(tokens.len());
+
+    for token in tokens {
+        let Token::Macro(name) = token else {
+            out.push(token);
+            continue;
+        };
+        let key = name.to_lowercase();
+
+        if let Some(start) = stack.iter().position(|n| *n == key) {
+            let cycle: Vec<String> = stack[start..]
+                .iter()
+                .chain(std::iter::once(&key))
+                .map(|n| format!("@{}", n))
+                .collect();
+            bail!("saved search cycle: {}", cycle.join(" -> "));
+        }
+
+        let Some(query) = saved.get(&key) else {
+            bail!("unknown saved search '@{}'", name);
+        };
+
+        stack.push(key);
+        let inner = expand_inner(lexer::tokenize(query), saved, stack)?;
+        stack.pop();
+
+        out.push(Token::LParen);
+        out.extend(inner);
+        out.push(Token::RParen);
+    }
+
+    Ok(out)
+}
@@ -0,0 +1,20 @@
+use std::collections::HashMap;
+
+/// The fetcher that supplied each field in a bookmark's latest metadata
+/// report, keyed by bookmark id and then lowercased field name. Owned by
+/// `metadata::reports::ReportStore`.
+pub type MetadataSources = HashMap<u64, HashMap<String, String>>;
+
+/// `source:ddg` matches bookmarks with any field supplied by the fetcher,
+/// `source:title=ddg` only those whose title came from it.
+pub(super) fn source_matches(term: &str, id: u64, sources: &MetadataSources) -> bool {
+    let term = term.trim().to_lowercase();
+    let Some(fields) = sources.get(&id) else {
+        return false;
+    };
+
+    match term.split_once('=') {
+        Some((field, fetcher)) => fields.get(field.trim()).is_some_and(|f| f == fetcher.trim()),
+        None => fields.values().any(|f| *f == term),
+    }
+}
@@ -0,0 +1,116 @@
+/// Tag pattern used by `#` query terms and the legacy `SearchQuery.tags` filter.
+///
+/// Syntax:
+/// - `dev` — `dev` and every descendant (`dev/rust`, `dev/rust/async`)
+/// - `=dev` — only `dev` itself, no descendants
+/// - `*` inside a segment matches any characters within that segment
+///   (`lang/*`, `*/rust`, `ru*`)
+/// - `**` matches zero or more whole segments (`**/async`)
+///
+/// Matching is case-insensitive.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TagPattern {
+    exact: bool,
+    segments: Vec<String>,
+}
+
+impl TagPattern {
+    pub fn parse(pattern: &str) -> Self {
+        let (exact, body) = match pattern.strip_prefix('=') {
+            Some(rest) => (true, rest),
+            None => (false, pattern),
+        };
+
+        Self {
+            exact,
+            segments: body.to_lowercase().split('/').map(String::from).collect(),
+        }
+    }
+
+    pub fn matches(&self, tag: &str) -> bool {
+        let tag_lower = tag.to_lowercase();
+        let tag_segments: Vec<&str> = tag_lower.split('/').collect();
+
+        if self.exact {
+            return match_segments(&self.segments, &tag_segments);
+        }
+
+        // Hierarchical: the pattern may match the tag itself or any of its ancestors.
+        (1..=tag_segments.len()).any(|depth| match_segments(&self.segments, &tag_segments[..depth]))
+    }
+
+    /// Segments without globs. Any tag the pattern matches has each of them
+    /// as one of its own segments.
+    pub fn literal_segments(&self) -> impl Iterator<Item = &str> {
+        self.segments
+            .iter()
+            .filter(|s| !s.contains('*'))
+            .map(String::as_str)
+    }
+}
+
+fn match_segments(pattern: &[String], tag: &[&str]) -> bool {
+    match pattern.split_first() {
+        None => tag.is_empty(),
+        Some((head, rest)) if head == "**" => {
+            (0..=tag.len()).any(|skip| match_segments(rest, &tag[skip..]))
+        }
+        Some((head, rest)) => match tag.split_first() {
+            Some((segment, tag_rest)) => {
+                glob_match(head, segment) && match_segments(rest, tag_rest)
+            }
+            None => false,
+        },
+    }
+}
+
+/// Single-segment glob where `*` matches any run of characters.
+fn glob_match(pattern: &str, input: &str) -> bool {
+    if !pattern.contains('*') {
+        return pattern == input;
+    }
+
+    let parts: Vec<&str> = pattern.split('*').collect();
+    let first = parts[0];
+    let last = parts[parts.len() - 1];
+
+    if !input.starts_with(first) {
+        return false;
+    }
+    let mut rest = &input[first.len()..];
+
+    for part in &parts[1..parts.len() - 1] {
+        match rest.find(part) {
+            Some(pos) => rest = &rest[pos + part.len()..],
+            None => return false,
+        }
+    }
+
+    rest.len() >= last.len() && rest.ends_with(last)
+}
+
+/// Tag count comparison used by `tags:` terms: `tags:0`, `tags:>3`, `tags:<=2`.
+/// Returns `None` for malformed input.
+pub fn tag_count_matches(term: &str, count: usize) -> Option<bool> {
+    let term = term.trim();
+    let (op, value) = if let Some(v) = term.strip_prefix(">=") {
+        (">=", v)
+    } else if let Some(v) = term.strip_prefix("<=") {
+        ("<=", v)
+    } else if let Some(v) = term.strip_prefix('>') {
+        (">", v)
+    } else if let Some(v) = term.strip_prefix('<') {
+        ("<", v)
+    } else {
+        ("=", term)
+    };
+
+    let n = value.parse::<usize>().ok()?;
+    Some(match op {
+        ">=" => count >= n,
+        "<=" => count <= n,
+        ">" => count > n,
+        "<" => count < n,
+        _ => count == n,
+    })
+}
@@ -225,7 +225,7 @@ fn test_tolerant_workspace_merge_empty_keyword() {
 fn test_parse_simple_word() {
     let f = parse("video").unwrap();
     let bm = make_bookmark("My Video", "", "", &[]);
-    assert!(eval(&f, &bm));
+    assert!(eval(&f, &bm, &Default::default()));
 }
 
 #[test]
@@ -464,6 +464,474 @@ fn test_normalize_balance_parens_indirect() {
     let filter = result.unwrap();
     let bm_match = make_bookmark("", "", "", &["dev"]);
     let bm_miss = make_bookmark("", "", "", &["other"]);
-    assert!(eval(&filter, &bm_match));
-    assert!(!eval(&filter, &bm_miss));
+    assert!(eval(&filter, &bm_match, &Default::default()));
+    assert!(!eval(&filter, &bm_miss, &Default::default()));
+}
+
+// === Tag patterns: exact, glob, depth, count ===
+
+#[test]
+fn test_tag_exact_excludes_descendants() {
+    let parent = make_bookmark("", "", "", &["dev"]);
+    let child = make_bookmark("", "", "", &["dev/rust"]);
+    assert!(matches("#=dev", &parent).unwrap());
+    assert!(!matches("#=dev", &child).unwrap());
+    assert!(matches("#dev", &child).unwrap());
+}
+
+#[test]
+fn test_tag_exact_parses_as_tag_term() {
+    let f = parse("#=dev").unwrap();
+    assert_eq!(f, SearchFilter::Term(FieldTarget::Tag, "=dev".into()));
+}
+
+#[test]
+fn test_tag_glob_leading_segment() {
+    assert!(matches("#*/rust", &make_bookmark("", "", "", &["lang/rust"])).unwrap());
+    assert!(matches("#*/rust", &make_bookmark("", "", "", &["lang/rust/async"])).unwrap());
+    assert!(!matches("#*/rust", &make_bookmark("", "", "", &["rust"])).unwrap());
+    assert!(!matches("#*/rust", &make_bookmark("", "", "", &["lang/rustacean"])).unwrap());
+}
+
+#[test]
+fn test_tag_glob_trailing_segment() {
+    assert!(matches("#lang/*", &make_bookmark("", "", "", &["lang/go"])).unwrap());
+    assert!(!matches("#lang/*", &make_bookmark("", "", "", &["lang"])).unwrap());
+}
+
+#[test]
+fn test_tag_glob_within_segment() {
+    assert!(matches("#lang/ru*", &make_bookmark("", "", "", &["lang/rust"])).unwrap());
+    assert!(matches("#*script", &make_bookmark("", "", "", &["typescript"])).unwrap());
+    assert!(!matches("#lang/ru*", &make_bookmark("", "", "", &["lang/go"])).unwrap());
+}
+
+#[test]
+fn test_tag_exact_depth() {
+    // `=*/*` — exactly two levels deep
+    assert!(matches("#=*/*", &make_bookmark("", "", "", &["lang/rust"])).unwrap());
+    assert!(!matches("#=*/*", &make_bookmark("", "", "", &["lang"])).unwrap());
+    assert!(!matches("#=*/*", &make_bookmark("", "", "", &["lang/rust/async"])).unwrap());
+}
+
+#[test]
+fn test_tag_double_star_any_depth() {
+    assert!(matches("#**/async", &make_bookmark("", "", "", &["async"])).unwrap());
+    assert!(matches("#**/async", &make_bookmark("", "", "", &["lang/rust/async"])).unwrap());
+    assert!(!matches("#=**/async", &make_bookmark("", "", "", &["lang/async/tokio"])).unwrap());
+}
+
+#[test]
+fn test_tag_count_operator() {
+    let untagged = make_bookmark("", "", "", &[]);
+    let many = make_bookmark("", "", "", &["a", "b", "c", "d"]);
+    assert!(matches("tags:0", &untagged).unwrap());
+    assert!(!matches("tags:0", &many).unwrap());
+    assert!(matches("tags:>3", &many).unwrap());
+    assert!(!matches("tags:>4", &many).unwrap());
+    assert!(matches("tags:<=4", &many).unwrap());
+    assert!(!matches("tags:abc", &many).unwrap());
+}
+
+#[test]
+fn test_tag_count_parses_as_keyword() {
+    let f = parse("tags:>2").unwrap();
+    assert_eq!(f, SearchFilter::Term(FieldTarget::TagCount, ">2".into()));
+}
+
+#[test]
+fn test_unknown_keyword_stays_literal() {
+    let f = parse("foo:bar").unwrap();
+    assert_eq!(f, SearchFilter::Term(FieldTarget::All, "foo:bar".into()));
+    let f = parse("tags:").unwrap();
+    assert_eq!(f, SearchFilter::Term(FieldTarget::All, "tags:".into()));
+}
+
+#[test]
+fn test_fuzzy_parses_as_fuzzy_term() {
+    let f = parse("~kubernets").unwrap();
+    assert_eq!(f, SearchFilter::Fuzzy(FieldTarget::All, "kubernets".into()));
+    let f = parse(".~postgress").unwrap();
+    assert_eq!(f, SearchFilter::Fuzzy(FieldTarget::Title, "postgress".into()));
+    let f = parse("~\"machin lerning\"").unwrap();
+    assert_eq!(f, SearchFilter::Fuzzy(FieldTarget::All, "machin lerning".into()));
+}
+
+#[test]
+fn test_fuzzy_escaped_and_bare_tilde_stay_literal() {
+    let f = parse("\\~kubernets").unwrap();
+    assert_eq!(f, SearchFilter::Term(FieldTarget::All, "~kubernets".into()));
+    let f = parse("~").unwrap();
+    assert_eq!(f, SearchFilter::Term(FieldTarget::All, "~".into()));
+}
+
+#[test]
+fn test_fuzzy_tolerates_typos() {
+    let bm = make_bookmark("Kubernetes in Action", "", "https://example.com", &[]);
+    assert!(matches("~kubernets", &bm).unwrap());
+    assert!(matches("~kuberntes", &bm).unwrap());
+    assert!(!matches("kubernets", &bm).unwrap());
+    assert!(!matches("~kbrnts", &bm).unwrap());
+}
+
+#[test]
+fn test_fuzzy_field_prefix() {
+    let bm = make_bookmark("PostgreSQL tuning", "notes on postgres", "", &["database"]);
+    assert!(matches(".~postgresq", &bm).unwrap());
+    assert!(matches(">~postgress", &bm).unwrap());
+    assert!(matches("#~databse", &bm).unwrap());
+    assert!(!matches(".~databse", &bm).unwrap());
+}
+
+#[test]
+fn test_fuzzy_folds_diacritics() {
+    let bm = make_bookmark("Mon résumé", "", "", &[]);
+    assert!(matches("~resume", &bm).unwrap());
+    assert!(!matches("resume", &bm).unwrap());
+}
+
+#[test]
+fn test_fuzzy_short_words_stay_exact() {
+    let bm = make_bookmark("The cat", "", "", &[]);
+    assert!(matches("~cat", &bm).unwrap());
+    assert!(!matches("~car", &bm).unwrap());
+}
+
+#[test]
+fn test_fuzzify_keeps_tags_and_negations_exact() {
+    let f = crate::search_query::fuzzify(&parse("kubernets #dev not java").unwrap());
+    assert_eq!(
+        f,
+        SearchFilter::And(
+            Box::new(SearchFilter::And(
+                Box::new(SearchFilter::Fuzzy(FieldTarget::All, "kubernets".into())),
+                Box::new(SearchFilter::Term(FieldTarget::Tag, "dev".into())),
+            )),
+            Box::new(SearchFilter::Not(Box::new(SearchFilter::Term(
+                FieldTarget::All,
+                "java".into()
+            )))),
+        )
+    );
+}
+
+#[test]
+fn test_match_quality_prefers_closer_matches() {
+    let f = parse("~kubernets").unwrap();
+    let exact = make_bookmark("kubernets notes", "", "", &[]);
+    let close = make_bookmark("kubernetes notes", "", "", &[]);
+    assert!(
+        crate::search_query::match_quality(&f, &exact, &Default::default())
+            > crate::search_query::match_quality(&f, &close, &Default::default())
+    );
+}
+
+#[test]
+fn test_has_missing_parse_as_keywords() {
+    let f = parse("has:image").unwrap();
+    assert_eq!(f, SearchFilter::Term(FieldTarget::Has, "image".into()));
+    let f = parse("missing:icon").unwrap();
+    assert_eq!(f, SearchFilter::Term(FieldTarget::Missing, "icon".into()));
+}
+
+#[test]
+fn test_has_missing_image_and_icon() {
+    let mut bm = make_bookmark("Title", "", "https://example.com", &[]);
+    assert!(matches("missing:image", &bm).unwrap());
+    assert!(matches("missing:icon", &bm).unwrap());
+    assert!(!matches("has:image", &bm).unwrap());
+
+    bm.image_id = Some("abc.png".into());
+    bm.icon_id = Some("def.png".into());
+    assert!(matches("has:image has:icon", &bm).unwrap());
+    assert!(!matches("missing:image", &bm).unwrap());
+}
+
+#[test]
+fn test_has_missing_text_fields() {
+    let bm = make_bookmark("Title", "  ", "https://example.com", &["dev"]);
+    assert!(matches("missing:description", &bm).unwrap());
+    assert!(matches("has:title has:tags", &bm).unwrap());
+    assert!(!matches("missing:tags", &bm).unwrap());
+
+    let untagged = make_bookmark("", "desc", "https://example.com", &[]);
+    assert!(matches("missing:tags missing:title", &untagged).unwrap());
+}
+
+#[test]
+fn test_missing_title_when_title_is_url() {
+    let bm = make_bookmark("https://example.com", "", "https://example.com", &[]);
+    assert!(matches("missing:title", &bm).unwrap());
+    assert!(!matches("has:title", &bm).unwrap());
+}
+
+#[test]
+fn test_has_missing_unknown_field_matches_nothing() {
+    let bm = make_bookmark("", "", "", &[]);
+    assert!(!matches("has:colour", &bm).unwrap());
+    assert!(!matches("missing:colour", &bm).unwrap());
+}
+
+#[test]
+fn test_domain_and_site_parse_as_keywords() {
+    let f = parse("domain:github.com").unwrap();
+    assert_eq!(f, SearchFilter::Term(FieldTarget::Domain, "github.com".into()));
+    let f = parse("site:github.com").unwrap();
+    assert_eq!(f, SearchFilter::Term(FieldTarget::Domain, "github.com".into()));
+    let f = parse("path:/docs").unwrap();
+    assert_eq!(f, SearchFilter::Term(FieldTarget::Path, "/docs".into()));
+}
+
+#[test]
+fn test_domain_matches_host_and_subdomains() {
+    let bm = |url: &str| make_bookmark("", "", url, &[]);
+    assert!(matches("domain:github.com", &bm("https://github.com/rust-lang")).unwrap());
+    assert!(matches("domain:github.com", &bm("https://www.github.com/")).unwrap());
+    assert!(matches("domain:github.com", &bm("https://gist.github.com/x")).unwrap());
+    assert!(matches("site:www.github.com", &bm("https://github.com/")).unwrap());
+    assert!(matches("domain:GitHub.com", &bm("https://GITHUB.COM/")).unwrap());
+    assert!(matches("domain:co.uk", &bm("https://news.bbc.co.uk/")).unwrap());
+    assert!(!matches("domain:co.uk", &bm("https://example.uk/")).unwrap());
+}
+
+#[test]
+fn test_domain_rejects_lookalikes() {
+    let bm = |url: &str| make_bookmark("", "", url, &[]);
+    assert!(!matches("domain:github.com", &bm("https://notgithub.com/")).unwrap());
+    assert!(!matches("domain:github.com", &bm("https://github.com.evil/")).unwrap());
+    assert!(!matches("domain:github.com", &bm("https://example.com/?ref=github.com")).unwrap());
+    // the substring operator still matches these
+    assert!(matches(":github.com", &bm("https://example.com/?ref=github.com")).unwrap());
+}
+
+#[test]
+fn test_path_prefix() {
+    let bm = make_bookmark("", "", "https://docs.rs/axum/latest/axum/", &[]);
+    assert!(matches("path:/axum", &bm).unwrap());
+    assert!(matches("path:axum/latest", &bm).unwrap());
+    assert!(!matches("path:/latest", &bm).unwrap());
+    assert!(matches("domain:docs.rs path:/axum", &bm).unwrap());
+}
+
+#[test]
+fn test_source_matches_latest_report_winners() {
+    let mut bm = make_bookmark("Some Title", "", "https://example.com/", &[]);
+    bm.id = 7;
+    let sources: super::MetadataSources =
+        [(7, [("title".to_string(), "ddg".to_string()), ("icon".to_string(), "plain".to_string())].into())].into();
+    let source_matches = |query: &str, bm: &Bookmark| eval(&parse(query).unwrap(), bm, &sources);
+
+    assert_eq!(parse("source:title=ddg").unwrap(), SearchFilter::Term(FieldTarget::Source, "title=ddg".into()));
+    assert!(source_matches("source:ddg", &bm));
+    assert!(source_matches("source:title=DDG", &bm));
+    assert!(source_matches("source:icon=plain", &bm));
+    assert!(!source_matches("source:icon=ddg", &bm));
+    assert!(!source_matches("source:oembed", &bm));
+    assert!(!matches("source:ddg", &bm).unwrap());
+
+    bm.id = 8;
+    assert!(!source_matches("source:ddg", &bm));
+}
+
+#[test]
+fn test_domain_counts() {
+    let bookmarks = vec![
+        make_bookmark("", "", "https://github.com/a", &[]),
+        make_bookmark("", "", "https://www.github.com/b", &[]),
+        make_bookmark("", "", "https://docs.rs/c", &[]),
+        make_bookmark("", "", "not a url", &[]),
+    ];
+    assert_eq!(
+        super::domain_counts(&bookmarks),
+        vec![("github.com".to_string(), 2), ("docs.rs".to_string(), 1)]
+    );
+}
+
+#[test]
+fn test_domain_counts_group_by_registrable_domain() {
+    let bookmarks = vec![
+        make_bookmark("", "", "https://news.bbc.co.uk/a", &[]),
+        make_bookmark("", "", "https://www.bbc.co.uk/b", &[]),
+        make_bookmark("", "", "https://gist.github.com/c", &[]),
+        make_bookmark("", "", "https://rust-lang.github.io/d", &[]),
+        make_bookmark("", "", "http://127.0.0.1:8080/e", &[]),
+    ];
+    assert_eq!(
+        super::domain_counts(&bookmarks),
+        vec![
+            ("bbc.co.uk".to_string(), 2),
+            ("127.0.0.1".to_string(), 1),
+            ("github.com".to_string(), 1),
+            ("rust-lang.github.io".to_string(), 1),
+        ]
+    );
+}
+
+#[test]
+fn test_facets_aggregate_tag_paths() {
+    let mut with_image = make_bookmark("Rust", "", "https://github.com/a", &["dev/rust", "dev/go"]);
+    with_image.image_id = Some("img".to_string());
+    let bookmarks = vec![
+        with_image,
+        make_bookmark("", "notes", "https://docs.rs/b", &["dev", "reading"]),
+        make_bookmark("Go", "", "https://github.com/c", &["dev/go/tools"]),
+    ];
+    let facets = super::facets(&bookmarks);
+
+    let tags: Vec<(&str, usize)> = facets.tags.iter().map(|f| (f.value.as_str(), f.count)).collect();
+    // `dev` counts each bookmark once, however many `dev/...` tags it has
+    assert_eq!(
+        tags,
+        vec![("dev", 3), ("dev/go", 2), ("dev/go/tools", 1), ("dev/rust", 1), ("reading", 1)]
+    );
+
+    let domains: Vec<(&str, usize)> = facets.domains.iter().map(|f| (f.value.as_str(), f.count)).collect();
+    assert_eq!(domains, vec![("github.com", 2), ("docs.rs", 1)]);
+
+    let flags: Vec<(&str, usize)> = facets.flags.iter().map(|f| (f.value.as_str(), f.count)).collect();
+    assert_eq!(
+        flags,
+        vec![("title", 2), ("description", 1), ("tags", 3), ("image", 1), ("icon", 0)]
+    );
+}
+
+// === Saved search references ===
+
+fn saved(entries: &[(&str, &str)]) -> std::collections::HashMap<String, String> {
+    entries.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+}
+
+#[test]
+fn test_saved_search_expands_as_group() {
+    let s = saved(&[("reading", "#toread or #later")]);
+    let f = super::parse_with("@reading and #rust", &s).unwrap();
+    assert_eq!(f, super::parse_with("(#toread or #later) and #rust", &s).unwrap());
+
+    let bm = make_bookmark("", "", "", &["later", "rust"]);
+    assert!(eval(&f, &bm, &Default::default()));
+    let bm = make_bookmark("", "", "", &["later"]);
+    assert!(!eval(&f, &bm, &Default::default()));
+}
+
+#[test]
+fn test_saved_search_nested_and_case_insensitive() {
+    let s = saved(&[("base", "#rust"), ("async-rust", "@base .async")]);
+    let f = super::parse_with("@Async-Rust", &s).unwrap();
+    assert!(eval(&f, &make_bookmark("Async book", "", "", &["rust"]), &Default::default()));
+    assert!(!eval(&f, &make_bookmark("Async book", "", "", &["go"]), &Default::default()));
+}
+
+#[test]
+fn test_saved_search_unknown_name_is_error() {
+    let err = super::parse_with("@nope and #rust", &saved(&[])).unwrap_err();
+    assert!(err.to_string().contains("unknown saved search '@nope'"), "{err}");
+}
+
+#[test]
+fn test_saved_search_cycle_is_error() {
+    let s = saved(&[("a", "#x or @b"), ("b", "@a")]);
+    let err = super::parse_with("@a", &s).unwrap_err();
+    assert_eq!(err.to_string(), "saved search cycle: @a -> @b -> @a");
+
+    let s = saved(&[("self", "@self")]);
+    assert!(super::parse_with("@self", &s).is_err());
+}
+
+#[test]
+fn test_at_sign_literals() {
+    let s = saved(&[]);
+    // escaped, mid-word and bare `@` are plain text
+    let f = super::parse_with("\\@home", &s).unwrap();
+    assert_eq!(f, SearchFilter::Term(FieldTarget::All, "@home".into()));
+    let f = super::parse_with("me@example.com", &s).unwrap();
+    assert_eq!(f, SearchFilter::Term(FieldTarget::All, "me@example.com".into()));
+    let f = super::parse_with("@", &s).unwrap();
+    assert_eq!(f, SearchFilter::Term(FieldTarget::All, "@".into()));
+    let f = super::parse_with("@foo.bar", &s).unwrap();
+    assert_eq!(f, SearchFilter::Term(FieldTarget::All, "@foo.bar".into()));
+}
+
+// === Explain ===
+
+#[test]
+fn test_explain_normalizes_and_reports_strict_error() {
+    let e = super::explain("#dev and", &Default::default(), &Default::default(), None).unwrap();
+    assert_eq!(e.normalized, "#dev");
+    assert!(e.strict_error.is_some());
+    assert_eq!(
+        e.ast,
+        Some(super::explain::QueryNode::Term {
+            field: "tag".into(),
+            value: "dev".into(),
+            fuzzy: false
+        })
+    );
+
+    let e = super::explain("   ", &Default::default(), &Default::default(), None).unwrap();
+    assert_eq!(e.ast, None);
+    assert_eq!(e.normalized, "");
+}
+
+#[test]
+fn test_explain_normalized_round_trips() {
+    for q in [
+        "(a or b) c",
+        "not (a b) or .x",
+        "\"two words\" #dev/rust",
+        "~kubernets >\"a b\" domain:github.com",
+        "\"and\" \"#literal\"",
+    ] {
+        let e = super::explain(q, &Default::default(), &Default::default(), None).unwrap();
+        assert_eq!(
+            parse(&e.normalized).unwrap(),
+            parse_tolerant(q).unwrap().unwrap(),
+            "{q} -> {}",
+            e.normalized
+        );
+    }
+    assert_eq!(super::explain("(a or b) c", &Default::default(), &Default::default(), None).unwrap().normalized, "(a or b) and c");
+    assert_eq!(super::explain("not (a b)", &Default::default(), &Default::default(), None).unwrap().normalized, "not (a and b)");
+}
+
+#[test]
+fn test_explain_bookmark_terms_and_highlights() {
+    let mut bm = make_bookmark("Rust and RUST", "", "https://www.github.com/rust-lang", &["lang/rust"]);
+    bm.id = 9;
+    let e = super::explain("rust domain:github.com not python", &Default::default(), &Default::default(), Some(&bm)).unwrap();
+    let b = e.bookmark.unwrap();
+    assert_eq!(b.id, 9);
+    assert!(b.matched);
+
+    assert_eq!(b.terms[0].fields, vec!["title", "url", "tags"]);
+    assert_eq!(b.terms[1].fields, vec!["url"]);
+    assert!(b.terms[2].negated && !b.terms[2].matched);
+
+    let spans: Vec<(&str, Option<usize>, usize, usize)> = b
+        .highlights
+        .iter()
+        .map(|h| (h.field.as_str(), h.tag, h.start, h.end))
+        .collect();
+    assert_eq!(
+        spans,
+        vec![
+            ("tags", Some(0), 5, 9),
+            ("title", None, 0, 4),
+            ("title", None, 9, 13),
+            ("url", None, 12, 22),
+            ("url", None, 23, 27),
+        ]
+    );
+}
+
+#[test]
+fn test_explain_fuzzy_and_negated_matches() {
+    let bm = make_bookmark("Kubernetes in action", "", "", &[]);
+    let e = super::explain("~kubernets not action", &Default::default(), &Default::default(), Some(&bm)).unwrap();
+    let b = e.bookmark.unwrap();
+    assert!(!b.matched);
+    assert!(b.terms[0].fuzzy && b.terms[0].quality.is_some());
+    assert!(b.terms[1].negated && b.terms[1].matched);
+    // only the fuzzy word is highlighted, not the negated `action`
+    assert_eq!(b.highlights.len(), 1);
+    assert_eq!((b.highlights[0].start, b.highlights[0].end), (0, 10));
 }
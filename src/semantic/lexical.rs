@@ -1,7 +1,8 @@
-//! Lexical (keyword) scoring for hybrid search.
+//! Lexical (keyword) scoring.
 //!
-//! Provides simple keyword matching against bookmark content.
-//! Used alongside semantic search for RRF fusion.
+//! `score_lexical` provides simple keyword matching against bookmark content,
+//! used alongside semantic search for RRF fusion. `score_bm25` is a BM25F
+//! scorer used to rank plain query results by relevance.
 //!
 //! Length normalization: Description matches are weighted inversely to description
 //! length to prevent long descriptions from having unfair advantage due to more
@@ -148,6 +149,125 @@ fn count_matches(
     (matched_terms, total_hits)
 }
 
+/// Bookmark fields a BM25 query term is scored against.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TermScope {
+    All,
+    Title,
+    Tags,
+    Description,
+    Url,
+}
+
+impl TermScope {
+    /// Whether the scope covers the field at `FIELD_WEIGHTS[field]`.
+    fn covers(self, field: usize) -> bool {
+        match self {
+            TermScope::All => true,
+            TermScope::Title => field == 0,
+            TermScope::Tags => field == 1,
+            TermScope::Description => field == 2,
+            TermScope::Url => field == 3,
+        }
+    }
+}
+
+/// A bookmark as seen by `score_bm25`.
+#[derive(Debug, Clone, Copy)]
+pub struct LexicalDoc<'a> {
+    pub title: &'a str,
+    pub description: &'a str,
+    pub url: &'a str,
+    pub tags: &'a [String],
+}
+
+/// BM25F field weights, in order: title, tags, description, url.
+const FIELD_WEIGHTS: [f32; 4] = [3.0, 2.0, 1.0, 0.5];
+
+/// Term frequency saturation.
+const BM25_K1: f32 = 1.2;
+
+/// Strength of field length normalization.
+const BM25_B: f32 = 0.75;
+
+/// BM25F relevance of each document for the query `terms`, in input order.
+///
+/// Each term is split like a query (`tokenize`) and counts wherever a word of
+/// a covered field contains it, matching the substring semantics of search.
+/// Field frequencies are length-normalized and weighted by `FIELD_WEIGHTS`
+/// before saturation. Document frequencies are taken over `docs`, so scores
+/// rank a result set against itself.
+pub fn score_bm25(terms: &[(TermScope, String)], docs: &[LexicalDoc]) -> Vec<f32> {
+    let terms: Vec<(TermScope, String)> = terms
+        .iter()
+        .flat_map(|(scope, text)| tokenize(text).into_iter().map(move |word| (*scope, word)))
+        .collect();
+
+    let mut scores = vec![0.0; docs.len()];
+    if terms.is_empty() || docs.is_empty() {
+        return scores;
+    }
+
+    let fields: Vec<[Vec<String>; 4]> = docs
+        .iter()
+        .map(|doc| {
+            [
+                words(doc.title),
+                doc.tags.iter().flat_map(|tag| words(tag)).collect(),
+                words(doc.description),
+                words(doc.url),
+            ]
+        })
+        .collect();
+
+    let n = docs.len() as f32;
+    let mut avg_len = [0.0f32; 4];
+    for (field, avg) in avg_len.iter_mut().enumerate() {
+        let total: usize = fields.iter().map(|doc| doc[field].len()).sum();
+        *avg = (total as f32 / n).max(1.0);
+    }
+
+    for (scope, term) in &terms {
+        // Weighted, length-normalized frequency of the term per document
+        let tf: Vec<f32> = fields
+            .iter()
+            .map(|doc| {
+                (0..FIELD_WEIGHTS.len())
+                    .filter(|&field| scope.covers(field))
+                    .map(|field| {
+                        let count = doc[field].iter().filter(|w| w.contains(term.as_str())).count();
+                        if count == 0 {
+                            return 0.0;
+                        }
+                        let norm = 1.0 - BM25_B + BM25_B * doc[field].len() as f32 / avg_len[field];
+                        FIELD_WEIGHTS[field] * count as f32 / norm
+                    })
+                    .sum()
+            })
+            .collect();
+
+        let df = tf.iter().filter(|&&tf| tf > 0.0).count() as f32;
+        if df == 0.0 {
+            continue;
+        }
+        let idf = (1.0 + (n - df + 0.5) / (df + 0.5)).ln();
+
+        for (score, tf) in scores.iter_mut().zip(tf) {
+            *score += idf * tf * (BM25_K1 + 1.0) / (tf + BM25_K1);
+        }
+    }
+
+    scores
+}
+
+/// Lowercased alphanumeric words of a field.
+fn words(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -357,4 +477,79 @@ mod tests {
             long_result.total_hits
         );
     }
+
+    // BM25F tests
+    fn doc<'a>(title: &'a str, description: &'a str, url: &'a str, tags: &'a [String]) -> LexicalDoc<'a> {
+        LexicalDoc {
+            title,
+            description,
+            url,
+            tags,
+        }
+    }
+
+    fn all(term: &str) -> Vec<(TermScope, String)> {
+        vec![(TermScope::All, term.to_string())]
+    }
+
+    #[test]
+    fn test_bm25_field_weights() {
+        let rust = vec!["rust".to_string()];
+        let none: Vec<String> = vec![];
+        let docs = [
+            doc("Notes", "some other notes here", "https://rust.example.com", &none),
+            doc("Notes", "all about rust here", "https://example.com", &none),
+            doc("Notes", "some other notes here", "https://example.com", &rust),
+            doc("Rust", "some other notes here", "https://example.com", &none),
+        ];
+
+        let scores = score_bm25(&all("rust"), &docs);
+        // title > tags > description > url
+        assert!(scores[3] > scores[2], "{scores:?}");
+        assert!(scores[2] > scores[1], "{scores:?}");
+        assert!(scores[1] > scores[0], "{scores:?}");
+        assert!(scores[0] > 0.0);
+    }
+
+    #[test]
+    fn test_bm25_scoped_terms_and_no_match() {
+        let none: Vec<String> = vec![];
+        let docs = [
+            doc("Rust", "", "", &none),
+            doc("Notes", "rust", "", &none),
+            doc("Cooking", "", "", &none),
+        ];
+
+        let scores = score_bm25(&[(TermScope::Description, "rust".into())], &docs);
+        assert_eq!(scores[0], 0.0);
+        assert!(scores[1] > 0.0);
+        assert_eq!(scores[2], 0.0);
+    }
+
+    #[test]
+    fn test_bm25_rare_terms_weigh_more() {
+        let none: Vec<String> = vec![];
+        let docs = [
+            doc("rust async", "", "", &none),
+            doc("rust tokio", "", "", &none),
+            doc("rust serde", "", "", &none),
+        ];
+
+        // `tokio` is rarer than `rust`, so the doc with both ranks first
+        let scores = score_bm25(&all("rust tokio"), &docs);
+        assert!(scores[1] > scores[0]);
+        assert_eq!(scores[0], scores[2]);
+    }
+
+    #[test]
+    fn test_bm25_shorter_title_ranks_higher() {
+        let none: Vec<String> = vec![];
+        let docs = [
+            doc("Rust", "", "", &none),
+            doc("A long title that happens to mention rust among others", "", "", &none),
+        ];
+
+        let scores = score_bm25(&all("rust"), &docs);
+        assert!(scores[0] > scores[1]);
+    }
 }
@@ -268,3 +268,666 @@ fn merge_metadata_with_force_overwrites_existing_fields() {
     assert_eq!(result.title, "New Title");
     assert_eq!(result.description, "New Desc");
 }
+
+// --- rule actions ---
+
+fn url_rule(url: &str, action: crate::rules::Action) -> crate::rules::Rule {
+    crate::rules::Rule {
+        url: Some(url.to_string()),
+        title: None,
+        description: None,
+        tags: None,
+        query: None,
+        comment: None,
+        when: vec![],
+        stop: false,
+        priority: 0,
+        id: 0,
+        enabled: true,
+        compiled: Default::default(),
+        action,
+    }
+}
+
+#[test]
+fn apply_rules_removes_tags_and_rewrites_url() {
+    use crate::rules::{Action, Trigger};
+
+    let (app, _tmp) = create_app();
+    let (bmark, _) = app.create(
+        bookmarks::BookmarkCreate {
+            url: "https://old.reddit.com/r/rust?utm_source=feed".into(),
+            tags: Some(vec!["reddit".into(), "ToRead".into(), "rust".into()]),
+            ..Default::default()
+        },
+        default_add_opts(),
+    ).unwrap();
+
+    let rules = vec![
+        url_rule("reddit.com", Action::RemoveTags { tags: vec!["toread".into()] }),
+        url_rule("reddit.com", Action::RewriteUrl {
+            pattern: r"//old\.reddit\.com".into(),
+            replacement: "//reddit.com".into(),
+        }),
+        url_rule("reddit.com", Action::RewriteUrl {
+            pattern: r"\?.*$".into(),
+            replacement: "".into(),
+        }),
+    ];
+
+    let result = AppLocal::apply_rules(bmark.id, app.bmark_mgr.clone(), &rules, &Default::default(), &[Trigger::Manual])
+        .unwrap()
+        .unwrap();
+    assert_eq!(result.url, "https://reddit.com/r/rust");
+    assert_eq!(result.tags, vec!["reddit".to_string(), "rust".to_string()]);
+}
+
+#[test]
+fn apply_rules_keeps_url_taken_by_another_bookmark() {
+    use crate::rules::{Action, Trigger};
+
+    let (app, _tmp) = create_app();
+    let create = |url: &str| {
+        app.create(
+            bookmarks::BookmarkCreate {
+                url: url.into(),
+                ..Default::default()
+            },
+            default_add_opts(),
+        ).unwrap().0
+    };
+    let existing = create("https://reddit.com/r/rust");
+    let old = create("https://old.reddit.com/r/rust");
+
+    let rules = vec![
+        url_rule("reddit.com", Action::RewriteUrl {
+            pattern: r"//old\.reddit\.com".into(),
+            replacement: "//reddit.com".into(),
+        }),
+        url_rule("reddit.com", Action::UpdateBookmark {
+            title: Some("Rust".into()),
+            description: None,
+            tags: None,
+        }),
+    ];
+
+    let result = AppLocal::apply_rules(old.id, app.bmark_mgr.clone(), &rules, &Default::default(), &[Trigger::Manual])
+        .unwrap()
+        .unwrap();
+    assert_eq!(result.url, old.url);
+    assert_eq!(result.title, "Rust");
+    let dupes = app.search(bookmarks::SearchQuery {
+        url: Some(existing.url.clone()),
+        exact: true,
+        ..Default::default()
+    }).unwrap();
+    assert_eq!(dupes.len(), 1);
+}
+
+#[test]
+fn apply_rules_replaces_description() {
+    use crate::rules::{Action, Trigger};
+
+    let (app, _tmp) = create_app();
+    let (bmark, _) = app.create(
+        bookmarks::BookmarkCreate {
+            url: "https://example.com".into(),
+            description: Some("Great post - Example Blog".into()),
+            ..Default::default()
+        },
+        default_add_opts(),
+    ).unwrap();
+
+    let strip_suffix = vec![url_rule("example.com", Action::ReplaceDescription {
+        pattern: Some(r"\s*-\s*Example Blog$".into()),
+        replacement: "".into(),
+    })];
+    let result = AppLocal::apply_rules(bmark.id, app.bmark_mgr.clone(), &strip_suffix, &Default::default(), &[Trigger::Manual])
+        .unwrap()
+        .unwrap();
+    assert_eq!(result.description, "Great post");
+
+    let replace_all = vec![url_rule("example.com", Action::ReplaceDescription {
+        pattern: None,
+        replacement: "Example".into(),
+    })];
+    let result = AppLocal::apply_rules(bmark.id, app.bmark_mgr.clone(), &replace_all, &Default::default(), &[Trigger::Manual])
+        .unwrap()
+        .unwrap();
+    assert_eq!(result.description, "Example");
+}
+
+#[test]
+fn create_with_delete_rule_removes_bookmark() {
+    use crate::rules::Action;
+
+    let (app, _tmp) = create_app();
+    app.rules_config()
+        .write()
+        .unwrap()
+        .rules_mut()
+        .push(url_rule("spam.example", Action::Delete));
+
+    let created = app.create(
+        bookmarks::BookmarkCreate {
+            url: "https://spam.example/offer".into(),
+            ..Default::default()
+        },
+        default_add_opts(),
+    );
+    assert!(created.is_err());
+
+    let query = bookmarks::SearchQuery {
+        url: Some("spam.example".into()),
+        ..Default::default()
+    };
+    assert!(app.search(query).unwrap().is_empty());
+
+    // other bookmarks are untouched
+    let (kept, _) = app.create(
+        bookmarks::BookmarkCreate {
+            url: "https://example.com".into(),
+            ..Default::default()
+        },
+        default_add_opts(),
+    ).unwrap();
+    assert_eq!(kept.url, "https://example.com");
+}
+
+#[test]
+fn fetch_rules_skip_metadata_and_force_headless() {
+    use crate::rules::Action;
+
+    let bmark = bookmarks::Bookmark {
+        id: 0,
+        url: "https://spa.example/app".into(),
+        title: String::new(),
+        description: String::new(),
+        tags: vec![],
+        image_id: None,
+        icon_id: None,
+    };
+
+    let mut meta_opts = crate::metadata::MetaOptions::default();
+    let rules = vec![url_rule("spa.example", Action::ForceHeadless)];
+    assert!(AppLocal::apply_fetch_rules(&bmark, &rules, &Default::default(), &mut meta_opts));
+    assert!(meta_opts.scrape_config.unwrap().always_headless);
+
+    let mut meta_opts = crate::metadata::MetaOptions::default();
+    let rules = vec![
+        url_rule("other.example", Action::SkipMetadata),
+        url_rule("spa.example", Action::SkipMetadata),
+    ];
+    assert!(!AppLocal::apply_fetch_rules(&bmark, &rules, &Default::default(), &mut meta_opts));
+    assert!(meta_opts.scrape_config.is_none());
+}
+
+// --- rule triggers and ordering ---
+
+fn tag_rule(tag: &str) -> crate::rules::Rule {
+    url_rule("example.com", crate::rules::Action::UpdateBookmark {
+        title: None,
+        description: None,
+        tags: Some(vec![tag.to_string()]),
+    })
+}
+
+fn title_rule(title: &str, when: Vec<crate::rules::Trigger>) -> crate::rules::Rule {
+    crate::rules::Rule {
+        when,
+        ..url_rule("example.com", crate::rules::Action::UpdateBookmark {
+            title: Some(title.to_string()),
+            description: None,
+            tags: None,
+        })
+    }
+}
+
+fn create_example(app: &AppLocal) -> bookmarks::Bookmark {
+    app.create(
+        bookmarks::BookmarkCreate {
+            url: "https://example.com".into(),
+            ..Default::default()
+        },
+        crate::app::backend::AddOpts {
+            skip_rules: true,
+            ..default_add_opts()
+        },
+    ).unwrap().0
+}
+
+#[test]
+fn apply_rules_stop_halts_evaluation() {
+    use crate::rules::Trigger;
+
+    let (app, _tmp) = create_app();
+    let bmark = create_example(&app);
+
+    let rules = vec![
+        tag_rule("first"),
+        crate::rules::Rule { stop: true, ..tag_rule("second") },
+        tag_rule("third"),
+    ];
+    let result = AppLocal::apply_rules(bmark.id, app.bmark_mgr.clone(), &rules, &Default::default(), &[Trigger::OnCreate])
+        .unwrap()
+        .unwrap();
+    assert_eq!(result.tags, vec!["first".to_string(), "second".to_string()]);
+}
+
+#[test]
+fn apply_rules_runs_by_priority_then_file_order() {
+    use crate::rules::Trigger;
+
+    let (app, _tmp) = create_app();
+    let bmark = create_example(&app);
+
+    let rules = vec![
+        tag_rule("low"),
+        crate::rules::Rule { priority: -1, ..tag_rule("last") },
+        crate::rules::Rule { priority: 10, ..tag_rule("high") },
+        tag_rule("low2"),
+    ];
+    let result = AppLocal::apply_rules(bmark.id, app.bmark_mgr.clone(), &rules, &Default::default(), &[Trigger::OnCreate])
+        .unwrap()
+        .unwrap();
+    assert_eq!(result.tags, vec!["high", "low", "low2", "last"]);
+
+    // a high-priority `stop` rule shadows everything after it
+    let rules = vec![
+        tag_rule("low"),
+        crate::rules::Rule { priority: 1, stop: true, ..tag_rule("only") },
+    ];
+    let bmark = app.update(bmark.id, bookmarks::BookmarkUpdate {
+        tags: Some(vec![]),
+        ..Default::default()
+    }).unwrap();
+    let result = AppLocal::apply_rules(bmark.id, app.bmark_mgr.clone(), &rules, &Default::default(), &[Trigger::OnCreate])
+        .unwrap()
+        .unwrap();
+    assert_eq!(result.tags, vec!["only"]);
+}
+
+#[test]
+fn apply_rules_respects_when() {
+    use crate::rules::Trigger;
+
+    let (app, _tmp) = create_app();
+    let bmark = create_example(&app);
+
+    let rules = vec![
+        title_rule("created", vec![Trigger::OnCreate]),
+        title_rule("by hand", vec![Trigger::Manual]),
+    ];
+
+    let result = AppLocal::apply_rules(bmark.id, app.bmark_mgr.clone(), &rules, &Default::default(), &[Trigger::AfterMetadata])
+        .unwrap()
+        .unwrap();
+    assert_eq!(result.title, "");
+
+    let result = AppLocal::apply_rules(bmark.id, app.bmark_mgr.clone(), &rules, &Default::default(), &[Trigger::OnCreate])
+        .unwrap()
+        .unwrap();
+    assert_eq!(result.title, "created");
+
+    // applying by hand runs every rule
+    let result = AppLocal::apply_rules(bmark.id, app.bmark_mgr.clone(), &rules, &Default::default(), &[Trigger::Manual])
+        .unwrap()
+        .unwrap();
+    assert_eq!(result.title, "by hand");
+}
+
+#[test]
+fn update_runs_on_update_rules_only() {
+    use crate::rules::Trigger;
+
+    let (app, _tmp) = create_app();
+    let bmark = create_example(&app);
+    {
+        let rules_config = app.rules_config();
+        let mut rules_config = rules_config.write().unwrap();
+        rules_config.rules_mut().push(tag_rule("default"));
+        rules_config.rules_mut().push(crate::rules::Rule {
+            when: vec![Trigger::OnUpdate],
+            ..tag_rule("edited")
+        });
+    }
+
+    let result = app.update(bmark.id, bookmarks::BookmarkUpdate {
+        description: Some("changed".into()),
+        ..Default::default()
+    }).unwrap();
+    assert_eq!(result.tags, vec!["edited"]);
+}
+
+/// A forced metadata refresh overwrites title and description, then runs only
+/// the `after_metadata` rules. Values set by `on_create`-only rules are lost;
+/// rules that run after metadata (the default) set them again.
+#[test]
+fn force_overwrite_refresh_reruns_after_metadata_rules_only() {
+    use crate::rules::Trigger;
+
+    let fetched = || crate::metadata::Metadata {
+        title: Some("Fetched Title".into()),
+        ..Default::default()
+    };
+    let refresh = |app: &AppLocal, bmark: bookmarks::Bookmark, rules: &[crate::rules::Rule]| {
+        let id = bmark.id;
+        let img_config = crate::config::ImageConfig::default();
+        AppLocal::merge_metadata(
+            bmark,
+            fetched(),
+            app.storage_mgr.clone(),
+            app.bmark_mgr.clone(),
+            &img_config,
+            true,
+        ).unwrap();
+        AppLocal::apply_rules(id, app.bmark_mgr.clone(), rules, &Default::default(), &[Trigger::AfterMetadata])
+            .unwrap()
+            .unwrap()
+    };
+
+    // on_create only: the refresh wins
+    let (app, _tmp) = create_app();
+    let bmark = create_example(&app);
+    let rules = vec![title_rule("Pinned", vec![Trigger::OnCreate])];
+    let bmark = AppLocal::apply_rules(bmark.id, app.bmark_mgr.clone(), &rules, &Default::default(), &[Trigger::OnCreate])
+        .unwrap()
+        .unwrap();
+    assert_eq!(bmark.title, "Pinned");
+    assert_eq!(refresh(&app, bmark, &rules).title, "Fetched Title");
+
+    // default triggers: the rule applies again after the refresh
+    let (app, _tmp) = create_app();
+    let bmark = create_example(&app);
+    let rules = vec![title_rule("Pinned", vec![])];
+    let bmark = AppLocal::apply_rules(bmark.id, app.bmark_mgr.clone(), &rules, &Default::default(), &[Trigger::OnCreate])
+        .unwrap()
+        .unwrap();
+    assert_eq!(refresh(&app, bmark, &rules).title, "Pinned");
+}
+
+/// A fetch skipped by a rule runs no `after_metadata` rules, whether the
+/// bookmark was added synchronously or the fetch ran from the task queue.
+#[test]
+fn skipped_fetch_runs_no_after_metadata_rules_sync_or_async() {
+    use crate::rules::{Action, Trigger};
+
+    let with_rules = || {
+        let (app, tmp) = create_app();
+        *app.rules_config().write().unwrap().rules_mut() = vec![
+            url_rule("example.com", Action::SkipMetadata),
+            crate::rules::Rule { when: vec![Trigger::OnCreate], ..tag_rule("created") },
+            crate::rules::Rule { when: vec![Trigger::AfterMetadata], ..tag_rule("fetched") },
+        ];
+        (app, tmp)
+    };
+    let add = |app: &AppLocal, async_meta: bool| {
+        app.create(
+            bookmarks::BookmarkCreate {
+                url: "https://example.com".into(),
+                ..Default::default()
+            },
+            crate::app::backend::AddOpts {
+                async_meta,
+                meta_opts: Some(Default::default()),
+                ..default_add_opts()
+            },
+        ).unwrap().0
+    };
+
+    let (app, _tmp) = with_rules();
+    let sync = add(&app, false);
+
+    let (app, _tmp) = with_rules();
+    let queued = add(&app, true);
+    let status = Task::FetchMetadata {
+        bmark_id: queued.id,
+        opts: Default::default(),
+    }
+    .run(
+        app.bmark_mgr.clone(),
+        app.storage_mgr.clone(),
+        app.config(),
+        app.rules_config(),
+        &app.reports(),
+        &app.saved_searches().unwrap(),
+    );
+    assert!(matches!(status, crate::app::task_runner::Status::Done));
+    let queued = app.search(bookmarks::SearchQuery { id: Some(queued.id), ..Default::default() }).unwrap();
+
+    assert_eq!(sync.tags, vec!["created"]);
+    assert_eq!(queued[0].tags, sync.tags);
+}
+
+// --- retroactive rule application ---
+
+fn rule_apply_opts(dry_run: bool) -> crate::app::backend::RuleApplyOpts {
+    crate::app::backend::RuleApplyOpts {
+        rule: None,
+        query: None,
+        dry_run,
+    }
+}
+
+#[test]
+fn rule_apply_dry_run_reports_without_writing() {
+    let (app, _tmp) = create_app();
+    let bmark = create_example(&app);
+    app.create(
+        bookmarks::BookmarkCreate {
+            url: "https://other.org".into(),
+            ..Default::default()
+        },
+        default_add_opts(),
+    ).unwrap();
+    {
+        let rules_config = app.rules_config();
+        let mut rules_config = rules_config.write().unwrap();
+        *rules_config.rules_mut() = vec![tag_rule("example")];
+        rules_config.assign_ids();
+    }
+
+    let report = app.apply_rules_to_bookmarks(rule_apply_opts(true)).unwrap();
+    assert!(!report.applied);
+    assert_eq!(report.changes.len(), 1);
+    assert_eq!(report.changes[0].before.id, bmark.id);
+    assert_eq!(report.changes[0].after.as_ref().unwrap().tags, vec!["example".to_string()]);
+    assert_eq!(report.changes[0].rules, vec![1]);
+
+    let stored = app.search(bookmarks::SearchQuery { id: Some(bmark.id), ..Default::default() }).unwrap();
+    assert!(stored[0].tags.is_empty());
+}
+
+#[test]
+fn rule_apply_skips_unchanged_and_undo_restores() {
+    let (app, _tmp) = create_app();
+    let tagged = create_example(&app);
+    let doomed = app.create(
+        bookmarks::BookmarkCreate {
+            url: "https://doomed.example.com".into(),
+            title: Some("doomed".into()),
+            ..Default::default()
+        },
+        default_add_opts(),
+    ).unwrap().0;
+    *app.rules_config().write().unwrap().rules_mut() = vec![
+        crate::rules::Rule { stop: true, ..url_rule("doomed", crate::rules::Action::Delete) },
+        tag_rule("example"),
+    ];
+
+    let report = app.apply_rules_to_bookmarks(rule_apply_opts(false)).unwrap();
+    assert!(report.applied);
+    assert_eq!(report.changes.len(), 2);
+    assert!(app.search(bookmarks::SearchQuery { id: Some(doomed.id), ..Default::default() }).unwrap().is_empty());
+
+    // a second run has nothing left to change
+    let report = app.apply_rules_to_bookmarks(rule_apply_opts(true)).unwrap();
+    assert!(report.changes.is_empty());
+
+    assert_eq!(app.undo_rules_apply().unwrap(), 2);
+    let restored = app.search(bookmarks::SearchQuery { id: Some(tagged.id), ..Default::default() }).unwrap();
+    assert!(restored[0].tags.is_empty());
+    let all = app.search(bookmarks::SearchQuery::default()).unwrap();
+    assert!(all.iter().any(|b| b.id == doomed.id && b.title == "doomed" && b.url == doomed.url));
+
+    assert!(app.undo_rules_apply().is_err());
+}
+
+#[test]
+fn rule_apply_keeps_urls_unique() {
+    use crate::rules::Action;
+
+    let (app, _tmp) = create_app();
+    for url in ["https://reddit.com/r/rust", "https://old.reddit.com/r/rust", "https://old.reddit.com/r/go", "https://www.reddit.com/r/go"] {
+        app.create(
+            bookmarks::BookmarkCreate {
+                url: url.into(),
+                ..Default::default()
+            },
+            default_add_opts(),
+        ).unwrap();
+    }
+    *app.rules_config().write().unwrap().rules_mut() = vec![url_rule("reddit.com", Action::RewriteUrl {
+        pattern: r"//(old|www)\.reddit\.com".into(),
+        replacement: "//reddit.com".into(),
+    })];
+
+    // only one of the two r/go bookmarks can take the URL
+    let report = app.apply_rules_to_bookmarks(rule_apply_opts(false)).unwrap();
+    assert_eq!(report.changes.len(), 1);
+    assert_eq!(report.changes[0].after.as_ref().unwrap().url, "https://reddit.com/r/go");
+
+    let mut urls: Vec<String> = app.search(bookmarks::SearchQuery::default()).unwrap().into_iter().map(|b| b.url).collect();
+    urls.sort();
+    urls.dedup();
+    assert_eq!(urls.len(), 4);
+}
+
+#[test]
+fn test_metadata_reports_kept_per_bookmark() {
+    let (app, tmp) = create_app();
+    app.config().write().unwrap().scrape.report_history = 2;
+    let bmark = app.create(
+        bookmarks::BookmarkCreate {
+            url: "https://example.com/reports".into(),
+            ..Default::default()
+        },
+        default_add_opts(),
+    ).unwrap().0;
+
+    let report = |winner: &str| crate::metadata::MetadataReport {
+        field_decisions: vec![crate::metadata::FieldDecision {
+            field: "title".into(),
+            winner: winner.into(),
+            reason: "first available".into(),
+            value_preview: None,
+        }],
+        ..Default::default()
+    };
+    for winner in ["Plain", "DDG", "Headless"] {
+        AppLocal::record_report(&app.reports(), &app.config(), bmark.id, &bmark.url, &report(winner));
+    }
+
+    let reports = app.metadata_reports(bmark.id).unwrap();
+    let winners: Vec<&str> = reports.iter().map(|r| r.report.field_decisions[0].winner.as_str()).collect();
+    assert_eq!(winners, ["Headless", "DDG"]);
+    assert_eq!(reports[0].url, bmark.url);
+    assert_eq!(reports[0].sources()["title"], "Headless");
+
+    // persisted next to bookmarks.csv, one file per bookmark
+    let report_file = tmp.path().join("metadata_reports").join(format!("{}.json", bmark.id));
+    assert!(report_file.exists());
+    let reloaded = crate::metadata::reports::ReportStore::load(tmp.path().to_str().unwrap());
+    assert_eq!(reloaded.get(bmark.id).len(), 2);
+    assert_eq!(reloaded.sources()[&bmark.id]["title"], "headless");
+
+    let found = |query: &str| {
+        app.search(bookmarks::SearchQuery {
+            query: Some(query.into()),
+            ..Default::default()
+        })
+        .unwrap()
+        .len()
+    };
+    assert_eq!(found("source:title=headless"), 1);
+    assert_eq!(found("source:ddg"), 0);
+
+    assert!(app.metadata_reports(bmark.id + 1).is_err());
+
+    app.delete(bmark.id).unwrap();
+    assert!(app.reports().get(bmark.id).is_empty());
+    assert!(app.reports().sources().is_empty());
+    assert!(!report_file.exists());
+}
+
+#[test]
+fn saved_searches_resolve_in_searches_and_rules() {
+    let (app, _tmp) = create_app();
+    app.saved_searches()
+        .unwrap()
+        .write()
+        .unwrap()
+        .create("reading".into(), "#toread".into(), None)
+        .unwrap();
+    app.rules_config().write().unwrap().rules_mut().push(crate::rules::Rule {
+        url: None,
+        query: Some("@reading".into()),
+        ..tag_rule("queued")
+    });
+
+    let create = |url: &str, tag: &str| {
+        app.create(
+            bookmarks::BookmarkCreate {
+                url: url.into(),
+                tags: Some(vec![tag.into()]),
+                ..Default::default()
+            },
+            default_add_opts(),
+        ).unwrap().0
+    };
+    let later = create("https://example.com/later", "toread");
+    let done = create("https://example.com/done", "done");
+    assert_eq!(later.tags, ["toread", "queued"]);
+    assert_eq!(done.tags, ["done"]);
+
+    let page = app.search_page(bookmarks::SearchQuery {
+        query: Some("@reading".into()),
+        sort: Some(bookmarks::SortField::Relevance),
+        ..Default::default()
+    }).unwrap();
+    let ids: Vec<u64> = page.bookmarks.iter().map(|b| b.id).collect();
+    assert_eq!(ids, [later.id]);
+
+    let explained = app.explain_query("@reading", None).unwrap();
+    assert_eq!(explained.strict_error, None);
+    assert!(app.search(bookmarks::SearchQuery {
+        query: Some("@unknown".into()),
+        ..Default::default()
+    }).is_err());
+}
+
+#[test]
+fn saved_search_crud_through_backend() {
+    let (app, _tmp) = create_app();
+    app.create_saved_search("reading".into(), "#toread".into(), Some("to read".into()))
+        .unwrap();
+    assert!(app.create_saved_search("Reading".into(), "#later".into(), None).is_err());
+
+    let renamed = app.update_saved_search("@reading", Some("queue".into()), Some("#later".into()), None)
+        .unwrap();
+    assert_eq!(renamed.name, "queue");
+    assert_eq!(renamed.description.as_deref(), Some("to read"));
+
+    // a rule still references it, so it can't go
+    app.rules_config().write().unwrap().rules_mut().push(crate::rules::Rule {
+        url: None,
+        query: Some("@queue".into()),
+        ..tag_rule("queued")
+    });
+    let err = app.delete_saved_search("queue").unwrap_err().to_string();
+    assert!(err.contains("still used by rule"), "{err}");
+
+    app.rules_config().write().unwrap().rules_mut().clear();
+    app.delete_saved_search("queue").unwrap();
+    assert!(app.list_saved_searches().unwrap().is_empty());
+}
@@ -382,6 +382,107 @@ fn search_hierarchical_tag_matching() {
     assert_eq!(results.len(), 2);
 }
 
+#[test]
+fn search_tag_filter_exact_and_glob() {
+    let (mgr, _tmp) = fresh_mgr();
+    mgr.create(BookmarkCreate {
+        url: "https://a.com".into(),
+        tags: Some(vec!["dev".into()]),
+        ..Default::default()
+    }).unwrap();
+    mgr.create(BookmarkCreate {
+        url: "https://b.com".into(),
+        tags: Some(vec!["dev/rust".into()]),
+        ..Default::default()
+    }).unwrap();
+    mgr.create(BookmarkCreate {
+        url: "https://c.com".into(),
+        tags: Some(vec!["lang/rust".into()]),
+        ..Default::default()
+    }).unwrap();
+
+    // exact: "dev" only, no descendants
+    let results = mgr.search(SearchQuery {
+        tags: Some(vec!["=dev".into()]),
+        ..Default::default()
+    }).unwrap();
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].url, "https://a.com");
+
+    // glob: any parent with a "rust" child
+    let results = mgr.search(SearchQuery {
+        tags: Some(vec!["*/rust".into()]),
+        ..Default::default()
+    }).unwrap();
+    assert_eq!(results.len(), 2);
+
+    // negated exact keeps descendants
+    let results = mgr.search(SearchQuery {
+        tags: Some(vec!["-=dev".into(), "*/rust".into()]),
+        ..Default::default()
+    }).unwrap();
+    assert_eq!(results.len(), 2);
+}
+
+#[test]
+fn search_fuzzy_fallback_and_ordering() {
+    let (mgr, _tmp) = fresh_mgr();
+    mgr.create(BookmarkCreate {
+        url: "https://a.com".into(),
+        title: Some("Kubernetes the hard way".into()),
+        ..Default::default()
+    }).unwrap();
+    mgr.create(BookmarkCreate {
+        url: "https://b.com".into(),
+        title: Some("Kubernets typo in title".into()),
+        ..Default::default()
+    }).unwrap();
+    mgr.create(BookmarkCreate {
+        url: "https://c.com".into(),
+        title: Some("Unrelated".into()),
+        ..Default::default()
+    }).unwrap();
+
+    // closest match first
+    let results = mgr.search(SearchQuery {
+        query: Some("~kubernets".into()),
+        ..Default::default()
+    }).unwrap();
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[0].url, "https://b.com");
+
+    // limit applies after ordering
+    let results = mgr.search(SearchQuery {
+        query: Some("~kubernetis".into()),
+        limit: Some(1),
+        ..Default::default()
+    }).unwrap();
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].url, "https://a.com");
+
+    // no fallback unless requested
+    let results = mgr.search(SearchQuery {
+        query: Some("kubernetis".into()),
+        ..Default::default()
+    }).unwrap();
+    assert!(results.is_empty());
+
+    let results = mgr.search(SearchQuery {
+        query: Some("kubernetis".into()),
+        fuzzy_fallback: true,
+        ..Default::default()
+    }).unwrap();
+    assert_eq!(results.len(), 2);
+
+    // exact hits never fall back
+    let results = mgr.search(SearchQuery {
+        query: Some("kubernetes".into()),
+        fuzzy_fallback: true,
+        ..Default::default()
+    }).unwrap();
+    assert_eq!(results.len(), 1);
+}
+
 #[test]
 fn search_negated_tags() {
     let (mgr, _tmp) = fresh_mgr();
@@ -454,6 +555,30 @@ fn search_delete_no_match_removes_nothing() {
     assert_eq!(mgr.search(SearchQuery::default()).unwrap().len(), 3);
 }
 
+// --- write_batch ---
+
+#[test]
+fn write_batch_replaces_restores_and_deletes() {
+    let (mgr, tmp) = fresh_mgr();
+    seed(&mgr, 4);
+    let all = mgr.search(SearchQuery::default()).unwrap();
+    mgr.delete(all[1].id).unwrap();
+
+    let mut renamed = all[0].clone();
+    renamed.title = "Renamed".into();
+    renamed.tags = vec!["x".into(), "x".into()];
+    mgr.write_batch(vec![renamed, all[1].clone()], &[all[3].id]).unwrap();
+
+    let reloaded = BackendCsv::load(tmp.path().join("bookmarks.csv").to_str().unwrap()).unwrap();
+    let ids: Vec<u64> = reloaded.search(SearchQuery::default()).unwrap().iter().map(|b| b.id).collect();
+    assert_eq!(ids, vec![all[0].id, all[1].id, all[2].id]);
+
+    let first = reloaded.search(SearchQuery { id: Some(all[0].id), ..Default::default() }).unwrap();
+    assert_eq!(first[0].title, "Renamed");
+    assert_eq!(first[0].tags, vec!["x".to_string()]);
+    assert_eq!(reloaded.search(SearchQuery { title: Some("Title 1".into()), ..Default::default() }).unwrap().len(), 1);
+}
+
 // --- search_update ---
 
 #[test]
@@ -800,3 +925,179 @@ fn create_with_reordered_internal_list_uses_max_not_last() {
     let unique_count = ids.iter().collect::<std::collections::HashSet<_>>().len();
     assert_eq!(ids.len(), unique_count);
 }
+
+// --- search index ---
+
+const INDEX_QUERIES: &[&str] = &[
+    "rust",
+    ".async",
+    ">guide tokio",
+    ":docs.rs",
+    "#lang/rust",
+    "#lang",
+    "#=lang",
+    "#*/rust",
+    "rust or python",
+    "#dev and not go",
+    "(tokio or serde) and .book",
+    "\"async book\"",
+    "ru",
+    "tags:>2",
+    "nothing-matches-this",
+];
+
+/// Ids matching `query` by evaluating it against every bookmark.
+fn scan_ids(mgr: &BackendCsv, query: &str) -> Vec<u64> {
+    let filter = crate::search_query::parse(&query.to_lowercase()).unwrap();
+    let list = mgr.list();
+    let list = list.read().unwrap();
+    list.iter()
+        .filter(|b| crate::search_query::eval(&filter, b, &Default::default()))
+        .map(|b| b.id)
+        .collect()
+}
+
+fn search_ids(mgr: &BackendCsv, query: &str) -> Vec<u64> {
+    mgr.search(SearchQuery {
+        query: Some(query.to_string()),
+        ..Default::default()
+    })
+    .unwrap()
+    .iter()
+    .map(|b| b.id)
+    .collect()
+}
+
+fn assert_index_matches_scan(mgr: &BackendCsv) {
+    for query in INDEX_QUERIES {
+        assert_eq!(search_ids(mgr, query), scan_ids(mgr, query), "query: {query}");
+    }
+}
+
+#[test]
+fn search_index_tracks_create_update_delete() {
+    let (mgr, _tmp) = fresh_mgr();
+    let fixtures = [
+        ("Async Rust book", "A guide to tokio", "https://docs.rs/tokio", vec!["lang/rust", "dev"]),
+        ("Python tips", "", "https://python.org", vec!["lang/python", "dev"]),
+        ("Go concurrency", "channels", "https://go.dev", vec!["lang/go", "dev", "go"]),
+        ("Serde book", "serialization", "https://serde.rs", vec!["rust"]),
+    ];
+    for (title, description, url, tags) in fixtures {
+        mgr.create(BookmarkCreate {
+            url: url.into(),
+            title: Some(title.into()),
+            description: Some(description.into()),
+            tags: Some(tags.into_iter().map(String::from).collect()),
+            ..Default::default()
+        })
+        .unwrap();
+    }
+    assert_index_matches_scan(&mgr);
+    assert_eq!(search_ids(&mgr, "#lang/rust"), vec![0]);
+
+    mgr.update(
+        1,
+        BookmarkUpdate {
+            title: Some("Async Python".into()),
+            tags: Some(vec!["lang/rust".into()]),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    assert_index_matches_scan(&mgr);
+    assert_eq!(search_ids(&mgr, "#lang/rust .async"), vec![0, 1]);
+    assert!(search_ids(&mgr, "python.org #lang/python").is_empty());
+
+    mgr.delete(0).unwrap();
+    assert_index_matches_scan(&mgr);
+    assert_eq!(search_ids(&mgr, ".async"), vec![1]);
+
+    mgr.search_update(
+        SearchQuery {
+            query: Some("#dev".into()),
+            ..Default::default()
+        },
+        BookmarkUpdate {
+            append_tags: Some(vec!["lang/rust".into()]),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    assert_index_matches_scan(&mgr);
+    assert_eq!(search_ids(&mgr, "#lang/rust"), vec![1, 2]);
+
+    mgr.search_delete(SearchQuery {
+        query: Some("serde".into()),
+        ..Default::default()
+    })
+    .unwrap();
+    assert_index_matches_scan(&mgr);
+    assert!(search_ids(&mgr, "serde").is_empty());
+}
+
+#[test]
+#[ignore = "benchmark over 100k bookmarks, run with --release -- --ignored"]
+fn search_index_matches_scan_at_100k() {
+    use std::time::Instant;
+
+    const WORDS: &[&str] = &[
+        "rust", "async", "tokio", "serde", "python", "go", "guide", "book", "kubernetes",
+        "docs", "release", "notes", "intro", "deep", "dive", "web", "server", "cli",
+    ];
+    const TAGS: &[&str] = &["lang/rust", "lang/python", "lang/go", "dev", "dev/tools", "go", "read"];
+    const HOSTS: &[&str] = &["docs.rs", "github.com", "python.org", "go.dev", "example.com"];
+
+    let tmp = tempfile::tempdir().unwrap();
+    let csv_path = tmp.path().join("bookmarks.csv");
+
+    // Deterministic pseudo-random content
+    let mut seed: u64 = 0x2545_f491_4f6c_dd1d;
+    let mut next = |n: usize| {
+        seed ^= seed << 13;
+        seed ^= seed >> 7;
+        seed ^= seed << 17;
+        (seed % n as u64) as usize
+    };
+
+    let mut wrt = csv::Writer::from_path(&csv_path).unwrap();
+    wrt.write_record(["id", "url", "title", "description", "tags", "image_id", "icon_id"])
+        .unwrap();
+    for id in 0..100_000 {
+        let title: Vec<&str> = (0..4).map(|_| WORDS[next(WORDS.len())]).collect();
+        let description: Vec<&str> = (0..12).map(|_| WORDS[next(WORDS.len())]).collect();
+        let tags: Vec<&str> = (0..next(4)).map(|_| TAGS[next(TAGS.len())]).collect();
+        let url = format!("https://{}/{}/{id}", HOSTS[next(HOSTS.len())], WORDS[next(WORDS.len())]);
+        wrt.write_record([
+            id.to_string(),
+            url,
+            title.join(" "),
+            description.join(" "),
+            tags.join(","),
+            String::new(),
+            String::new(),
+        ])
+        .unwrap();
+    }
+    wrt.flush().unwrap();
+
+    let started = Instant::now();
+    let mgr = BackendCsv::load(csv_path.to_str().unwrap()).unwrap();
+    eprintln!("load + index: {:?}", started.elapsed());
+
+    for query in INDEX_QUERIES {
+        let started = Instant::now();
+        let scanned = scan_ids(&mgr, query);
+        let scan_time = started.elapsed();
+
+        let started = Instant::now();
+        let searched = search_ids(&mgr, query);
+        let search_time = started.elapsed();
+
+        eprintln!(
+            "{query:>28}: {:>6} hits, scan {scan_time:>10.2?}, indexed {search_time:>10.2?}",
+            searched.len()
+        );
+        assert_eq!(searched, scanned, "query: {query}");
+    }
+}
@@ -136,3 +136,105 @@ pub fn test_query_search() {
         .unwrap();
     assert_eq!(results.len(), 0);
 }
+
+fn sample_bookmark(id: u64, title: &str, url: &str) -> bookmarks::Bookmark {
+    bookmarks::Bookmark {
+        id,
+        title: title.to_string(),
+        url: url.to_string(),
+        ..Default::default()
+    }
+}
+
+#[test]
+pub fn test_paginate_sort_and_offset() {
+    use bookmarks::{paginate, SearchQuery, SortDirection, SortField};
+
+    let all = vec![
+        sample_bookmark(1, "beta", "https://www.zeta.org/a"),
+        sample_bookmark(2, "Alpha", "https://alpha.com/b"),
+        sample_bookmark(3, "gamma", "https://docs.alpha.com/c"),
+    ];
+    let ids = |page: &bookmarks::SearchPage| page.bookmarks.iter().map(|b| b.id).collect::<Vec<_>>();
+
+    let page = paginate(all.clone(), &SearchQuery {
+        sort: Some(SortField::Title),
+        ..Default::default()
+    });
+    assert_eq!(ids(&page), vec![2, 1, 3]);
+    assert_eq!(page.total, 3);
+
+    let page = paginate(all.clone(), &SearchQuery {
+        sort: Some(SortField::Domain),
+        ..Default::default()
+    });
+    assert_eq!(ids(&page), vec![2, 3, 1]);
+
+    let page = paginate(all.clone(), &SearchQuery {
+        sort: Some(SortField::Id),
+        direction: Some(SortDirection::Desc),
+        offset: Some(1),
+        limit: Some(1),
+        ..Default::default()
+    });
+    assert_eq!(ids(&page), vec![2]);
+    assert_eq!(page.total, 3);
+
+    // relevance keeps the ranked order unless reversed
+    let page = paginate(all.clone(), &SearchQuery {
+        sort: Some(SortField::Relevance),
+        ..Default::default()
+    });
+    assert_eq!(ids(&page), vec![1, 2, 3]);
+    let page = paginate(all.clone(), &SearchQuery {
+        sort: Some(SortField::Relevance),
+        direction: Some(SortDirection::Asc),
+        ..Default::default()
+    });
+    assert_eq!(ids(&page), vec![3, 2, 1]);
+
+    let page = paginate(all, &SearchQuery {
+        offset: Some(5),
+        ..Default::default()
+    });
+    assert!(page.bookmarks.is_empty());
+    assert_eq!(page.total, 3);
+}
+
+#[test]
+pub fn test_paginate_relevance_ranks_query_matches() {
+    use bookmarks::{paginate, SearchQuery, SortDirection, SortField};
+
+    let mut tagged = sample_bookmark(2, "Weekly notes", "https://example.com/2");
+    tagged.tags = vec!["lang/rust".to_string()];
+    let all = vec![
+        sample_bookmark(1, "Notes", "https://rust.example.com/1"),
+        tagged,
+        sample_bookmark(3, "Rust", "https://example.com/3"),
+        sample_bookmark(4, "Unrelated", "https://example.com/4"),
+    ];
+    let ids = |page: &bookmarks::SearchPage| page.bookmarks.iter().map(|b| b.id).collect::<Vec<_>>();
+
+    let query = SearchQuery {
+        query: Some("rust or unrelated".to_string()),
+        sort: Some(SortField::Relevance),
+        ..Default::default()
+    };
+    // the rare `unrelated` title hit first, then `rust` in a title, a tag and a url
+    let page = paginate(all.clone(), &query);
+    assert_eq!(ids(&page), vec![4, 3, 2, 1]);
+
+    let page = paginate(all.clone(), &SearchQuery {
+        direction: Some(SortDirection::Asc),
+        ..query.clone()
+    });
+    assert_eq!(ids(&page), vec![1, 2, 3, 4]);
+
+    // terms under `not` don't count
+    let page = paginate(all, &SearchQuery {
+        query: Some("not rust".to_string()),
+        sort: Some(SortField::Relevance),
+        ..Default::default()
+    });
+    assert_eq!(ids(&page), vec![1, 2, 3, 4]);
+}
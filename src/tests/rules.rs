@@ -7,7 +7,8 @@ pub fn test_plaintext_matches() {
     assert!(rules::Rule::is_string_matches("1 one", "1 one"));
     assert!(!rules::Rule::is_string_matches("1 one", "1"));
     assert!(rules::Rule::is_string_matches("", "123"));
-    assert!(rules::Rule::is_string_matches("r/testing", "r/testing"));
+    // `r/` always starts a regex, so a lone one is an error, not a substring
+    assert!(rules::Pattern::parse("r/testing").is_err());
     assert!(rules::Rule::is_string_matches("/testing/", "/testing/"));
 }
 
@@ -41,6 +42,12 @@ pub fn test_record_match() {
         tags: None,
         query: None,
         comment: None,
+        when: vec![],
+        stop: false,
+        priority: 0,
+        id: 0,
+        enabled: true,
+        compiled: Default::default(),
         action: rules::Action::UpdateBookmark {
             tags: Some(vec!["favorite".to_string()]),
             title: None,
@@ -53,14 +60,14 @@ pub fn test_record_match() {
         title: None,
         description: None,
         tags: None,
-    }));
+    }, &Default::default()));
 
     assert!(!rule.is_match(&rules::Record {
         url: "stupidpage.com/ihateit".into(),
         title: None,
         description: None,
         tags: None,
-    }));
+    }, &Default::default()));
 }
 
 #[test]
@@ -72,6 +79,12 @@ pub fn test_rule_matches_by_title() {
         tags: None,
         query: None,
         comment: None,
+        when: vec![],
+        stop: false,
+        priority: 0,
+        id: 0,
+        enabled: true,
+        compiled: Default::default(),
         action: rules::Action::UpdateBookmark {
             tags: None,
             title: None,
@@ -84,14 +97,14 @@ pub fn test_rule_matches_by_title() {
         title: Some("Very Important Page".into()),
         description: None,
         tags: None,
-    }));
+    }, &Default::default()));
 
     assert!(!rule.is_match(&rules::Record {
         url: "https://any.com".into(),
         title: Some("Boring Page".into()),
         description: None,
         tags: None,
-    }));
+    }, &Default::default()));
 }
 
 #[test]
@@ -103,6 +116,12 @@ pub fn test_rule_matches_by_description() {
         tags: None,
         query: None,
         comment: None,
+        when: vec![],
+        stop: false,
+        priority: 0,
+        id: 0,
+        enabled: true,
+        compiled: Default::default(),
         action: rules::Action::UpdateBookmark {
             tags: None,
             title: None,
@@ -115,14 +134,14 @@ pub fn test_rule_matches_by_description() {
         title: None,
         description: Some("A great tutorial on Rust".into()),
         tags: None,
-    }));
+    }, &Default::default()));
 
     assert!(!rule.is_match(&rules::Record {
         url: "https://any.com".into(),
         title: None,
         description: Some("Just some article".into()),
         tags: None,
-    }));
+    }, &Default::default()));
 }
 
 #[test]
@@ -134,6 +153,12 @@ pub fn test_rule_matches_by_tags_with_url() {
         tags: Some(vec!["programming".to_string(), "rust".to_string()]),
         query: None,
         comment: None,
+        when: vec![],
+        stop: false,
+        priority: 0,
+        id: 0,
+        enabled: true,
+        compiled: Default::default(),
         action: rules::Action::UpdateBookmark {
             tags: None,
             title: None,
@@ -151,7 +176,7 @@ pub fn test_rule_matches_by_tags_with_url() {
             "rust".to_string(),
             "extra".to_string(),
         ]),
-    }));
+    }, &Default::default()));
 
     // only one tag — no match
     assert!(!rule.is_match(&rules::Record {
@@ -159,7 +184,7 @@ pub fn test_rule_matches_by_tags_with_url() {
         title: None,
         description: None,
         tags: Some(vec!["programming".to_string()]),
-    }));
+    }, &Default::default()));
 
     // no tags — no match
     assert!(!rule.is_match(&rules::Record {
@@ -167,7 +192,7 @@ pub fn test_rule_matches_by_tags_with_url() {
         title: None,
         description: None,
         tags: None,
-    }));
+    }, &Default::default()));
 }
 
 #[test]
@@ -180,6 +205,12 @@ pub fn test_rule_empty_tags_matches_untagged() {
         tags: Some(vec![]),
         query: None,
         comment: None,
+        when: vec![],
+        stop: false,
+        priority: 0,
+        id: 0,
+        enabled: true,
+        compiled: Default::default(),
         action: rules::Action::UpdateBookmark {
             tags: None,
             title: None,
@@ -192,14 +223,14 @@ pub fn test_rule_empty_tags_matches_untagged() {
         title: None,
         description: None,
         tags: None,
-    }));
+    }, &Default::default()));
 
     assert!(rule.is_match(&rules::Record {
         url: "https://any.com".into(),
         title: None,
         description: None,
         tags: Some(vec![]),
-    }));
+    }, &Default::default()));
 
     // Non-empty record tags don't match empty rule tags
     assert!(!rule.is_match(&rules::Record {
@@ -207,7 +238,7 @@ pub fn test_rule_empty_tags_matches_untagged() {
         title: None,
         description: None,
         tags: Some(vec!["has-tag".to_string()]),
-    }));
+    }, &Default::default()));
 }
 
 #[test]
@@ -219,6 +250,12 @@ pub fn test_rule_multiple_fields_all_must_match() {
         tags: None,
         query: None,
         comment: None,
+        when: vec![],
+        stop: false,
+        priority: 0,
+        id: 0,
+        enabled: true,
+        compiled: Default::default(),
         action: rules::Action::UpdateBookmark {
             tags: None,
             title: None,
@@ -232,7 +269,7 @@ pub fn test_rule_multiple_fields_all_must_match() {
         title: Some("My repo".into()),
         description: None,
         tags: None,
-    }));
+    }, &Default::default()));
 
     // url matches but title doesn't
     assert!(!rule.is_match(&rules::Record {
@@ -240,7 +277,7 @@ pub fn test_rule_multiple_fields_all_must_match() {
         title: Some("My project".into()),
         description: None,
         tags: None,
-    }));
+    }, &Default::default()));
 
     // title matches but url doesn't
     assert!(!rule.is_match(&rules::Record {
@@ -248,7 +285,7 @@ pub fn test_rule_multiple_fields_all_must_match() {
         title: Some("My repo".into()),
         description: None,
         tags: None,
-    }));
+    }, &Default::default()));
 }
 
 #[test]
@@ -260,6 +297,12 @@ pub fn test_rule_tag_matching_is_case_insensitive() {
         tags: Some(vec!["Rust".to_string()]),
         query: None,
         comment: None,
+        when: vec![],
+        stop: false,
+        priority: 0,
+        id: 0,
+        enabled: true,
+        compiled: Default::default(),
         action: rules::Action::UpdateBookmark {
             tags: None,
             title: None,
@@ -272,7 +315,7 @@ pub fn test_rule_tag_matching_is_case_insensitive() {
         title: None,
         description: None,
         tags: Some(vec!["rust".to_string()]),
-    }));
+    }, &Default::default()));
 }
 
 #[test]
@@ -284,6 +327,12 @@ pub fn test_rule_tags_only_matches() {
         tags: Some(vec!["rust".to_string()]),
         query: None,
         comment: None,
+        when: vec![],
+        stop: false,
+        priority: 0,
+        id: 0,
+        enabled: true,
+        compiled: Default::default(),
         action: rules::Action::UpdateBookmark {
             tags: None,
             title: None,
@@ -296,14 +345,14 @@ pub fn test_rule_tags_only_matches() {
         title: None,
         description: None,
         tags: Some(vec!["rust".to_string()]),
-    }));
+    }, &Default::default()));
 
     assert!(!rule.is_match(&rules::Record {
         url: "https://any.com".into(),
         title: None,
         description: None,
         tags: Some(vec!["python".to_string()]),
-    }));
+    }, &Default::default()));
 }
 
 #[test]
@@ -315,6 +364,12 @@ pub fn test_rule_tag_order_independent() {
         tags: Some(vec!["b".to_string(), "a".to_string()]),
         query: None,
         comment: None,
+        when: vec![],
+        stop: false,
+        priority: 0,
+        id: 0,
+        enabled: true,
+        compiled: Default::default(),
         action: rules::Action::UpdateBookmark {
             tags: None,
             title: None,
@@ -328,21 +383,56 @@ pub fn test_rule_tag_order_independent() {
         title: None,
         description: None,
         tags: Some(vec!["a".to_string(), "b".to_string()]),
-    }));
+    }, &Default::default()));
 
     assert!(rule.is_match(&rules::Record {
         url: "https://any.com".into(),
         title: None,
         description: None,
         tags: Some(vec!["b".to_string(), "a".to_string()]),
-    }));
+    }, &Default::default()));
 }
 
 #[test]
-#[should_panic(expected = "malformed regex")]
-pub fn test_malformed_regex_panics() {
-    // is_string_matches panics on malformed regex via .expect()
-    rules::Rule::is_string_matches("r/[unclosed/", "input");
+pub fn test_malformed_regex_never_matches() {
+    assert!(!rules::Rule::is_string_matches("r/[unclosed/", "input"));
+    assert!(rules::Pattern::parse("r/[unclosed/").is_err());
+
+    let rule: rules::Rule = serde_yml::from_str("url: r/[unclosed/\naction: Delete\n").unwrap();
+    assert!(rule.compile().unwrap_err().starts_with("url: invalid regex"));
+    assert!(!rule.is_match(&rules::Record {
+        url: "[unclosed".into(),
+        title: None,
+        description: None,
+        tags: None,
+    }, &Default::default()));
+}
+
+#[test]
+pub fn test_action_regex_compiled_once() {
+    let rule: rules::Rule =
+        serde_yml::from_str("url: reddit.com\naction: !RewriteUrl\n  pattern: '//old\\.'\n  replacement: '//'\n").unwrap();
+    let regex = rule.action_regex().unwrap().unwrap();
+    assert!(std::ptr::eq(regex, rule.action_regex().unwrap().unwrap()));
+    assert_eq!(regex.replace_all("https://old.reddit.com", "//"), "https://reddit.com");
+
+    let rule: rules::Rule = serde_yml::from_str("url: a.com\naction: Delete\n").unwrap();
+    assert!(rule.action_regex().unwrap().is_none());
+    let rule: rules::Rule =
+        serde_yml::from_str("url: a.com\naction: !RewriteUrl\n  pattern: '['\n  replacement: ''\n").unwrap();
+    assert!(rule.action_regex().is_err());
+}
+
+#[test]
+pub fn test_regex_flags() {
+    assert!(!rules::Rule::is_string_matches("r/github/", "GitHub.com"));
+    assert!(rules::Rule::is_string_matches("r/github/i", "GitHub.com"));
+    assert!(rules::Rule::is_string_matches("r/^second$/m", "first\nsecond"));
+    assert!(!rules::Rule::is_string_matches("r/^second$/", "first\nsecond"));
+    assert!(rules::Rule::is_string_matches("r/a.b/s", "a\nb"));
+    assert!(rules::Rule::is_string_matches("r/ git hub /x", "github"));
+    // anything but flags after the last slash is rejected
+    assert!(rules::Pattern::parse("r/a/b").unwrap_err().contains("unsupported flag 'b'"));
 }
 
 #[test]
@@ -357,6 +447,12 @@ pub fn test_title_rule_vs_none_title_record() {
         tags: None,
         query: None,
         comment: None,
+        when: vec![],
+        stop: false,
+        priority: 0,
+        id: 0,
+        enabled: true,
+        compiled: Default::default(),
         action: rules::Action::UpdateBookmark {
             tags: None,
             title: None,
@@ -369,7 +465,7 @@ pub fn test_title_rule_vs_none_title_record() {
         title: None,
         description: None,
         tags: None,
-    }));
+    }, &Default::default()));
 }
 
 #[test]
@@ -383,6 +479,12 @@ pub fn test_description_rule_vs_none_description_record() {
         tags: None,
         query: None,
         comment: None,
+        when: vec![],
+        stop: false,
+        priority: 0,
+        id: 0,
+        enabled: true,
+        compiled: Default::default(),
         action: rules::Action::UpdateBookmark {
             tags: None,
             title: None,
@@ -395,7 +497,7 @@ pub fn test_description_rule_vs_none_description_record() {
         title: None,
         description: None,
         tags: None,
-    }));
+    }, &Default::default()));
 }
 
 #[test]
@@ -408,6 +510,12 @@ pub fn test_no_condition_rule_returns_false() {
         tags: None,
         query: None,
         comment: None,
+        when: vec![],
+        stop: false,
+        priority: 0,
+        id: 0,
+        enabled: true,
+        compiled: Default::default(),
         action: rules::Action::UpdateBookmark {
             tags: None,
             title: None,
@@ -420,7 +528,7 @@ pub fn test_no_condition_rule_returns_false() {
         title: Some("anything".into()),
         description: Some("anything".into()),
         tags: Some(vec!["tag".to_string()]),
-    }));
+    }, &Default::default()));
 }
 
 #[test]
@@ -432,17 +540,17 @@ pub fn test_action_fields_exist() {
         tags: Some(vec!["tag1".to_string(), "tag2".to_string()]),
     };
 
-    match action {
-        rules::Action::UpdateBookmark {
-            title,
-            description,
-            tags,
-        } => {
-            assert_eq!(title.unwrap(), "New Title");
-            assert_eq!(description.unwrap(), "New Description");
-            assert_eq!(tags.unwrap().len(), 2);
-        }
-    }
+    let rules::Action::UpdateBookmark {
+        title,
+        description,
+        tags,
+    } = action
+    else {
+        panic!("expected an UpdateBookmark action");
+    };
+    assert_eq!(title.unwrap(), "New Title");
+    assert_eq!(description.unwrap(), "New Description");
+    assert_eq!(tags.unwrap().len(), 2);
 }
 
 #[test]
@@ -454,6 +562,12 @@ pub fn test_query_simple_term() {
         tags: None,
         query: Some("rust".to_string()),
         comment: None,
+        when: vec![],
+        stop: false,
+        priority: 0,
+        id: 0,
+        enabled: true,
+        compiled: Default::default(),
         action: rules::Action::UpdateBookmark {
             tags: None,
             title: None,
@@ -466,14 +580,14 @@ pub fn test_query_simple_term() {
         title: Some("Learning Rust".into()),
         description: None,
         tags: None,
-    }));
+    }, &Default::default()));
 
     assert!(!rule.is_match(&rules::Record {
         url: "https://example.com".into(),
         title: Some("Learning Python".into()),
         description: None,
         tags: None,
-    }));
+    }, &Default::default()));
 }
 
 #[test]
@@ -485,6 +599,12 @@ pub fn test_query_boolean_ops() {
         tags: None,
         query: Some("rust and tutorial".to_string()),
         comment: None,
+        when: vec![],
+        stop: false,
+        priority: 0,
+        id: 0,
+        enabled: true,
+        compiled: Default::default(),
         action: rules::Action::UpdateBookmark {
             tags: None,
             title: None,
@@ -497,14 +617,14 @@ pub fn test_query_boolean_ops() {
         title: Some("Rust Tutorial".into()),
         description: None,
         tags: None,
-    }));
+    }, &Default::default()));
 
     assert!(!rule.is_match(&rules::Record {
         url: "https://example.com".into(),
         title: Some("Rust Reference".into()),
         description: None,
         tags: None,
-    }));
+    }, &Default::default()));
 }
 
 #[test]
@@ -516,6 +636,12 @@ pub fn test_query_not_operator() {
         tags: None,
         query: Some("rust not beginner".to_string()),
         comment: None,
+        when: vec![],
+        stop: false,
+        priority: 0,
+        id: 0,
+        enabled: true,
+        compiled: Default::default(),
         action: rules::Action::UpdateBookmark {
             tags: None,
             title: None,
@@ -528,14 +654,14 @@ pub fn test_query_not_operator() {
         title: Some("Advanced Rust".into()),
         description: None,
         tags: None,
-    }));
+    }, &Default::default()));
 
     assert!(!rule.is_match(&rules::Record {
         url: "https://example.com".into(),
         title: Some("Rust beginner guide".into()),
         description: None,
         tags: None,
-    }));
+    }, &Default::default()));
 }
 
 #[test]
@@ -547,6 +673,12 @@ pub fn test_query_field_prefix_tag() {
         tags: None,
         query: Some("#programming".to_string()),
         comment: None,
+        when: vec![],
+        stop: false,
+        priority: 0,
+        id: 0,
+        enabled: true,
+        compiled: Default::default(),
         action: rules::Action::UpdateBookmark {
             tags: None,
             title: None,
@@ -559,14 +691,14 @@ pub fn test_query_field_prefix_tag() {
         title: Some("Any".into()),
         description: None,
         tags: Some(vec!["programming".to_string()]),
-    }));
+    }, &Default::default()));
 
     assert!(!rule.is_match(&rules::Record {
         url: "https://example.com".into(),
         title: Some("Any".into()),
         description: None,
         tags: Some(vec!["cooking".to_string()]),
-    }));
+    }, &Default::default()));
 }
 
 #[test]
@@ -578,6 +710,12 @@ pub fn test_query_field_prefix_url() {
         tags: None,
         query: Some(":github.com".to_string()),
         comment: None,
+        when: vec![],
+        stop: false,
+        priority: 0,
+        id: 0,
+        enabled: true,
+        compiled: Default::default(),
         action: rules::Action::UpdateBookmark {
             tags: None,
             title: None,
@@ -590,14 +728,14 @@ pub fn test_query_field_prefix_url() {
         title: None,
         description: None,
         tags: None,
-    }));
+    }, &Default::default()));
 
     assert!(!rule.is_match(&rules::Record {
         url: "https://gitlab.com/user/repo".into(),
         title: None,
         description: None,
         tags: None,
-    }));
+    }, &Default::default()));
 }
 
 #[test]
@@ -609,6 +747,12 @@ pub fn test_query_combined_with_url_condition() {
         tags: None,
         query: Some("#rust".to_string()),
         comment: None,
+        when: vec![],
+        stop: false,
+        priority: 0,
+        id: 0,
+        enabled: true,
+        compiled: Default::default(),
         action: rules::Action::UpdateBookmark {
             tags: None,
             title: None,
@@ -622,7 +766,7 @@ pub fn test_query_combined_with_url_condition() {
         title: None,
         description: None,
         tags: Some(vec!["rust".to_string()]),
-    }));
+    }, &Default::default()));
 
     // URL matches but query doesn't
     assert!(!rule.is_match(&rules::Record {
@@ -630,7 +774,7 @@ pub fn test_query_combined_with_url_condition() {
         title: None,
         description: None,
         tags: Some(vec!["python".to_string()]),
-    }));
+    }, &Default::default()));
 
     // Query matches but URL doesn't
     assert!(!rule.is_match(&rules::Record {
@@ -638,7 +782,7 @@ pub fn test_query_combined_with_url_condition() {
         title: None,
         description: None,
         tags: Some(vec!["rust".to_string()]),
-    }));
+    }, &Default::default()));
 }
 
 #[test]
@@ -650,6 +794,12 @@ pub fn test_query_invalid_returns_false() {
         tags: None,
         query: Some("(unclosed".to_string()),
         comment: None,
+        when: vec![],
+        stop: false,
+        priority: 0,
+        id: 0,
+        enabled: true,
+        compiled: Default::default(),
         action: rules::Action::UpdateBookmark {
             tags: None,
             title: None,
@@ -663,7 +813,7 @@ pub fn test_query_invalid_returns_false() {
         title: Some("anything".into()),
         description: None,
         tags: None,
-    }));
+    }, &Default::default()));
 }
 
 #[test]
@@ -675,6 +825,12 @@ pub fn test_query_empty_string_returns_false() {
         tags: None,
         query: Some("".to_string()),
         comment: None,
+        when: vec![],
+        stop: false,
+        priority: 0,
+        id: 0,
+        enabled: true,
+        compiled: Default::default(),
         action: rules::Action::UpdateBookmark {
             tags: None,
             title: None,
@@ -688,5 +844,205 @@ pub fn test_query_empty_string_returns_false() {
         title: Some("anything".into()),
         description: None,
         tags: None,
-    }));
+    }, &Default::default()));
+}
+
+#[test]
+pub fn test_rule_when_accepts_one_or_many() {
+    use rules::Trigger;
+
+    let rule: rules::Rule = serde_yml::from_str("url: a.com\naction: Delete\n").unwrap();
+    assert!(rule.when.is_empty());
+    assert!(rule.runs_on(Trigger::OnCreate));
+    assert!(rule.runs_on(Trigger::AfterMetadata));
+    assert!(!rule.runs_on(Trigger::OnUpdate));
+    assert!(rule.runs_on(Trigger::Manual));
+
+    let rule: rules::Rule =
+        serde_yml::from_str("url: a.com\nwhen: on_update\naction: Delete\n").unwrap();
+    assert_eq!(rule.when, vec![Trigger::OnUpdate]);
+    assert!(!rule.runs_on(Trigger::OnCreate));
+
+    let rule: rules::Rule = serde_yml::from_str(
+        "url: a.com\nwhen: [manual]\nstop: true\npriority: 5\naction: Delete\n",
+    )
+    .unwrap();
+    assert!(!rule.runs_on(Trigger::OnCreate));
+    assert!(!rule.runs_on(Trigger::AfterMetadata));
+    assert!(rule.runs_on(Trigger::Manual));
+    assert!(rule.stop);
+    assert_eq!(rule.priority, 5);
+
+    // defaults stay out of the saved file
+    let rule: rules::Rule = serde_yml::from_str("url: a.com\naction: Delete\n").unwrap();
+    let saved = serde_yml::to_string(&rule).unwrap();
+    assert!(!saved.contains("when") && !saved.contains("stop") && !saved.contains("priority"));
+}
+
+#[test]
+pub fn test_disabled_rule_never_runs() {
+    use rules::Trigger;
+
+    let rule: rules::Rule =
+        serde_yml::from_str("url: a.com\nenabled: false\naction: Delete\n").unwrap();
+    assert!(!rule.runs_on(Trigger::OnCreate));
+    assert!(!rule.runs_on(Trigger::Manual));
+    assert!(rules::in_order(&[rule], &[Trigger::Manual]).is_empty());
+}
+
+#[test]
+pub fn test_template_capture_groups() {
+    let rule: rules::Rule = serde_yml::from_str(
+        r#"
+url: 'r/github\.com/([^/]+)/([^/]+)/'
+action: !UpdateBookmark
+  title: $1/$2
+  tags: [gh/$1]
+"#,
+    )
+    .unwrap();
+    let record = rules::Record {
+        url: "https://github.com/rust-lang/regex/issues".into(),
+        ..Default::default()
+    };
+    assert!(rule.is_match(&record, &Default::default()));
+
+    let ctx = rule.template_context(&record);
+    assert_eq!(ctx.render("$1/$2"), "rust-lang/regex");
+    assert_eq!(ctx.render("${1}x"), "rust-langx");
+    assert_eq!(ctx.render("gh/$1"), "gh/rust-lang");
+    // the closing slash ends the regex, it isn't part of the match
+    assert_eq!(ctx.render("$0"), "github.com/rust-lang/regex");
+    // missing groups render empty, `$$` is a literal dollar
+    assert_eq!(ctx.render("[$3]"), "[]");
+    assert_eq!(ctx.render("$$5 {x} $"), "$5 {x} $");
+}
+
+#[test]
+pub fn test_template_named_groups_and_fields() {
+    let rule: rules::Rule = serde_yml::from_str(
+        r#"
+url: 'r/(?P<user>[^/.]+)\.github\.io/'
+title: 'r/^(.+) - (?P<site>.+)$/'
+action: !UpdateBookmark
+  title: ${user} on {{domain}}
+"#,
+    )
+    .unwrap();
+    let record = rules::Record {
+        url: "https://www.Alice.github.io/post".into(),
+        title: Some("Hello - Blog".into()),
+        description: None,
+        tags: Some(vec!["a".into(), "b".into()]),
+    };
+    assert!(rule.is_match(&record, &Default::default()));
+
+    let ctx = rule.template_context(&record);
+    assert_eq!(ctx.render("${user} on {{domain}}"), "Alice on alice.github.io");
+    // groups are numbered across the url, title and description conditions
+    assert_eq!(ctx.render("$1|$2|$3|${site}"), "Alice|Hello|Blog|Blog");
+    assert_eq!(ctx.render("{{ title }} [{{tags}}] {{url}}"), "Hello - Blog [a,b] https://www.Alice.github.io/post");
+    assert_eq!(ctx.render("{{description}}|{{nope}}"), "|{{nope}}");
+}
+
+#[test]
+pub fn test_template_fields_are_validated() {
+    let action = rules::Action::UpdateBookmark {
+        title: Some("{{domain}}".into()),
+        description: None,
+        tags: Some(vec!["{{host}}".into()]),
+    };
+    let err = action.validate().unwrap_err();
+    assert!(err.starts_with("unknown template field '{{host}}'"), "{err}");
+}
+
+fn suggest_bmark(id: u64, url: &str, title: &str, tags: &[&str]) -> crate::bookmarks::Bookmark {
+    crate::bookmarks::Bookmark {
+        id,
+        url: url.into(),
+        title: title.into(),
+        tags: tags.iter().map(|t| t.to_string()).collect(),
+        ..Default::default()
+    }
+}
+
+#[test]
+pub fn test_suggest_mines_domain_and_title_rules() {
+    let bookmarks = vec![
+        suggest_bmark(1, "https://github.com/rust-lang/rust", "Rust compiler", &["code", "rust"]),
+        suggest_bmark(2, "https://github.com/rust-lang/cargo", "Cargo", &["code", "rust"]),
+        suggest_bmark(3, "https://www.github.com/tokio-rs/tokio", "Tokio runtime", &["code"]),
+        suggest_bmark(4, "https://example.com/recipes", "Pasta recipes", &["food"]),
+        suggest_bmark(5, "https://example.org/more", "Bread recipes", &["food"]),
+    ];
+    let opts = rules::SuggestOpts {
+        min_support: 2,
+        min_confidence: 0.8,
+        limit: 10,
+    };
+
+    let suggestions = rules::suggest(&bookmarks, &[], &opts);
+    let summary: Vec<_> = suggestions
+        .iter()
+        .map(|s| {
+            let rules::Action::UpdateBookmark { tags: Some(tags), .. } = &s.rule.action else {
+                panic!("unexpected action {:?}", s.rule.action);
+            };
+            (s.rule.query.clone(), s.rule.url.clone(), s.rule.title.clone(), tags.join(","), s.support)
+        })
+        .collect();
+
+    // github.com/rust-lang -> rust holds as well, the domain doesn't predict rust
+    assert_eq!(
+        summary,
+        vec![
+            (Some("domain:github.com".into()), None, None, "code".into(), 3),
+            (None, Some(r"r/^[a-z]+://(www\.)?github\.com/rust\-lang([/?#]|$)/i".into()), None, "rust".into(), 2),
+            (None, None, Some(r"r/\brecipes\b/i".into()), "food".into(), 2),
+        ]
+    );
+    assert_eq!(suggestions[0].confidence, 1.0);
+    for suggestion in &suggestions {
+        suggestion.rule.compile().unwrap();
+        assert!(suggestion.rule.action.validate().is_ok());
+    }
+
+    let org = |url: &str| rules::Record {
+        url: url.into(),
+        ..Default::default()
+    };
+    assert!(suggestions[1].rule.is_match(&org("https://github.com/rust-lang"), &Default::default()));
+    assert!(suggestions[1].rule.is_match(&org("https://www.github.com/rust-lang/rust?tab=readme"), &Default::default()));
+    assert!(!suggestions[1].rule.is_match(&org("https://github.com/rust-lang-nursery/rust-cookbook"), &Default::default()));
+
+    let low = rules::SuggestOpts {
+        min_support: 4,
+        ..opts
+    };
+    assert!(rules::suggest(&bookmarks, &[], &low).is_empty());
+}
+
+#[test]
+pub fn test_suggest_skips_covered_associations() {
+    let bookmarks = vec![
+        suggest_bmark(1, "https://news.ycombinator.com/item?id=1", "One", &["hn"]),
+        suggest_bmark(2, "https://news.ycombinator.com/item?id=2", "Two", &["hn"]),
+        suggest_bmark(3, "https://news.ycombinator.com/item?id=3", "Three", &["hn"]),
+    ];
+    let opts = rules::SuggestOpts {
+        min_support: 2,
+        ..Default::default()
+    };
+
+    let suggestions = rules::suggest(&bookmarks, &[], &opts);
+    assert_eq!(suggestions.len(), 1);
+    assert_eq!(suggestions[0].rule.query.as_deref(), Some("domain:news.ycombinator.com"));
+
+    let mut existing: Vec<rules::Rule> =
+        serde_yml::from_str("- url: ycombinator.com\n  action: !UpdateBookmark\n    tags: [hn]\n").unwrap();
+    assert!(rules::suggest(&bookmarks, &existing, &opts).is_empty());
+
+    // a disabled rule doesn't count
+    existing[0].enabled = false;
+    assert_eq!(rules::suggest(&bookmarks, &existing, &opts).len(), 1);
 }
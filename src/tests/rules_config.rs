@@ -74,6 +74,12 @@ fn test_rules_crud() {
         tags: None,
         query: None,
         comment: Some("dev rule".to_string()),
+        when: vec![],
+        stop: false,
+        priority: 0,
+        id: 0,
+        enabled: true,
+        compiled: Default::default(),
         action: Action::UpdateBookmark {
             title: None,
             description: None,
@@ -136,3 +142,171 @@ rules:
     let config = Config::load_with(base_path);
     assert!(config.is_ok(), "Config should tolerate a rules key in config.yaml");
 }
+
+/// Actions load from YAML and bad regexes or empty tag lists fail validation
+#[test]
+fn test_rule_actions_validate() {
+    let dir = temp_dir();
+    let base_path = dir.path().to_str().unwrap();
+    let store = BackendLocal::new(base_path).unwrap();
+
+    let rules_content = r#"
+rules:
+- url: old.reddit.com
+  action: !RewriteUrl
+    pattern: //old\.reddit\.com
+    replacement: //reddit.com
+- url: spam.example
+  action: Delete
+- url: twitter.com
+  action: SkipMetadata
+"#;
+    store.write("rules.yaml", rules_content.as_bytes()).unwrap();
+    let rules_config = RulesConfig::load_with(base_path).unwrap();
+    assert!(matches!(rules_config.rules()[1].action, Action::Delete));
+
+    let invalid = RulesConfig::from_rules(vec![
+        Rule {
+            url: Some("example.com".to_string()),
+            title: None,
+            description: None,
+            tags: None,
+            query: None,
+            comment: None,
+            when: vec![],
+            stop: false,
+            priority: 0,
+            id: 0,
+            enabled: true,
+            compiled: Default::default(),
+            action: Action::RewriteUrl {
+                pattern: "(unclosed".to_string(),
+                replacement: String::new(),
+            },
+        },
+        Rule {
+            url: Some("example.com".to_string()),
+            title: None,
+            description: None,
+            tags: None,
+            query: None,
+            comment: None,
+            when: vec![],
+            stop: false,
+            priority: 0,
+            id: 0,
+            enabled: true,
+            compiled: Default::default(),
+            action: Action::RemoveTags { tags: vec![] },
+        },
+    ]);
+    let errors = invalid.validate(&Default::default()).unwrap_err();
+    assert_eq!(errors.len(), 2);
+    assert!(errors[0].starts_with("rule #1 has invalid action: RewriteUrl pattern"));
+    assert!(errors[1].starts_with("rule #2 has invalid action: RemoveTags"));
+}
+
+/// Ids: rules without one get the next free id on load, and it's persisted
+#[test]
+fn test_load_assigns_stable_ids() {
+    let dir = temp_dir();
+    let base_path = dir.path().to_str().unwrap();
+    let store = BackendLocal::new(base_path).unwrap();
+
+    let rules_content = r#"
+rules:
+- url: example.com
+  action: Delete
+- id: 5
+  url: github.com
+  action: Delete
+"#;
+    store.write("rules.yaml", rules_content.as_bytes()).unwrap();
+
+    let mut rules_config = RulesConfig::load_with(base_path).unwrap();
+    let ids: Vec<u64> = rules_config.rules().iter().map(|r| r.id).collect();
+    assert_eq!(ids, vec![6, 5]);
+
+    let reloaded = RulesConfig::load_with(base_path).unwrap();
+    assert_eq!(reloaded.rules()[0].id, 6);
+
+    // deleting a rule doesn't renumber the others
+    rules_config.rules_mut().remove(0);
+    rules_config.rules_mut().push(reloaded.rules()[0].clone());
+    rules_config.rules_mut()[1].id = 0;
+    assert!(rules_config.assign_ids());
+    assert_eq!(rules_config.position(5), Some(0));
+    assert_eq!(rules_config.rules()[1].id, 6);
+
+    rules_config.rules_mut()[1].id = 5;
+    let errors = rules_config.validate(&Default::default()).unwrap_err();
+    assert_eq!(errors, vec!["rule #2 reuses id 5".to_string()]);
+}
+
+/// Validation: malformed patterns are reported instead of panicking later
+#[test]
+fn test_invalid_pattern_fails_validation() {
+    let dir = temp_dir();
+    let base_path = dir.path().to_str().unwrap();
+    let store = BackendLocal::new(base_path).unwrap();
+
+    let rules_content = r#"
+rules:
+- url: example.com
+  title: r/(unclosed/i
+  action: Delete
+"#;
+    store.write("rules.yaml", rules_content.as_bytes()).unwrap();
+
+    let err = RulesConfig::load_with(base_path).unwrap_err().to_string();
+    assert!(err.contains("rule #1 has invalid title: invalid regex 'r/(unclosed/i'"), "{err}");
+
+    let rules_content = r#"
+rules:
+- url: r/github\.com
+  action: Delete
+- url: r/github\.com/foo
+  action: Delete
+- description: r/spam/g
+  action: Delete
+"#;
+    store.write("rules.yaml", rules_content.as_bytes()).unwrap();
+
+    let err = RulesConfig::load_with(base_path).unwrap_err().to_string();
+    assert!(err.contains("rule #1 has invalid url: invalid regex 'r/github\\.com': missing closing '/'"), "{err}");
+    assert!(err.contains("rule #2 has invalid url: invalid regex 'r/github\\.com/foo': unsupported flag 'f'"), "{err}");
+    assert!(err.contains("rule #3 has invalid description: invalid regex 'r/spam/g': unsupported flag 'g'"), "{err}");
+}
+
+/// Validation: fetch directives are only read from `after_metadata` rules
+#[test]
+fn test_fetch_directives_require_after_metadata() {
+    let dir = temp_dir();
+    let base_path = dir.path().to_str().unwrap();
+    let store = BackendLocal::new(base_path).unwrap();
+
+    let rules_content = r#"
+rules:
+- url: twitter.com
+  action: SkipMetadata
+- url: spa.example
+  when: [on_create, after_metadata]
+  action: ForceHeadless
+"#;
+    store.write("rules.yaml", rules_content.as_bytes()).unwrap();
+    assert!(RulesConfig::load_with(base_path).is_ok());
+
+    let rules_content = r#"
+rules:
+- url: twitter.com
+  when: [on_create]
+  action: SkipMetadata
+- url: spa.example
+  when: [manual]
+  action: ForceHeadless
+"#;
+    store.write("rules.yaml", rules_content.as_bytes()).unwrap();
+    let err = RulesConfig::load_with(base_path).unwrap_err().to_string();
+    assert!(err.contains("rule #1 has invalid when: SkipMetadata only takes effect on after_metadata"), "{err}");
+    assert!(err.contains("rule #2 has invalid when: ForceHeadless only takes effect on after_metadata"), "{err}");
+}
@@ -5,11 +5,13 @@ use crate::{
         task_runner::{self, QueueDump},
     },
     auth::{AuthConfig, AuthLayer},
-    bookmarks::{Bookmark, BookmarkCreate, BookmarkUpdate, SearchQuery},
+    bookmarks::{Bookmark, BookmarkCreate, BookmarkUpdate, SearchQuery, SortDirection, SortField},
     config::Config,
     eid::Eid,
     images,
     metadata::MetaOptions,
+    saved_searches::{self, SavedSearch, SavedSearchError, SavedSearchStore},
+    search_query::{Facets, QueryExplanation},
     storage::{self, StorageManager},
     workspaces::{WorkspaceError, WorkspaceStore},
 };
@@ -34,6 +36,7 @@ struct SharedState {
     app_service: Arc<RwLock<AppService>>,
     storage_mgr: Arc<dyn StorageManager>,
     workspace_store: Arc<RwLock<WorkspaceStore>>,
+    saved_search_store: Arc<RwLock<SavedSearchStore>>,
 }
 
 async fn start_app(app_service: AppService, base_path: &str) {
@@ -42,10 +45,16 @@ async fn start_app(app_service: AppService, base_path: &str) {
             .expect("failed to initialize uploads storage"),
     );
     let workspace_store = WorkspaceStore::load(base_path).expect("failed to load workspace store");
+    // shared with the backend, which expands `@name` references
+    let saved_search_store = app_service.saved_searches().unwrap_or_else(|| {
+        let store = SavedSearchStore::load(base_path).expect("failed to load saved searches");
+        Arc::new(RwLock::new(store))
+    });
     let shared_state = Arc::new(RwLock::new(SharedState {
         app_service: Arc::new(RwLock::new(app_service)),
         storage_mgr,
         workspace_store: Arc::new(RwLock::new(workspace_store)),
+        saved_search_store,
     }));
 
     let webui = Router::new()
@@ -113,10 +122,15 @@ async fn start_app(app_service: AppService, base_path: &str) {
         .route("/api/bookmarks/search_delete", post(search_delete))
         .route("/api/bookmarks/total", post(total))
         .route("/api/bookmarks/tags", post(tags))
+        .route("/api/bookmarks/domains", post(domains))
+        .route("/api/bookmarks/:id/metadata_report", get(metadata_reports))
+        .route("/api/query/explain", post(explain_query))
         .route("/api/config", get(get_config))
         .route("/api/config", post(update_config))
         .route("/api/rules", get(get_rules))
         .route("/api/rules", post(update_rules))
+        .route("/api/rules/apply", post(apply_rules))
+        .route("/api/rules/undo", post(undo_rules_apply))
         .route("/api/task_queue", get(task_queue))
         .route("/api/semantic/status", get(semantic_status))
         .route("/api/workspaces", get(list_workspaces))
@@ -124,6 +138,10 @@ async fn start_app(app_service: AppService, base_path: &str) {
         .route("/api/workspaces/reorder", post(reorder_workspaces))
         .route("/api/workspaces/:id", put(update_workspace))
         .route("/api/workspaces/:id", delete_method(delete_workspace))
+        .route("/api/searches", get(list_saved_searches))
+        .route("/api/searches", post(create_saved_search))
+        .route("/api/searches/:name", put(update_saved_search))
+        .route("/api/searches/:name", delete_method(delete_saved_search))
         .layer(auth_layer);
 
     // Health endpoint - no auth required (for container health checks)
@@ -217,6 +235,9 @@ pub enum AppError {
     #[error("{0}")]
     Workspace(#[from] WorkspaceError),
 
+    #[error("{0}")]
+    SavedSearch(#[from] SavedSearchError),
+
     #[error("{0:#}")]
     Other(#[from] anyhow::Error),
 }
@@ -264,6 +285,17 @@ impl IntoResponse for AppError {
                 };
                 (status, "WORKSPACE_ERROR", self.to_string())
             }
+            AppError::SavedSearch(ref e) => {
+                let status = match e {
+                    SavedSearchError::NotFound(_) => StatusCode::NOT_FOUND,
+                    SavedSearchError::InvalidName
+                    | SavedSearchError::DuplicateName(_)
+                    | SavedSearchError::InvalidQuery(_) => StatusCode::BAD_REQUEST,
+                    SavedSearchError::InUse(_) => StatusCode::CONFLICT,
+                    SavedSearchError::Storage(_) => StatusCode::INTERNAL_SERVER_ERROR,
+                };
+                (status, "SAVED_SEARCH_ERROR", self.to_string())
+            }
             AppError::Other(ref e) => {
                 let msg = format!("{:#}", e);
 
@@ -319,6 +351,77 @@ pub struct ListBookmarksRequest {
 
     #[serde(default)]
     pub limit: Option<usize>,
+
+    /// Retry with typo-tolerant matching when `query` finds nothing
+    #[serde(default)]
+    pub fuzzy_fallback: bool,
+
+    #[serde(default)]
+    pub sort: Option<SortField>,
+
+    #[serde(default)]
+    pub direction: Option<SortDirection>,
+
+    #[serde(default)]
+    pub offset: Option<usize>,
+
+    /// Return `{bookmarks, facets}` instead of a bare list
+    #[serde(default)]
+    pub facets: bool,
+}
+
+impl ListBookmarksRequest {
+    fn into_query(self) -> SearchQuery {
+        SearchQuery {
+            id: self.id,
+            title: self.title,
+            url: self.url,
+            description: self.description,
+            tags: self.tags.map(crate::parse_tags),
+            query: self.query,
+            semantic: self.semantic,
+            threshold: self.threshold,
+            exact: self.exact,
+            limit: self.limit,
+            fuzzy_fallback: self.fuzzy_fallback,
+            sort: self.sort,
+            direction: self.direction,
+            offset: self.offset,
+            facets: self.facets,
+            ..Default::default()
+        }
+    }
+}
+
+/// Map search failures to the matching client error.
+fn search_error(e: anyhow::Error) -> AppError {
+    // Use alternate format to get full anyhow cause chain
+    let full = format!("{:#}", e);
+    if full.contains("invalid search query") {
+        // Use the deepest cause that mentions the parse error
+        // (CHAIN[1] is AppError::Other with Debug backtrace, CHAIN[2] is the clean message)
+        let message = e
+            .chain()
+            .map(|c| c.to_string())
+            .filter(|s| s.starts_with("invalid search query"))
+            .last()
+            .unwrap_or_else(|| full.clone());
+        // Capitalize first letter for consistency
+        let message = format!("I{}", &message[1..]);
+        AppError::InvalidQuery { message }
+    } else if full.contains("Semantic search is disabled") {
+        AppError::SemanticDisabled {
+            message: "Semantic search is disabled in configuration".to_string(),
+        }
+    } else if full.contains("model") && full.contains("unavailable")
+        || full.contains("Failed to initialize")
+    {
+        AppError::ModelUnavailable {
+            message: "Semantic search model is unavailable".to_string(),
+        }
+    } else {
+        AppError::Other(e)
+    }
 }
 
 #[derive(Serialize)]
@@ -329,6 +432,12 @@ struct BookmarkResponse {
     fetching: bool,
 }
 
+#[derive(Serialize)]
+struct FacetedSearchResponse {
+    bookmarks: Vec<BookmarkResponse>,
+    facets: Facets,
+}
+
 async fn search(
     State(state): State<Arc<RwLock<SharedState>>>,
     headers: HeaderMap,
@@ -373,49 +482,12 @@ async fn search(
         }
     }
 
-    let query = SearchQuery {
-        id: payload.id,
-        title: payload.title,
-        url: payload.url,
-        description: payload.description,
-        tags: payload.tags.map(crate::parse_tags),
-        query: payload.query,
-        semantic: payload.semantic,
-        threshold: payload.threshold,
-        exact: payload.exact,
-        limit: payload.limit,
-    };
-    let bookmarks = app_service.search_bookmarks(query, false).map_err(|e| {
-        // Use alternate format to get full anyhow cause chain
-        let full = format!("{:#}", e);
-        if full.contains("invalid search query") {
-            // Use the deepest cause that mentions the parse error
-            // (CHAIN[1] is AppError::Other with Debug backtrace, CHAIN[2] is the clean message)
-            let message = e
-                .chain()
-                .map(|c| c.to_string())
-                .filter(|s| s.starts_with("invalid search query"))
-                .last()
-                .unwrap_or_else(|| full.clone());
-            // Capitalize first letter for consistency
-            let message = format!("I{}", &message[1..]);
-            AppError::InvalidQuery { message }
-        } else if full.contains("Semantic search is disabled") {
-            AppError::SemanticDisabled {
-                message: "Semantic search is disabled in configuration".to_string(),
-            }
-        } else if full.contains("model") && full.contains("unavailable")
-            || full.contains("Failed to initialize")
-        {
-            AppError::ModelUnavailable {
-                message: "Semantic search model is unavailable".to_string(),
-            }
-        } else {
-            AppError::Other(e)
-        }
-    })?;
+    let page = app_service
+        .search_page(payload.into_query())
+        .map_err(search_error)?;
 
-    let enriched: Vec<BookmarkResponse> = bookmarks
+    let enriched: Vec<BookmarkResponse> = page
+        .bookmarks
         .into_iter()
         .map(|b| {
             let fetching = fetching_ids.contains(&b.id);
@@ -426,11 +498,70 @@ async fn search(
         })
         .collect();
 
-    let mut response = axum::Json(enriched).into_response();
+    let mut response = match page.facets {
+        Some(facets) => axum::Json(FacetedSearchResponse {
+            bookmarks: enriched,
+            facets,
+        })
+        .into_response(),
+        None => axum::Json(enriched).into_response(),
+    };
     response.headers_mut().insert("etag", etag.parse().unwrap());
+    response
+        .headers_mut()
+        .insert(TOTAL_COUNT_HEADER, page.total.into());
     Ok(response)
 }
 
+#[derive(Serialize)]
+struct DomainFacet {
+    domain: String,
+    count: usize,
+}
+
+/// Domains of the bookmarks matching the request, with counts.
+async fn domains(
+    State(state): State<Arc<RwLock<SharedState>>>,
+    Json(payload): Json<ListBookmarksRequest>,
+) -> Result<axum::Json<Vec<DomainFacet>>, AppError> {
+    let state = state.read().unwrap();
+    let app_service = state.app_service.read().unwrap();
+
+    let bookmarks = app_service
+        .search_bookmarks(payload.into_query().without_paging(), false)
+        .map_err(search_error)?;
+
+    let facets = crate::search_query::domain_counts(&bookmarks)
+        .into_iter()
+        .map(|(domain, count)| DomainFacet { domain, count })
+        .collect();
+
+    Ok(axum::Json(facets))
+}
+
+#[derive(Deserialize)]
+struct ExplainRequest {
+    query: String,
+    /// Bookmark to match the query against
+    #[serde(default)]
+    id: Option<u64>,
+}
+
+/// Parsed query AST and, for a bookmark, per-term matches and highlight spans.
+async fn explain_query(
+    State(state): State<Arc<RwLock<SharedState>>>,
+    Json(payload): Json<ExplainRequest>,
+) -> Result<axum::Json<QueryExplanation>, AppError> {
+    let state = state.read().unwrap();
+    let app_service = state.app_service.read().unwrap();
+
+    let explanation = app_service
+        .explain_query(&payload.query, payload.id)
+        .map_err(search_error)?;
+
+    Ok(axum::Json(explanation))
+}
+
 #[derive(Deserialize)]
 pub struct BookmarkCreateRequest {
     pub title: Option<String>,
@@ -792,6 +923,9 @@ pub struct SemanticStatusResponse {
     pub total_bookmarks: usize,
 }
 
+/// Response header carrying the number of matches before `offset`/`limit`.
+pub const TOTAL_COUNT_HEADER: &str = "x-total-count";
+
 #[derive(Serialize, Deserialize, Clone)]
 pub struct TotalResponse {
     pub total: usize,
@@ -847,6 +981,18 @@ async fn update_config(
     Ok(axum::Json(payload))
 }
 
+/// The last `scrape.report_history` metadata reports of a bookmark, newest first.
+async fn metadata_reports(
+    State(state): State<Arc<RwLock<SharedState>>>,
+    Path(id): Path<u64>,
+) -> Result<axum::Json<Vec<crate::metadata::reports::StoredReport>>, AppError> {
+    let state = state.read().unwrap();
+    let app_service = state.app_service.read().unwrap();
+
+    let reports = app_service.metadata_reports(id)?;
+    Ok(axum::Json(reports))
+}
+
 async fn get_rules(
     State(state): State<Arc<RwLock<SharedState>>>,
 ) -> Result<axum::Json<Vec<crate::rules::Rule>>, AppError> {
@@ -865,18 +1011,52 @@ async fn update_rules(
     let state = state.read().unwrap();
     let app_service = state.app_service.read().unwrap();
 
+    // validating compiles each rule's patterns, so keep the validated copies
+    let validated = crate::config::RulesConfig::from_rules(payload);
+    let saved = state.saved_search_store.read().unwrap().map();
+    if let Err(errors) = validated.validate(&saved) {
+        return Err(AppError::Other(anyhow::anyhow!(
+            "rules validation failed:\n  - {}",
+            errors.join("\n  - ")
+        )));
+    }
+
     let rules_config = app_service.get_rules().context("Failed to get rules")?;
-    {
+    let rules = {
         let mut guard = rules_config.write().unwrap();
-        *guard.rules_mut() = payload.clone();
+        *guard.rules_mut() = validated.rules;
+        guard.assign_ids();
         guard.save().map_err(|e| AppError::Other(e))?;
-    }
+        guard.rules().to_vec()
+    };
 
-    Ok(axum::Json(payload))
+    Ok(axum::Json(rules))
+}
+
+async fn apply_rules(
+    State(state): State<Arc<RwLock<SharedState>>>,
+    Json(payload): Json<RuleApplyOpts>,
+) -> Result<axum::Json<RuleApplyReport>, AppError> {
+    let state = state.read().unwrap();
+    let app_service = state.app_service.read().unwrap();
+
+    let report = app_service.apply_rules(payload)?;
+    Ok(axum::Json(report))
+}
+
+async fn undo_rules_apply(
+    State(state): State<Arc<RwLock<SharedState>>>,
+) -> Result<axum::Json<usize>, AppError> {
+    let state = state.read().unwrap();
+    let app_service = state.app_service.read().unwrap();
+
+    let count = app_service.undo_rules_apply()?;
+    Ok(axum::Json(count))
 }
 
 async fn task_queue() -> Result<axum::Json<QueueDump>, AppError> {
-    let queue_dump = task_runner::read_queue_dump();
+    let mut queue_dump = task_runner::read_queue_dump();
+    queue_dump.hosts = crate::scrape::limiter::status();
     Ok(axum::Json(queue_dump))
 }
 
@@ -935,7 +1115,8 @@ async fn create_workspace(
 ) -> Result<(StatusCode, axum::Json<crate::workspaces::Workspace>), AppError> {
     let state = state.read().unwrap();
     let mut store = state.workspace_store.write().unwrap();
-    let workspace = store.create(payload.name, payload.filters, payload.view_prefs)?;
+    let saved = state.saved_search_store.read().unwrap().map();
+    let workspace = store.create(payload.name, payload.filters, payload.view_prefs, &saved)?;
     Ok((StatusCode::CREATED, Json(workspace)))
 }
 
@@ -956,7 +1137,8 @@ async fn update_workspace(
 ) -> Result<axum::Json<crate::workspaces::Workspace>, AppError> {
     let state = state.read().unwrap();
     let mut store = state.workspace_store.write().unwrap();
-    let workspace = store.update(&id, payload.name, payload.filters, payload.view_prefs)?;
+    let saved = state.saved_search_store.read().unwrap().map();
+    let workspace = store.update(&id, payload.name, payload.filters, payload.view_prefs, &saved)?;
     Ok(Json(workspace))
 }
 
@@ -985,6 +1167,74 @@ async fn reorder_workspaces(
     Ok(StatusCode::NO_CONTENT)
 }
 
+async fn list_saved_searches(
+    State(state): State<Arc<RwLock<SharedState>>>,
+) -> Result<axum::Json<Vec<SavedSearch>>, AppError> {
+    let state = state.read().unwrap();
+    let store = state.saved_search_store.read().unwrap();
+    Ok(Json(store.list().to_vec()))
+}
+
+#[derive(Deserialize)]
+struct SavedSearchCreateRequest {
+    name: String,
+    query: String,
+    #[serde(default)]
+    description: Option<String>,
+}
+
+async fn create_saved_search(
+    State(state): State<Arc<RwLock<SharedState>>>,
+    Json(payload): Json<SavedSearchCreateRequest>,
+) -> Result<(StatusCode, axum::Json<SavedSearch>), AppError> {
+    let state = state.read().unwrap();
+    let mut store = state.saved_search_store.write().unwrap();
+    let search = store.create(payload.name, payload.query, payload.description)?;
+    Ok((StatusCode::CREATED, Json(search)))
+}
+
+#[derive(Deserialize)]
+struct SavedSearchUpdateRequest {
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    query: Option<String>,
+    #[serde(default)]
+    description: Option<String>,
+}
+
+async fn update_saved_search(
+    State(state): State<Arc<RwLock<SharedState>>>,
+    Path(name): Path<String>,
+    Json(payload): Json<SavedSearchUpdateRequest>,
+) -> Result<axum::Json<SavedSearch>, AppError> {
+    let state = state.read().unwrap();
+    let uses = saved_search_uses(&state)?;
+    let mut store = state.saved_search_store.write().unwrap();
+    let search = store.update(&name, payload.name, payload.query, payload.description, &uses)?;
+    Ok(Json(search))
+}
+
+async fn delete_saved_search(
+    State(state): State<Arc<RwLock<SharedState>>>,
+    Path(name): Path<String>,
+) -> Result<StatusCode, AppError> {
+    let state = state.read().unwrap();
+    let uses = saved_search_uses(&state)?;
+    let mut store = state.saved_search_store.write().unwrap();
+    store.delete(&name, &uses)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// The rule and workspace queries a saved search change mustn't break.
+fn saved_search_uses(state: &SharedState) -> Result<Vec<saved_searches::QueryUse>, AppError> {
+    let app_service = state.app_service.read().unwrap();
+    let rules_config = app_service.get_rules().context("Failed to get rules")?;
+    let rules_config = rules_config.read().unwrap();
+    let workspaces = state.workspace_store.read().unwrap();
+    Ok(saved_searches::query_uses(&rules_config, &workspaces))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1280,10 +1530,15 @@ mod tests {
                 app_service: Arc::new(RwLock::new(app_service)),
                 storage_mgr: Arc::new(MockStorageManager),
                 workspace_store: Arc::new(RwLock::new(workspace_store)),
+                saved_search_store: Arc::new(RwLock::new(
+                    SavedSearchStore::load(test_dir.to_str().unwrap()).unwrap(),
+                )),
             }));
 
             Router::new()
                 .route("/api/bookmarks/search", post(search))
+                .route("/api/bookmarks/domains", post(domains))
+                .route("/api/query/explain", post(explain_query))
                 .route("/api/semantic/status", get(semantic_status))
                 .with_state(shared_state)
         }
@@ -1568,6 +1823,157 @@ mod tests {
             assert_eq!(json.len(), 2);
         }
 
+        #[tokio::test]
+        async fn test_search_sort_offset_and_total_header() {
+            let bookmarks = vec![
+                create_bookmark(1, "Charlie", ""),
+                create_bookmark(2, "alpha", ""),
+                create_bookmark(3, "Bravo", ""),
+            ];
+            let backend = Box::new(MockBackend::new(bookmarks, true));
+            let service = AppService::new(backend);
+            let app = test_api_router(service);
+
+            let req = axum::http::Request::builder()
+                .method("POST")
+                .uri("/api/bookmarks/search")
+                .header("content-type", "application/json")
+                .body(Body::from(
+                    r#"{"sort": "title", "direction": "desc", "offset": 1, "limit": 1}"#,
+                ))
+                .unwrap();
+
+            let resp = app.oneshot(req).await.unwrap();
+            assert_eq!(resp.status(), StatusCode::OK);
+            assert_eq!(resp.headers()[TOTAL_COUNT_HEADER], "3");
+
+            let body = resp.into_body().collect().await.unwrap().to_bytes();
+            let json: Vec<Bookmark> = serde_json::from_slice(&body).unwrap();
+            assert_eq!(json.len(), 1);
+            assert_eq!(json[0].title, "Bravo");
+        }
+
+        #[tokio::test]
+        async fn test_domains_counts_result_set() {
+            let mut other = create_bookmark(3, "Docs", "");
+            other.url = "https://docs.rs/axum".to_string();
+            let bookmarks = vec![
+                create_bookmark(1, "One", ""),
+                create_bookmark(2, "Two", ""),
+                other,
+            ];
+            let backend = Box::new(MockBackend::new(bookmarks, true));
+            let service = AppService::new(backend);
+            let app = test_api_router(service);
+
+            let req = axum::http::Request::builder()
+                .method("POST")
+                .uri("/api/bookmarks/domains")
+                .header("content-type", "application/json")
+                .body(Body::from(r#"{}"#))
+                .unwrap();
+
+            let resp = app.oneshot(req).await.unwrap();
+            assert_eq!(resp.status(), StatusCode::OK);
+
+            let body = resp.into_body().collect().await.unwrap().to_bytes();
+            let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+            assert_eq!(
+                json,
+                serde_json::json!([
+                    { "domain": "example.com", "count": 2 },
+                    { "domain": "docs.rs", "count": 1 },
+                ])
+            );
+        }
+
+        #[tokio::test]
+        async fn test_search_with_facets_counts_all_matches() {
+            let mut tagged = create_bookmark(1, "One", "");
+            tagged.tags = vec!["dev/rust".to_string()];
+            let bookmarks = vec![tagged, create_bookmark(2, "Two", ""), create_bookmark(3, "Three", "")];
+            let backend = Box::new(MockBackend::new(bookmarks, true));
+            let service = AppService::new(backend);
+            let app = test_api_router(service);
+
+            let req = axum::http::Request::builder()
+                .method("POST")
+                .uri("/api/bookmarks/search")
+                .header("content-type", "application/json")
+                .body(Body::from(r#"{"sort": "id", "limit": 1, "facets": true}"#))
+                .unwrap();
+
+            let resp = app.oneshot(req).await.unwrap();
+            assert_eq!(resp.status(), StatusCode::OK);
+            assert_eq!(resp.headers()[TOTAL_COUNT_HEADER], "3");
+
+            let body = resp.into_body().collect().await.unwrap().to_bytes();
+            let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+            assert_eq!(json["bookmarks"].as_array().unwrap().len(), 1);
+            // Facets cover the whole match set, not just the page
+            assert_eq!(
+                json["facets"]["tags"],
+                serde_json::json!([
+                    { "value": "dev", "count": 1 },
+                    { "value": "dev/rust", "count": 1 },
+                ])
+            );
+            assert_eq!(
+                json["facets"]["domains"],
+                serde_json::json!([{ "value": "example.com", "count": 3 }])
+            );
+            assert_eq!(json["facets"]["flags"][0], serde_json::json!({ "value": "title", "count": 3 }));
+        }
+
+        #[tokio::test]
+        async fn test_explain_query_reports_terms_and_highlights() {
+            let backend = Box::new(MockBackend::new(
+                vec![create_bookmark(7, "Rust async book", "")],
+                true,
+            ));
+            let service = AppService::new(backend);
+            let app = test_api_router(service);
+
+            let req = axum::http::Request::builder()
+                .method("POST")
+                .uri("/api/query/explain")
+                .header("content-type", "application/json")
+                .body(Body::from(r#"{"query": ".async and not python", "id": 7}"#))
+                .unwrap();
+
+            let resp = app.oneshot(req).await.unwrap();
+            assert_eq!(resp.status(), StatusCode::OK);
+
+            let body = resp.into_body().collect().await.unwrap().to_bytes();
+            let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+            assert_eq!(json["normalized"], ".async and not python");
+            assert_eq!(json["ast"]["type"], "and");
+            assert_eq!(json["bookmark"]["matched"], true);
+            assert_eq!(json["bookmark"]["terms"][0]["fields"], serde_json::json!(["title"]));
+            assert_eq!(json["bookmark"]["terms"][1]["negated"], true);
+            assert_eq!(
+                json["bookmark"]["highlights"],
+                serde_json::json!([{ "field": "title", "start": 5, "end": 10 }])
+            );
+        }
+
+        #[tokio::test]
+        async fn test_explain_query_invalid_returns_400() {
+            let backend = Box::new(MockBackend::new(vec![], true));
+            let service = AppService::new(backend);
+            let app = test_api_router(service);
+
+            let req = axum::http::Request::builder()
+                .method("POST")
+                .uri("/api/query/explain")
+                .header("content-type", "application/json")
+                .body(Body::from(r#"{"query": "@no-such-search"}"#))
+                .unwrap();
+
+            let resp = app.oneshot(req).await.unwrap();
+            assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+        }
+
         // -----------------------------------------------------------------
         // Workspace HTTP Integration Tests
         // -----------------------------------------------------------------
@@ -1582,6 +1988,9 @@ mod tests {
                 app_service: Arc::new(RwLock::new(service)),
                 storage_mgr: Arc::new(MockStorageManager),
                 workspace_store: Arc::new(RwLock::new(workspace_store)),
+                saved_search_store: Arc::new(RwLock::new(
+                    SavedSearchStore::load(dir.to_str().unwrap()).unwrap(),
+                )),
             }));
 
             Router::new()
@@ -1679,6 +2088,9 @@ mod tests {
                 app_service: Arc::new(RwLock::new(service)),
                 storage_mgr: Arc::new(MockStorageManager),
                 workspace_store: Arc::new(RwLock::new(workspace_store)),
+                saved_search_store: Arc::new(RwLock::new(
+                    SavedSearchStore::load(dir.to_str().unwrap()).unwrap(),
+                )),
             }));
 
             let app = Router::new()
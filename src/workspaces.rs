@@ -1,4 +1,4 @@
-use crate::{eid::Eid, search_query, storage, storage::StorageManager};
+use crate::{eid::Eid, search_query, search_query::SavedSearches, storage, storage::StorageManager};
 use serde::{Deserialize, Serialize};
 
 const WORKSPACES_FILE: &str = "workspaces.yaml";
@@ -97,10 +97,11 @@ impl WorkspaceStore {
         name: String,
         filters: Option<WorkspaceFilters>,
         view_prefs: Option<ViewPrefs>,
+        saved: &SavedSearches,
     ) -> Result<Workspace, WorkspaceError> {
         let name = validate_name(&name)?;
         let filters = filters.unwrap_or_default();
-        validate_query(&filters)?;
+        validate_query(&filters, saved)?;
         self.check_duplicate_name(&name, None)?;
 
         let workspace = Workspace {
@@ -121,6 +122,7 @@ impl WorkspaceStore {
         name: Option<String>,
         filters: Option<WorkspaceFilters>,
         view_prefs: Option<ViewPrefs>,
+        saved: &SavedSearches,
     ) -> Result<Workspace, WorkspaceError> {
         let idx = self
             .workspaces
@@ -135,7 +137,7 @@ impl WorkspaceStore {
         }
 
         if let Some(filters) = filters {
-            validate_query(&filters)?;
+            validate_query(&filters, saved)?;
             self.workspaces[idx].filters = filters;
         }
 
@@ -208,10 +210,11 @@ fn validate_name(name: &str) -> Result<String, WorkspaceError> {
     Ok(trimmed.to_string())
 }
 
-fn validate_query(filters: &WorkspaceFilters) -> Result<(), WorkspaceError> {
+/// `saved` resolves `@name` references in the query.
+fn validate_query(filters: &WorkspaceFilters, saved: &SavedSearches) -> Result<(), WorkspaceError> {
     if let Some(ref kw) = filters.query {
         if !kw.trim().is_empty() {
-            search_query::parse_tolerant(kw)
+            search_query::parse_tolerant_with(kw, saved)
                 .map_err(|e| WorkspaceError::InvalidQuery(e.to_string()))?;
         }
     }
@@ -262,7 +265,7 @@ mod tests {
     fn create_and_reload_roundtrip() {
         let dir = tmp_dir();
         let mut store = WorkspaceStore::load(&dir).unwrap();
-        let ws = store.create("Test".into(), None, None).unwrap();
+        let ws = store.create("Test".into(), None, None, &Default::default()).unwrap();
         assert!(!ws.id.is_empty());
         assert_eq!(ws.name, "Test");
 
@@ -276,9 +279,9 @@ mod tests {
     fn update_existing() {
         let dir = tmp_dir();
         let mut store = WorkspaceStore::load(&dir).unwrap();
-        let ws = store.create("Original".into(), None, None).unwrap();
+        let ws = store.create("Original".into(), None, None, &Default::default()).unwrap();
         let updated = store
-            .update(&ws.id, Some("Renamed".into()), None, None)
+            .update(&ws.id, Some("Renamed".into()), None, None, &Default::default())
             .unwrap();
         assert_eq!(updated.name, "Renamed");
     }
@@ -287,7 +290,7 @@ mod tests {
     fn update_nonexistent_returns_not_found() {
         let dir = tmp_dir();
         let mut store = WorkspaceStore::load(&dir).unwrap();
-        let err = store.update("nope", Some("X".into()), None, None).unwrap_err();
+        let err = store.update("nope", Some("X".into()), None, None, &Default::default()).unwrap_err();
         assert!(matches!(err, WorkspaceError::NotFound(_)));
     }
 
@@ -295,7 +298,7 @@ mod tests {
     fn delete_existing() {
         let dir = tmp_dir();
         let mut store = WorkspaceStore::load(&dir).unwrap();
-        let ws = store.create("ToDelete".into(), None, None).unwrap();
+        let ws = store.create("ToDelete".into(), None, None, &Default::default()).unwrap();
         store.delete(&ws.id).unwrap();
         assert!(store.list().is_empty());
     }
@@ -320,7 +323,7 @@ mod tests {
                 let s = store.clone();
                 std::thread::spawn(move || {
                     let mut guard = s.write().unwrap();
-                    guard.create(format!("ws-{i}"), None, None).unwrap();
+                    guard.create(format!("ws-{i}"), None, None, &Default::default()).unwrap();
                 })
             })
             .collect();
@@ -339,7 +342,7 @@ mod tests {
     fn empty_name_rejected() {
         let dir = tmp_dir();
         let mut store = WorkspaceStore::load(&dir).unwrap();
-        let err = store.create("".into(), None, None).unwrap_err();
+        let err = store.create("".into(), None, None, &Default::default()).unwrap_err();
         assert!(matches!(err, WorkspaceError::InvalidName));
     }
 
@@ -347,7 +350,7 @@ mod tests {
     fn whitespace_only_name_rejected() {
         let dir = tmp_dir();
         let mut store = WorkspaceStore::load(&dir).unwrap();
-        let err = store.create("   ".into(), None, None).unwrap_err();
+        let err = store.create("   ".into(), None, None, &Default::default()).unwrap_err();
         assert!(matches!(err, WorkspaceError::InvalidName));
     }
 
@@ -356,7 +359,7 @@ mod tests {
         let dir = tmp_dir();
         let mut store = WorkspaceStore::load(&dir).unwrap();
         let name = "a".repeat(101);
-        let err = store.create(name, None, None).unwrap_err();
+        let err = store.create(name, None, None, &Default::default()).unwrap_err();
         assert!(matches!(err, WorkspaceError::InvalidName));
     }
 
@@ -369,15 +372,15 @@ mod tests {
             query: Some("(unclosed".into()),
             ..Default::default()
         };
-        store.create("Valid".into(), Some(filters), None).unwrap();
+        store.create("Valid".into(), Some(filters), None, &Default::default()).unwrap();
     }
 
     #[test]
     fn duplicate_name_rejected_case_insensitive() {
         let dir = tmp_dir();
         let mut store = WorkspaceStore::load(&dir).unwrap();
-        store.create("Dev".into(), None, None).unwrap();
-        let err = store.create("dev".into(), None, None).unwrap_err();
+        store.create("Dev".into(), None, None, &Default::default()).unwrap();
+        let err = store.create("dev".into(), None, None, &Default::default()).unwrap_err();
         assert!(matches!(err, WorkspaceError::DuplicateName(_)));
     }
 
@@ -395,7 +398,7 @@ mod tests {
             columns: Some(3),
         };
         let ws = store
-            .create("Rust".into(), Some(filters), Some(view_prefs))
+            .create("Rust".into(), Some(filters), Some(view_prefs), &Default::default())
             .unwrap();
         assert_eq!(ws.name, "Rust");
         assert_eq!(ws.filters.tag_whitelist, vec!["rust"]);
@@ -406,9 +409,9 @@ mod tests {
     fn update_same_name_on_self_allowed() {
         let dir = tmp_dir();
         let mut store = WorkspaceStore::load(&dir).unwrap();
-        let ws = store.create("Keep".into(), None, None).unwrap();
+        let ws = store.create("Keep".into(), None, None, &Default::default()).unwrap();
         // updating the same workspace with the same name should work
-        let updated = store.update(&ws.id, Some("Keep".into()), None, None).unwrap();
+        let updated = store.update(&ws.id, Some("Keep".into()), None, None, &Default::default()).unwrap();
         assert_eq!(updated.name, "Keep");
     }
 
@@ -418,9 +421,9 @@ mod tests {
     fn reorder_valid_ids() {
         let dir = tmp_dir();
         let mut store = WorkspaceStore::load(&dir).unwrap();
-        let ws1 = store.create("First".into(), None, None).unwrap();
-        let ws2 = store.create("Second".into(), None, None).unwrap();
-        let ws3 = store.create("Third".into(), None, None).unwrap();
+        let ws1 = store.create("First".into(), None, None, &Default::default()).unwrap();
+        let ws2 = store.create("Second".into(), None, None, &Default::default()).unwrap();
+        let ws3 = store.create("Third".into(), None, None, &Default::default()).unwrap();
 
         // Verify initial order
         assert_eq!(store.list()[0].id, ws1.id);
@@ -447,8 +450,8 @@ mod tests {
     fn reorder_missing_id_fails() {
         let dir = tmp_dir();
         let mut store = WorkspaceStore::load(&dir).unwrap();
-        let ws1 = store.create("First".into(), None, None).unwrap();
-        store.create("Second".into(), None, None).unwrap();
+        let ws1 = store.create("First".into(), None, None, &Default::default()).unwrap();
+        store.create("Second".into(), None, None, &Default::default()).unwrap();
 
         // Try to reorder with a non-existent ID
         let err = store
@@ -461,8 +464,8 @@ mod tests {
     fn reorder_duplicate_id_fails() {
         let dir = tmp_dir();
         let mut store = WorkspaceStore::load(&dir).unwrap();
-        let ws1 = store.create("First".into(), None, None).unwrap();
-        store.create("Second".into(), None, None).unwrap();
+        let ws1 = store.create("First".into(), None, None, &Default::default()).unwrap();
+        store.create("Second".into(), None, None, &Default::default()).unwrap();
 
         // Try to reorder with duplicate ID
         let err = store
@@ -486,7 +489,7 @@ mod tests {
         let dir = tmp_dir();
         let mut store = WorkspaceStore::load(&dir).unwrap();
         let name = "a".repeat(100);
-        let ws = store.create(name.clone(), None, None).unwrap();
+        let ws = store.create(name.clone(), None, None, &Default::default()).unwrap();
         assert_eq!(ws.name, name);
     }
 
@@ -494,10 +497,10 @@ mod tests {
     fn test_update_to_duplicate_name_of_another() {
         let dir = tmp_dir();
         let mut store = WorkspaceStore::load(&dir).unwrap();
-        store.create("Alpha".into(), None, None).unwrap();
-        let beta = store.create("Beta".into(), None, None).unwrap();
+        store.create("Alpha".into(), None, None, &Default::default()).unwrap();
+        let beta = store.create("Beta".into(), None, None, &Default::default()).unwrap();
         let err = store
-            .update(&beta.id, Some("Alpha".into()), None, None)
+            .update(&beta.id, Some("Alpha".into()), None, None, &Default::default())
             .unwrap_err();
         assert!(matches!(err, WorkspaceError::DuplicateName(_)));
     }
@@ -510,7 +513,7 @@ mod tests {
             query: Some("".into()),
             ..Default::default()
         };
-        let ws = store.create("EmptyKw".into(), Some(filters), None).unwrap();
+        let ws = store.create("EmptyKw".into(), Some(filters), None, &Default::default()).unwrap();
         assert_eq!(ws.name, "EmptyKw");
     }
 
@@ -518,9 +521,9 @@ mod tests {
     fn test_reorder_with_subset_of_ids_fails() {
         let dir = tmp_dir();
         let mut store = WorkspaceStore::load(&dir).unwrap();
-        let ws1 = store.create("One".into(), None, None).unwrap();
-        let ws2 = store.create("Two".into(), None, None).unwrap();
-        store.create("Three".into(), None, None).unwrap();
+        let ws1 = store.create("One".into(), None, None, &Default::default()).unwrap();
+        let ws2 = store.create("Two".into(), None, None, &Default::default()).unwrap();
+        store.create("Three".into(), None, None, &Default::default()).unwrap();
         let err = store
             .reorder(&[ws1.id.clone(), ws2.id.clone()])
             .unwrap_err();
@@ -536,7 +539,7 @@ mod tests {
             ..Default::default()
         };
         let ws = store
-            .create("WhitespaceKw".into(), Some(filters), None)
+            .create("WhitespaceKw".into(), Some(filters), None, &Default::default())
             .unwrap();
         assert_eq!(ws.name, "WhitespaceKw");
     }